@@ -0,0 +1,29 @@
+//! Fuzz the parser with arbitrary source text.
+//!
+//! Run with `cargo +nightly fuzz run parse` from the repo root.
+//!
+//! Parsing must never panic (or overflow the stack). Any source which parses must produce an AST
+//! with correct parent links, which can be printed. The printed output must parse again, to an AST
+//! with the same structure as the original.
+
+#![no_main]
+
+use ast_cell_test::{compare::stmt_eq, parents::validate_tree, parser::parse_str, print::Printer};
+use libfuzzer_sys::fuzz_target;
+use oxc_allocator::Allocator;
+
+fuzz_target!(|source: &str| {
+    let alloc = Allocator::default();
+    let Ok(program) = parse_str(source, &alloc) else {
+        return;
+    };
+    validate_tree(program).unwrap();
+
+    let printed = Printer::print(program);
+    let reparsed = parse_str(&printed, &alloc)
+        .unwrap_or_else(|err| panic!("Printed output `{printed}` failed to parse: {err}"));
+    assert_eq!(program.body.len(), reparsed.body.len(), "{printed}");
+    for (original, reparsed) in program.body.iter().zip(reparsed.body.iter()) {
+        assert!(stmt_eq(original, reparsed), "{printed}");
+    }
+});
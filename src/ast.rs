@@ -18,62 +18,391 @@
 //! All types must be `#[repr(C)]` to ensure predictable type layouts.
 //! All enums must be `#[repr(C, u8)]` with explicit discriminants to ensure discriminants
 //! match between the "standard" and "traversable" types.
+//! `assert_size_align_match!` checks sizes, alignments, and field offsets of each pair of types
+//! at compile time.
 
 // TODO: Create the "Traversable" types with a macro to ensure they cannot be out of sync,
 // and apply `#[repr(C)]` (for structs) / `#[repr(C, u8)]` (for enums) programmatically,
 // so can't get forgotten.
 
+use alloc::string::{String, ToString};
+use core::{fmt, str::FromStr};
+
 use oxc_allocator::{Box, Vec};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
-use crate::cell::{shared_box, shared_vec};
+use crate::cell::{shared_box, shared_vec, GCell, SharedVec, Token};
 
-/// Macro to assert equivalence in size and alignment between standard and traversable types
+/// Macro to assert equivalence in size and alignment between standard and traversable types.
+/// For structs, all fields must be listed, and each is asserted to be at the same offset in both,
+/// so reordering fields in one type but not the other fails to compile.
 macro_rules! assert_size_align_match {
-    ($standard:ident, $traversable:ident) => {
+    ($standard:ident, $traversable:ident $(, $field:ident)* $(,)?) => {
         const _: () = {
-            use std::mem::{align_of, size_of};
+            use core::mem::{align_of, size_of};
             assert!(size_of::<$standard>() == size_of::<$traversable>());
             assert!(align_of::<$standard>() == align_of::<$traversable>());
             assert!(size_of::<Box<$standard>>() == size_of::<&crate::cell::GCell<$traversable>>());
             assert!(
                 align_of::<Box<$standard>>() == align_of::<&crate::cell::GCell<$traversable>>()
             );
+            $(assert!(
+                core::mem::offset_of!($standard, $field)
+                    == core::mem::offset_of!($traversable, $field)
+            );)*
         };
     };
 }
 
+/// Location of a node in source text, as byte offsets.
+/// Shared by both versions of the AST.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize))]
+#[repr(C)]
+pub struct Span {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl Span {
+    pub const fn new(start: u32, end: u32) -> Self {
+        Self { start, end }
+    }
+}
+
+/// Comment in source text.
+/// Shared by both versions of the AST.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(C)]
+pub struct Comment<'a> {
+    pub span: Span,
+    pub kind: CommentKind,
+    /// Text of the comment, excluding `//`, or `/*` and `*/`
+    pub text: &'a str,
+    /// Start offset of the statement this is a leading comment of.
+    /// `None` if the comment is not directly before a statement (e.g. inside an expression).
+    pub attached_to: Option<u32>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(u8)]
+pub enum CommentKind {
+    /// `// ...`
+    Line = 0,
+    /// `/* ... */`
+    Block = 1,
+}
+
+/// Operator precedence, from loosest to tightest binding.
+/// Shared by both versions of the AST.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[repr(u8)]
+pub enum Precedence {
+    Assignment = 0,
+    Conditional = 1,
+    /// `||` and `??`. `??` cannot be mixed with `||` or `&&` without parentheses.
+    LogicalOr = 2,
+    LogicalAnd = 3,
+    BitwiseOr = 4,
+    BitwiseXor = 5,
+    BitwiseAnd = 6,
+    Equality = 7,
+    /// `<`, `>`, `<=`, `>=`, `in`, `instanceof`
+    Relational = 8,
+    Shift = 9,
+    Additive = 10,
+    Multiplicative = 11,
+    /// `**`. Right-associative, and left side cannot be a unary expression without parentheses.
+    Exponentiation = 12,
+    Unary = 13,
+    /// Calls and member expressions
+    Call = 14,
+    /// Identifiers and literals
+    Primary = 15,
+}
+
+impl Precedence {
+    /// Get the precedence one level tighter than this one.
+    pub fn next(self) -> Self {
+        match self {
+            Self::Assignment => Self::Conditional,
+            Self::Conditional => Self::LogicalOr,
+            Self::LogicalOr => Self::LogicalAnd,
+            Self::LogicalAnd => Self::BitwiseOr,
+            Self::BitwiseOr => Self::BitwiseXor,
+            Self::BitwiseXor => Self::BitwiseAnd,
+            Self::BitwiseAnd => Self::Equality,
+            Self::Equality => Self::Relational,
+            Self::Relational => Self::Shift,
+            Self::Shift => Self::Additive,
+            Self::Additive => Self::Multiplicative,
+            Self::Multiplicative => Self::Exponentiation,
+            Self::Exponentiation => Self::Unary,
+            Self::Unary => Self::Call,
+            Self::Call | Self::Primary => Self::Primary,
+        }
+    }
+}
+
+/// Error returned when parsing an operator from a string fails.
 #[derive(Debug)]
+pub struct OperatorParseError {
+    pub operator: String,
+}
+
+impl fmt::Display for OperatorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown operator `{}`", self.operator)
+    }
+}
+
+impl core::error::Error for OperatorParseError {}
+
+/// Macro to implement `as_str` and `FromStr` for an operator enum, from a single list of spellings.
+/// With `serde` feature, operators are also serialized as those spellings.
+macro_rules! operator_strings {
+    ($ty:ident { $($variant:ident => $str:literal),+ $(,)? }) => {
+        impl $ty {
+            /// Get operator as it appears in JS source.
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(Self::$variant => $str,)+
+                }
+            }
+        }
+
+        impl FromStr for $ty {
+            type Err = OperatorParseError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $($str => Ok(Self::$variant),)+
+                    _ => Err(OperatorParseError { operator: s.to_string() }),
+                }
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl Serialize for $ty {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        #[cfg(feature = "serde")]
+        impl<'de> Deserialize<'de> for $ty {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                s.parse().map_err(serde::de::Error::custom)
+            }
+        }
+    };
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
 #[repr(C)]
 pub struct Program<'a> {
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::json::serialize_vec")
+    )]
     pub body: Vec<'a, Statement<'a>>,
+    /// All comments in source text, in source order.
+    /// ESTree has no comments on `Program`, so they're not serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub comments: Vec<'a, Comment<'a>>,
 }
 
 #[repr(C)]
 pub struct TraversableProgram<'a, 't> {
     pub body: shared_vec!(TraversableStatement<'a, 't>),
+    pub comments: Vec<'a, Comment<'a>>,
 }
 
-assert_size_align_match!(Program, TraversableProgram);
+assert_size_align_match!(Program, TraversableProgram, body, comments);
+
+impl<'a, 't> GCell<'t, TraversableProgram<'a, 't>> {
+    /// Iterate over top-level statements.
+    ///
+    /// The token is only borrowed immutably, so the AST cannot be mutated while iterating.
+    /// To mutate, collect the statements first (they are cheap to clone), or use `Traverse`.
+    pub fn statements<'b>(
+        &'b self,
+        tk: &'b Token<'t>,
+    ) -> impl Iterator<Item = &'b TraversableStatement<'a, 't>> + 'b {
+        self.borrow(tk).body.iter().map(move |stmt| stmt.borrow(tk))
+    }
+}
 
 #[derive(Debug)]
 #[repr(C, u8)]
 pub enum Statement<'a> {
     ExpressionStatement(Box<'a, ExpressionStatement<'a>>) = 0,
+    BlockStatement(Box<'a, BlockStatement<'a>>) = 1,
+    IfStatement(Box<'a, IfStatement<'a>>) = 2,
+    ReturnStatement(Box<'a, ReturnStatement<'a>>) = 3,
+    VariableDeclaration(Box<'a, VariableDeclaration<'a>>) = 4,
 }
 
 #[derive(Clone)]
 #[repr(C, u8)]
 pub enum TraversableStatement<'a, 't> {
     ExpressionStatement(shared_box!(TraversableExpressionStatement<'a, 't>)) = 0,
+    BlockStatement(shared_box!(TraversableBlockStatement<'a, 't>)) = 1,
+    IfStatement(shared_box!(TraversableIfStatement<'a, 't>)) = 2,
+    ReturnStatement(shared_box!(TraversableReturnStatement<'a, 't>)) = 3,
+    VariableDeclaration(shared_box!(TraversableVariableDeclaration<'a, 't>)) = 4,
 }
 
 assert_size_align_match!(Statement, TraversableStatement);
 
-#[derive(Clone, Copy, Debug)]
+impl<'a, 't> TraversableStatement<'a, 't> {
+    /// Get parent of this statement.
+    pub fn parent(&self, tk: &Token<'t>) -> TraversableStatementParent<'a, 't> {
+        match self {
+            Self::ExpressionStatement(expr_stmt) => expr_stmt.borrow(tk).parent,
+            Self::BlockStatement(block) => block.borrow(tk).parent,
+            Self::IfStatement(if_stmt) => if_stmt.borrow(tk).parent,
+            Self::ReturnStatement(return_stmt) => return_stmt.borrow(tk).parent,
+            Self::VariableDeclaration(var_decl) => var_decl.borrow(tk).parent,
+        }
+    }
+
+    /// Set parent of this statement.
+    pub fn set_parent(&self, parent: TraversableStatementParent<'a, 't>, tk: &mut Token<'t>) {
+        match self {
+            Self::ExpressionStatement(expr_stmt) => expr_stmt.borrow_mut(tk).parent = parent,
+            Self::BlockStatement(block) => block.borrow_mut(tk).parent = parent,
+            Self::IfStatement(if_stmt) => if_stmt.borrow_mut(tk).parent = parent,
+            Self::ReturnStatement(return_stmt) => return_stmt.borrow_mut(tk).parent = parent,
+            Self::VariableDeclaration(var_decl) => var_decl.borrow_mut(tk).parent = parent,
+        }
+    }
+
+    /// Returns `true` if `self` and `other` are the same node, not just nodes with equal content.
+    pub fn is_same(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::ExpressionStatement(a), Self::ExpressionStatement(b)) => core::ptr::eq(*a, *b),
+            (Self::BlockStatement(a), Self::BlockStatement(b)) => core::ptr::eq(*a, *b),
+            (Self::IfStatement(a), Self::IfStatement(b)) => core::ptr::eq(*a, *b),
+            (Self::ReturnStatement(a), Self::ReturnStatement(b)) => core::ptr::eq(*a, *b),
+            (Self::VariableDeclaration(a), Self::VariableDeclaration(b)) => core::ptr::eq(*a, *b),
+            _ => false,
+        }
+    }
+
+    /// Insert `new` into the statement list containing this statement, just before it.
+    /// Parent link of `new` is set to the owner of the list.
+    ///
+    /// If called during `Traverse`, `new` will not be visited.
+    ///
+    /// # Panics
+    /// Panics if this statement is not in a statement list (`Program` or `BlockStatement` body).
+    pub fn insert_before(&self, new: Self, tk: &mut Token<'t>) {
+        let parent = self.parent(tk);
+        new.set_parent(parent, tk);
+        let stmts = statement_list(parent, tk);
+        let index = position_in(stmts, self);
+        stmts.insert(index, GCell::new(new));
+    }
+
+    /// Insert `new` into the statement list containing this statement, just after it.
+    /// Parent link of `new` is set to the owner of the list.
+    ///
+    /// If called during `Traverse`, `new` will be visited after this statement.
+    ///
+    /// # Panics
+    /// Panics if this statement is not in a statement list (`Program` or `BlockStatement` body).
+    pub fn insert_after(&self, new: Self, tk: &mut Token<'t>) {
+        let parent = self.parent(tk);
+        new.set_parent(parent, tk);
+        let stmts = statement_list(parent, tk);
+        let index = position_in(stmts, self);
+        stmts.insert(index + 1, GCell::new(new));
+    }
+
+    /// Remove this statement from the statement list containing it.
+    /// This statement's parent link is not altered.
+    ///
+    /// If called during `Traverse` on the statement currently being visited, its descendants
+    /// are still visited, and traversal then continues with the statement which followed it.
+    ///
+    /// # Panics
+    /// Panics if this statement is not in a statement list (`Program` or `BlockStatement` body).
+    pub fn remove(&self, tk: &mut Token<'t>) {
+        let stmts = statement_list(self.parent(tk), tk);
+        let index = position_in(stmts, self);
+        stmts.remove(index);
+    }
+}
+
+/// Get statement list owned by `parent`.
+fn statement_list<'a, 't, 'b>(
+    parent: TraversableStatementParent<'a, 't>,
+    tk: &'b mut Token<'t>,
+) -> &'b mut shared_vec!(TraversableStatement<'a, 't>) {
+    match parent {
+        TraversableStatementParent::Program(program) => &mut program.borrow_mut(tk).body,
+        TraversableStatementParent::BlockStatement(block) => &mut block.borrow_mut(tk).body,
+        _ => panic!("Statement is not in a statement list"),
+    }
+}
+
+/// Get index of `stmt` in `stmts`.
+fn position_in<'a, 't>(
+    stmts: &mut shared_vec!(TraversableStatement<'a, 't>),
+    stmt: &TraversableStatement<'a, 't>,
+) -> usize {
+    stmts
+        .iter_mut()
+        .position(|other| other.get_mut().is_same(stmt))
+        .expect("Statement is not in its parent's statement list")
+}
+
+impl<'a> Statement<'a> {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::ExpressionStatement(expr_stmt) => expr_stmt.span,
+            Self::BlockStatement(block) => block.span,
+            Self::IfStatement(if_stmt) => if_stmt.span,
+            Self::ReturnStatement(return_stmt) => return_stmt.span,
+            Self::VariableDeclaration(var_decl) => var_decl.span,
+        }
+    }
+
+    /// Get parent of this statement.
+    pub fn parent(&self) -> StatementParent<'a> {
+        match self {
+            Self::ExpressionStatement(expr_stmt) => expr_stmt.parent,
+            Self::BlockStatement(block) => block.parent,
+            Self::IfStatement(if_stmt) => if_stmt.parent,
+            Self::ReturnStatement(return_stmt) => return_stmt.parent,
+            Self::VariableDeclaration(var_decl) => var_decl.parent,
+        }
+    }
+
+    /// Set parent of this statement.
+    pub fn set_parent(&mut self, parent: StatementParent<'a>) {
+        match self {
+            Self::ExpressionStatement(expr_stmt) => expr_stmt.parent = parent,
+            Self::BlockStatement(block) => block.parent = parent,
+            Self::IfStatement(if_stmt) => if_stmt.parent = parent,
+            Self::ReturnStatement(return_stmt) => return_stmt.parent = parent,
+            Self::VariableDeclaration(var_decl) => var_decl.parent = parent,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 #[repr(C, u8)]
 pub enum StatementParent<'a> {
     None = 0,
     Program(*const Program<'a>) = 1,
+    BlockStatement(*const BlockStatement<'a>) = 2,
+    IfStatementConsequent(*const IfStatement<'a>) = 3,
+    IfStatementAlternate(*const IfStatement<'a>) = 4,
 }
 
 #[derive(Clone, Copy)]
@@ -81,25 +410,39 @@ pub enum StatementParent<'a> {
 pub enum TraversableStatementParent<'a, 't> {
     None = 0,
     Program(shared_box!(TraversableProgram<'a, 't>)) = 1,
+    BlockStatement(shared_box!(TraversableBlockStatement<'a, 't>)) = 2,
+    IfStatementConsequent(shared_box!(TraversableIfStatement<'a, 't>)) = 3,
+    IfStatementAlternate(shared_box!(TraversableIfStatement<'a, 't>)) = 4,
 }
 
 assert_size_align_match!(StatementParent, TraversableStatementParent);
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
 #[repr(C)]
 pub struct ExpressionStatement<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
     pub expression: Expression<'a>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub parent: StatementParent<'a>,
 }
 
 #[derive(Clone)]
 #[repr(C)]
 pub struct TraversableExpressionStatement<'a, 't> {
+    pub span: Span,
     pub expression: TraversableExpression<'a, 't>,
     pub parent: TraversableStatementParent<'a, 't>,
 }
 
-assert_size_align_match!(ExpressionStatement, TraversableExpressionStatement);
+assert_size_align_match!(
+    ExpressionStatement,
+    TraversableExpressionStatement,
+    span,
+    expression,
+    parent
+);
 
 #[derive(Debug)]
 #[repr(C, u8)]
@@ -108,6 +451,17 @@ pub enum Expression<'a> {
     Identifier(Box<'a, IdentifierReference<'a>>) = 1,
     BinaryExpression(Box<'a, BinaryExpression<'a>>) = 2,
     UnaryExpression(Box<'a, UnaryExpression<'a>>) = 3,
+    NumericLiteral(Box<'a, NumericLiteral<'a>>) = 4,
+    BooleanLiteral(Box<'a, BooleanLiteral<'a>>) = 5,
+    NullLiteral(Box<'a, NullLiteral<'a>>) = 6,
+    CallExpression(Box<'a, CallExpression<'a>>) = 7,
+    MemberExpression(Box<'a, MemberExpression<'a>>) = 8,
+    LogicalExpression(Box<'a, LogicalExpression<'a>>) = 9,
+    ConditionalExpression(Box<'a, ConditionalExpression<'a>>) = 10,
+    AssignmentExpression(Box<'a, AssignmentExpression<'a>>) = 11,
+    TemplateLiteral(Box<'a, TemplateLiteral<'a>>) = 12,
+    ArrayExpression(Box<'a, ArrayExpression<'a>>) = 13,
+    ObjectExpression(Box<'a, ObjectExpression<'a>>) = 14,
 }
 
 #[derive(Clone)]
@@ -117,11 +471,122 @@ pub enum TraversableExpression<'a, 't> {
     Identifier(shared_box!(TraversableIdentifierReference<'a, 't>)) = 1,
     BinaryExpression(shared_box!(TraversableBinaryExpression<'a, 't>)) = 2,
     UnaryExpression(shared_box!(TraversableUnaryExpression<'a, 't>)) = 3,
+    NumericLiteral(shared_box!(TraversableNumericLiteral<'a, 't>)) = 4,
+    BooleanLiteral(shared_box!(TraversableBooleanLiteral<'a, 't>)) = 5,
+    NullLiteral(shared_box!(TraversableNullLiteral<'a, 't>)) = 6,
+    CallExpression(shared_box!(TraversableCallExpression<'a, 't>)) = 7,
+    MemberExpression(shared_box!(TraversableMemberExpression<'a, 't>)) = 8,
+    LogicalExpression(shared_box!(TraversableLogicalExpression<'a, 't>)) = 9,
+    ConditionalExpression(shared_box!(TraversableConditionalExpression<'a, 't>)) = 10,
+    AssignmentExpression(shared_box!(TraversableAssignmentExpression<'a, 't>)) = 11,
+    TemplateLiteral(shared_box!(TraversableTemplateLiteral<'a, 't>)) = 12,
+    ArrayExpression(shared_box!(TraversableArrayExpression<'a, 't>)) = 13,
+    ObjectExpression(shared_box!(TraversableObjectExpression<'a, 't>)) = 14,
 }
 
 assert_size_align_match!(Expression, TraversableExpression);
 
-#[derive(Clone, Copy, Debug)]
+impl<'a> Expression<'a> {
+    pub fn span(&self) -> Span {
+        match self {
+            Self::StringLiteral(str_lit) => str_lit.span,
+            Self::Identifier(id) => id.span,
+            Self::BinaryExpression(bin_expr) => bin_expr.span,
+            Self::UnaryExpression(unary_expr) => unary_expr.span,
+            Self::NumericLiteral(num_lit) => num_lit.span,
+            Self::BooleanLiteral(bool_lit) => bool_lit.span,
+            Self::NullLiteral(null_lit) => null_lit.span,
+            Self::CallExpression(call_expr) => call_expr.span,
+            Self::MemberExpression(member_expr) => member_expr.span,
+            Self::LogicalExpression(logical_expr) => logical_expr.span,
+            Self::ConditionalExpression(cond_expr) => cond_expr.span,
+            Self::AssignmentExpression(assign_expr) => assign_expr.span,
+            Self::TemplateLiteral(template_lit) => template_lit.span,
+            Self::ArrayExpression(array_expr) => array_expr.span,
+            Self::ObjectExpression(object_expr) => object_expr.span,
+        }
+    }
+
+    /// Get precedence of this expression, for deciding whether it needs parentheses.
+    pub fn precedence(&self) -> Precedence {
+        match self {
+            // Negative numbers are printed with a leading `-`, so they bind like unary `-`.
+            // Constant folding produces these, e.g. when folding `-5`.
+            Self::NumericLiteral(num_lit) if num_lit.value.is_sign_negative() => Precedence::Unary,
+            Self::Identifier(_)
+            | Self::StringLiteral(_)
+            | Self::NumericLiteral(_)
+            | Self::BooleanLiteral(_)
+            | Self::NullLiteral(_)
+            | Self::TemplateLiteral(_)
+            | Self::ArrayExpression(_)
+            | Self::ObjectExpression(_) => Precedence::Primary,
+            Self::BinaryExpression(bin_expr) => bin_expr.operator.precedence(),
+            Self::UnaryExpression(unary_expr) => unary_expr.operator.precedence(),
+            Self::CallExpression(_) | Self::MemberExpression(_) => Precedence::Call,
+            Self::LogicalExpression(logical_expr) => logical_expr.operator.precedence(),
+            Self::ConditionalExpression(_) => Precedence::Conditional,
+            Self::AssignmentExpression(_) => Precedence::Assignment,
+        }
+    }
+
+    /// Get parent of this expression.
+    pub fn parent(&self) -> ExpressionParent<'a> {
+        match self {
+            Self::StringLiteral(str_lit) => str_lit.parent,
+            Self::Identifier(id) => id.parent,
+            Self::BinaryExpression(bin_expr) => bin_expr.parent,
+            Self::UnaryExpression(unary_expr) => unary_expr.parent,
+            Self::NumericLiteral(num_lit) => num_lit.parent,
+            Self::BooleanLiteral(bool_lit) => bool_lit.parent,
+            Self::NullLiteral(null_lit) => null_lit.parent,
+            Self::CallExpression(call_expr) => call_expr.parent,
+            Self::MemberExpression(member_expr) => member_expr.parent,
+            Self::LogicalExpression(logical_expr) => logical_expr.parent,
+            Self::ConditionalExpression(cond_expr) => cond_expr.parent,
+            Self::AssignmentExpression(assign_expr) => assign_expr.parent,
+            Self::TemplateLiteral(template_lit) => template_lit.parent,
+            Self::ArrayExpression(array_expr) => array_expr.parent,
+            Self::ObjectExpression(object_expr) => object_expr.parent,
+        }
+    }
+
+    /// Set parent of this expression.
+    pub fn set_parent(&mut self, parent: ExpressionParent<'a>) {
+        match self {
+            Self::StringLiteral(str_lit) => str_lit.parent = parent,
+            Self::Identifier(id) => id.parent = parent,
+            Self::BinaryExpression(bin_expr) => bin_expr.parent = parent,
+            Self::UnaryExpression(unary_expr) => unary_expr.parent = parent,
+            Self::NumericLiteral(num_lit) => num_lit.parent = parent,
+            Self::BooleanLiteral(bool_lit) => bool_lit.parent = parent,
+            Self::NullLiteral(null_lit) => null_lit.parent = parent,
+            Self::CallExpression(call_expr) => call_expr.parent = parent,
+            Self::MemberExpression(member_expr) => member_expr.parent = parent,
+            Self::LogicalExpression(logical_expr) => logical_expr.parent = parent,
+            Self::ConditionalExpression(cond_expr) => cond_expr.parent = parent,
+            Self::AssignmentExpression(assign_expr) => assign_expr.parent = parent,
+            Self::TemplateLiteral(template_lit) => template_lit.parent = parent,
+            Self::ArrayExpression(array_expr) => array_expr.parent = parent,
+            Self::ObjectExpression(object_expr) => object_expr.parent = parent,
+        }
+    }
+
+    /// Replace this expression with `new`, and return the old expression.
+    ///
+    /// `new`'s parent link is set to this expression's parent, so the new node points to
+    /// the same parent as the old one did.
+    ///
+    /// Back-links of `new`'s own children are not touched. They must already point to `new`
+    /// (they will if `new` was created by `AstBuilder`). If any children of the old expression
+    /// are moved into `new`, caller must update their parent links.
+    pub fn replace_with(&mut self, mut new: Expression<'a>) -> Expression<'a> {
+        new.set_parent(self.parent());
+        core::mem::replace(self, new)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
 #[repr(C, u8)]
 pub enum ExpressionParent<'a> {
     None = 0,
@@ -129,6 +594,24 @@ pub enum ExpressionParent<'a> {
     BinaryExpressionLeft(*const BinaryExpression<'a>) = 2,
     BinaryExpressionRight(*const BinaryExpression<'a>) = 3,
     UnaryExpression(*const UnaryExpression<'a>) = 4,
+    CallExpressionCallee(*const CallExpression<'a>) = 5,
+    CallExpressionArgument(*const CallExpression<'a>) = 6,
+    MemberExpressionObject(*const MemberExpression<'a>) = 7,
+    MemberExpressionProperty(*const MemberExpression<'a>) = 8,
+    LogicalExpressionLeft(*const LogicalExpression<'a>) = 9,
+    LogicalExpressionRight(*const LogicalExpression<'a>) = 10,
+    ConditionalExpressionTest(*const ConditionalExpression<'a>) = 11,
+    ConditionalExpressionConsequent(*const ConditionalExpression<'a>) = 12,
+    ConditionalExpressionAlternate(*const ConditionalExpression<'a>) = 13,
+    AssignmentExpressionTarget(*const AssignmentExpression<'a>) = 14,
+    AssignmentExpressionValue(*const AssignmentExpression<'a>) = 15,
+    IfStatementTest(*const IfStatement<'a>) = 16,
+    ReturnStatementArgument(*const ReturnStatement<'a>) = 17,
+    VariableDeclaratorInit(*const VariableDeclarator<'a>) = 18,
+    TemplateLiteralExpression(*const TemplateLiteral<'a>) = 19,
+    ArrayExpressionElement(*const ArrayExpression<'a>) = 20,
+    PropertyKey(*const Property<'a>) = 21,
+    PropertyValue(*const Property<'a>) = 22,
 }
 
 #[derive(Clone, Copy)]
@@ -139,86 +622,580 @@ pub enum TraversableExpressionParent<'a, 't> {
     BinaryExpressionLeft(shared_box!(TraversableBinaryExpression<'a, 't>)) = 2,
     BinaryExpressionRight(shared_box!(TraversableBinaryExpression<'a, 't>)) = 3,
     UnaryExpression(shared_box!(TraversableUnaryExpression<'a, 't>)) = 4,
+    CallExpressionCallee(shared_box!(TraversableCallExpression<'a, 't>)) = 5,
+    CallExpressionArgument(shared_box!(TraversableCallExpression<'a, 't>)) = 6,
+    MemberExpressionObject(shared_box!(TraversableMemberExpression<'a, 't>)) = 7,
+    MemberExpressionProperty(shared_box!(TraversableMemberExpression<'a, 't>)) = 8,
+    LogicalExpressionLeft(shared_box!(TraversableLogicalExpression<'a, 't>)) = 9,
+    LogicalExpressionRight(shared_box!(TraversableLogicalExpression<'a, 't>)) = 10,
+    ConditionalExpressionTest(shared_box!(TraversableConditionalExpression<'a, 't>)) = 11,
+    ConditionalExpressionConsequent(shared_box!(TraversableConditionalExpression<'a, 't>)) = 12,
+    ConditionalExpressionAlternate(shared_box!(TraversableConditionalExpression<'a, 't>)) = 13,
+    AssignmentExpressionTarget(shared_box!(TraversableAssignmentExpression<'a, 't>)) = 14,
+    AssignmentExpressionValue(shared_box!(TraversableAssignmentExpression<'a, 't>)) = 15,
+    IfStatementTest(shared_box!(TraversableIfStatement<'a, 't>)) = 16,
+    ReturnStatementArgument(shared_box!(TraversableReturnStatement<'a, 't>)) = 17,
+    VariableDeclaratorInit(shared_box!(TraversableVariableDeclarator<'a, 't>)) = 18,
+    TemplateLiteralExpression(shared_box!(TraversableTemplateLiteral<'a, 't>)) = 19,
+    ArrayExpressionElement(shared_box!(TraversableArrayExpression<'a, 't>)) = 20,
+    PropertyKey(shared_box!(TraversableProperty<'a, 't>)) = 21,
+    PropertyValue(shared_box!(TraversableProperty<'a, 't>)) = 22,
 }
 
 assert_size_align_match!(ExpressionParent, TraversableExpressionParent);
 
+impl<'a, 't> TraversableExpression<'a, 't> {
+    /// Get parent of this expression.
+    pub fn parent(&self, tk: &Token<'t>) -> TraversableExpressionParent<'a, 't> {
+        match self {
+            Self::StringLiteral(str_lit) => str_lit.borrow(tk).parent,
+            Self::Identifier(id) => id.borrow(tk).parent,
+            Self::BinaryExpression(bin_expr) => bin_expr.borrow(tk).parent,
+            Self::UnaryExpression(unary_expr) => unary_expr.borrow(tk).parent,
+            Self::NumericLiteral(num_lit) => num_lit.borrow(tk).parent,
+            Self::BooleanLiteral(bool_lit) => bool_lit.borrow(tk).parent,
+            Self::NullLiteral(null_lit) => null_lit.borrow(tk).parent,
+            Self::CallExpression(call_expr) => call_expr.borrow(tk).parent,
+            Self::MemberExpression(member_expr) => member_expr.borrow(tk).parent,
+            Self::LogicalExpression(logical_expr) => logical_expr.borrow(tk).parent,
+            Self::ConditionalExpression(cond_expr) => cond_expr.borrow(tk).parent,
+            Self::AssignmentExpression(assign_expr) => assign_expr.borrow(tk).parent,
+            Self::TemplateLiteral(template_lit) => template_lit.borrow(tk).parent,
+            Self::ArrayExpression(array_expr) => array_expr.borrow(tk).parent,
+            Self::ObjectExpression(object_expr) => object_expr.borrow(tk).parent,
+        }
+    }
+
+    /// Get precedence of this expression, for deciding whether it needs parentheses.
+    pub fn precedence(&self, tk: &Token<'t>) -> Precedence {
+        match self {
+            // Negative numbers are printed with a leading `-`, so they bind like unary `-`
+            Self::NumericLiteral(num_lit) if num_lit.borrow(tk).value.is_sign_negative() => {
+                Precedence::Unary
+            }
+            Self::Identifier(_)
+            | Self::StringLiteral(_)
+            | Self::NumericLiteral(_)
+            | Self::BooleanLiteral(_)
+            | Self::NullLiteral(_)
+            | Self::TemplateLiteral(_)
+            | Self::ArrayExpression(_)
+            | Self::ObjectExpression(_) => Precedence::Primary,
+            Self::BinaryExpression(bin_expr) => bin_expr.borrow(tk).operator.precedence(),
+            Self::UnaryExpression(unary_expr) => unary_expr.borrow(tk).operator.precedence(),
+            Self::CallExpression(_) | Self::MemberExpression(_) => Precedence::Call,
+            Self::LogicalExpression(logical_expr) => logical_expr.borrow(tk).operator.precedence(),
+            Self::ConditionalExpression(_) => Precedence::Conditional,
+            Self::AssignmentExpression(_) => Precedence::Assignment,
+        }
+    }
+
+    /// Get parent of this expression, if the parent is itself an expression.
+    pub fn parent_expression(&self, tk: &Token<'t>) -> Option<TraversableExpression<'a, 't>> {
+        self.parent(tk).as_expression()
+    }
+
+    /// Iterate over ancestors of this expression, starting with its parent,
+    /// and ending with the `Program` at the root of the AST.
+    ///
+    /// e.g. to check if expression is inside a `return` statement:
+    /// ```ignore
+    /// let in_return = expr.ancestors(tk).any(|ancestor| {
+    ///     matches!(
+    ///         ancestor,
+    ///         AncestorKind::ExpressionParent(ExpressionParent::ReturnStatementArgument(_))
+    ///     )
+    /// });
+    /// ```
+    pub fn ancestors<'b>(&self, tk: &'b Token<'t>) -> TraversableAncestors<'a, 't, 'b> {
+        TraversableAncestors {
+            next: Some(TraversableAncestorKind::ExpressionParent(self.parent(tk))),
+            tk,
+        }
+    }
+
+    /// Set parent of this expression.
+    pub fn set_parent(&self, parent: TraversableExpressionParent<'a, 't>, tk: &mut Token<'t>) {
+        match self {
+            Self::StringLiteral(str_lit) => str_lit.borrow_mut(tk).parent = parent,
+            Self::Identifier(id) => id.borrow_mut(tk).parent = parent,
+            Self::BinaryExpression(bin_expr) => bin_expr.borrow_mut(tk).parent = parent,
+            Self::UnaryExpression(unary_expr) => unary_expr.borrow_mut(tk).parent = parent,
+            Self::NumericLiteral(num_lit) => num_lit.borrow_mut(tk).parent = parent,
+            Self::BooleanLiteral(bool_lit) => bool_lit.borrow_mut(tk).parent = parent,
+            Self::NullLiteral(null_lit) => null_lit.borrow_mut(tk).parent = parent,
+            Self::CallExpression(call_expr) => call_expr.borrow_mut(tk).parent = parent,
+            Self::MemberExpression(member_expr) => member_expr.borrow_mut(tk).parent = parent,
+            Self::LogicalExpression(logical_expr) => logical_expr.borrow_mut(tk).parent = parent,
+            Self::ConditionalExpression(cond_expr) => cond_expr.borrow_mut(tk).parent = parent,
+            Self::AssignmentExpression(assign_expr) => assign_expr.borrow_mut(tk).parent = parent,
+            Self::TemplateLiteral(template_lit) => template_lit.borrow_mut(tk).parent = parent,
+            Self::ArrayExpression(array_expr) => array_expr.borrow_mut(tk).parent = parent,
+            Self::ObjectExpression(object_expr) => object_expr.borrow_mut(tk).parent = parent,
+        }
+    }
+
+    /// Returns `true` if `self` and `other` are the same node, not just nodes with equal content.
+    pub fn is_same(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::StringLiteral(a), Self::StringLiteral(b)) => core::ptr::eq(*a, *b),
+            (Self::Identifier(a), Self::Identifier(b)) => core::ptr::eq(*a, *b),
+            (Self::BinaryExpression(a), Self::BinaryExpression(b)) => core::ptr::eq(*a, *b),
+            (Self::UnaryExpression(a), Self::UnaryExpression(b)) => core::ptr::eq(*a, *b),
+            (Self::NumericLiteral(a), Self::NumericLiteral(b)) => core::ptr::eq(*a, *b),
+            (Self::BooleanLiteral(a), Self::BooleanLiteral(b)) => core::ptr::eq(*a, *b),
+            (Self::NullLiteral(a), Self::NullLiteral(b)) => core::ptr::eq(*a, *b),
+            (Self::CallExpression(a), Self::CallExpression(b)) => core::ptr::eq(*a, *b),
+            (Self::MemberExpression(a), Self::MemberExpression(b)) => core::ptr::eq(*a, *b),
+            (Self::LogicalExpression(a), Self::LogicalExpression(b)) => core::ptr::eq(*a, *b),
+            (Self::ConditionalExpression(a), Self::ConditionalExpression(b)) => {
+                core::ptr::eq(*a, *b)
+            }
+            (Self::AssignmentExpression(a), Self::AssignmentExpression(b)) => core::ptr::eq(*a, *b),
+            (Self::TemplateLiteral(a), Self::TemplateLiteral(b)) => core::ptr::eq(*a, *b),
+            (Self::ArrayExpression(a), Self::ArrayExpression(b)) => core::ptr::eq(*a, *b),
+            (Self::ObjectExpression(a), Self::ObjectExpression(b)) => core::ptr::eq(*a, *b),
+            _ => false,
+        }
+    }
+
+    /// Replace this expression with `new` in the slot in its parent which holds it,
+    /// and return the old expression.
+    ///
+    /// Parent links are handled as for the standard AST's [`Expression::replace_with`].
+    /// Parent link of the old expression is not altered. Caller must update it if they re-insert
+    /// the old expression elsewhere in the AST.
+    ///
+    /// If called during `Traverse` from an `enter_*` hook for this expression, this expression's
+    /// descendants are still visited, not `new`'s.
+    ///
+    /// # Panics
+    /// Panics if this expression has no parent.
+    pub fn replace_with(&self, new: Self, tk: &mut Token<'t>) -> Self {
+        let parent = self.parent(tk);
+        new.set_parent(parent, tk);
+        let slot = expression_slot(parent, self, tk);
+        core::mem::replace(slot, new)
+    }
+}
+
+/// Get the slot in `parent` which holds `expr`.
+fn expression_slot<'a, 't, 'b>(
+    parent: TraversableExpressionParent<'a, 't>,
+    expr: &TraversableExpression<'a, 't>,
+    tk: &'b mut Token<'t>,
+) -> &'b mut TraversableExpression<'a, 't> {
+    use TraversableExpressionParent as EP;
+
+    match parent {
+        EP::None => panic!("Expression has no parent"),
+        EP::ExpressionStatement(expr_stmt) => &mut expr_stmt.borrow_mut(tk).expression,
+        EP::BinaryExpressionLeft(bin_expr) => &mut bin_expr.borrow_mut(tk).left,
+        EP::BinaryExpressionRight(bin_expr) => &mut bin_expr.borrow_mut(tk).right,
+        EP::UnaryExpression(unary_expr) => &mut unary_expr.borrow_mut(tk).argument,
+        EP::CallExpressionCallee(call_expr) => &mut call_expr.borrow_mut(tk).callee,
+        EP::CallExpressionArgument(call_expr) => call_expr
+            .borrow_mut(tk)
+            .arguments
+            .iter_mut()
+            .map(GCell::get_mut)
+            .find(|arg| arg.is_same(expr))
+            .expect("Expression is not in its parent's arguments"),
+        EP::MemberExpressionObject(member_expr) => &mut member_expr.borrow_mut(tk).object,
+        EP::MemberExpressionProperty(member_expr) => &mut member_expr.borrow_mut(tk).property,
+        EP::LogicalExpressionLeft(logical_expr) => &mut logical_expr.borrow_mut(tk).left,
+        EP::LogicalExpressionRight(logical_expr) => &mut logical_expr.borrow_mut(tk).right,
+        EP::ConditionalExpressionTest(cond_expr) => &mut cond_expr.borrow_mut(tk).test,
+        EP::ConditionalExpressionConsequent(cond_expr) => &mut cond_expr.borrow_mut(tk).consequent,
+        EP::ConditionalExpressionAlternate(cond_expr) => &mut cond_expr.borrow_mut(tk).alternate,
+        EP::AssignmentExpressionTarget(assign_expr) => &mut assign_expr.borrow_mut(tk).target,
+        EP::AssignmentExpressionValue(assign_expr) => &mut assign_expr.borrow_mut(tk).value,
+        EP::IfStatementTest(if_stmt) => &mut if_stmt.borrow_mut(tk).test,
+        EP::ReturnStatementArgument(return_stmt) => return_stmt
+            .borrow_mut(tk)
+            .argument
+            .as_mut()
+            .expect("Return statement has no argument"),
+        EP::VariableDeclaratorInit(declarator) => declarator
+            .borrow_mut(tk)
+            .init
+            .as_mut()
+            .expect("Variable declarator has no initializer"),
+        EP::TemplateLiteralExpression(template_lit) => template_lit
+            .borrow_mut(tk)
+            .expressions
+            .iter_mut()
+            .map(GCell::get_mut)
+            .find(|other| other.is_same(expr))
+            .expect("Expression is not in its parent's expressions"),
+        EP::ArrayExpressionElement(array_expr) => array_expr
+            .borrow_mut(tk)
+            .elements
+            .iter_mut()
+            .filter_map(|element| element.get_mut().as_mut())
+            .find(|other| other.is_same(expr))
+            .expect("Expression is not in its parent's elements"),
+        EP::PropertyKey(prop) => &mut prop.borrow_mut(tk).key,
+        EP::PropertyValue(prop) => &mut prop.borrow_mut(tk).value,
+    }
+}
+
+impl<'a, 't> TraversableExpressionParent<'a, 't> {
+    /// Convert parent to an `Expression`.
+    /// Returns `None` if there is no parent, or the parent is not an expression
+    /// (e.g. `ExpressionStatement`).
+    pub fn as_expression(self) -> Option<TraversableExpression<'a, 't>> {
+        match self {
+            Self::None
+            | Self::ExpressionStatement(_)
+            | Self::IfStatementTest(_)
+            | Self::ReturnStatementArgument(_)
+            | Self::VariableDeclaratorInit(_)
+            | Self::PropertyKey(_)
+            | Self::PropertyValue(_) => None,
+            Self::BinaryExpressionLeft(bin_expr) | Self::BinaryExpressionRight(bin_expr) => {
+                Some(TraversableExpression::BinaryExpression(bin_expr))
+            }
+            Self::UnaryExpression(unary_expr) => {
+                Some(TraversableExpression::UnaryExpression(unary_expr))
+            }
+            Self::CallExpressionCallee(call_expr) | Self::CallExpressionArgument(call_expr) => {
+                Some(TraversableExpression::CallExpression(call_expr))
+            }
+            Self::MemberExpressionObject(member_expr)
+            | Self::MemberExpressionProperty(member_expr) => {
+                Some(TraversableExpression::MemberExpression(member_expr))
+            }
+            Self::LogicalExpressionLeft(logical_expr)
+            | Self::LogicalExpressionRight(logical_expr) => {
+                Some(TraversableExpression::LogicalExpression(logical_expr))
+            }
+            Self::ConditionalExpressionTest(cond_expr)
+            | Self::ConditionalExpressionConsequent(cond_expr)
+            | Self::ConditionalExpressionAlternate(cond_expr) => {
+                Some(TraversableExpression::ConditionalExpression(cond_expr))
+            }
+            Self::AssignmentExpressionTarget(assign_expr)
+            | Self::AssignmentExpressionValue(assign_expr) => {
+                Some(TraversableExpression::AssignmentExpression(assign_expr))
+            }
+            Self::TemplateLiteralExpression(template_lit) => {
+                Some(TraversableExpression::TemplateLiteral(template_lit))
+            }
+            Self::ArrayExpressionElement(array_expr) => {
+                Some(TraversableExpression::ArrayExpression(array_expr))
+            }
+        }
+    }
+}
+
 #[derive(Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize),
+    serde(tag = "type", rename = "Identifier")
+)]
 #[repr(C)]
 pub struct IdentifierReference<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
     pub name: &'a str,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub parent: ExpressionParent<'a>,
 }
 
 #[derive(Clone)]
 #[repr(C)]
 pub struct TraversableIdentifierReference<'a, 't> {
+    pub span: Span,
     pub name: &'a str,
     pub parent: TraversableExpressionParent<'a, 't>,
 }
 
-assert_size_align_match!(IdentifierReference, TraversableIdentifierReference);
+assert_size_align_match!(
+    IdentifierReference,
+    TraversableIdentifierReference,
+    span,
+    name,
+    parent
+);
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
 #[repr(C)]
 pub struct StringLiteral<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
     pub value: &'a str,
+    /// Literal as written in source, including quotes. `None` for literals not from source.
+    /// Must be set to `None` if `value` is altered.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub raw: Option<&'a str>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub parent: ExpressionParent<'a>,
 }
 
 #[derive(Clone)]
 #[repr(C)]
 pub struct TraversableStringLiteral<'a, 't> {
+    pub span: Span,
     pub value: &'a str,
+    pub raw: Option<&'a str>,
     pub parent: TraversableExpressionParent<'a, 't>,
 }
 
-assert_size_align_match!(StringLiteral, TraversableStringLiteral);
+assert_size_align_match!(
+    StringLiteral,
+    TraversableStringLiteral,
+    span,
+    value,
+    raw,
+    parent
+);
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct NumericLiteral<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    pub value: f64,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: ExpressionParent<'a>,
+}
+
+#[derive(Clone)]
+#[repr(C)]
+pub struct TraversableNumericLiteral<'a, 't> {
+    pub span: Span,
+    pub value: f64,
+    pub parent: TraversableExpressionParent<'a, 't>,
+}
+
+assert_size_align_match!(
+    NumericLiteral,
+    TraversableNumericLiteral,
+    span,
+    value,
+    parent
+);
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct BooleanLiteral<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    pub value: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: ExpressionParent<'a>,
+}
+
+#[derive(Clone)]
+#[repr(C)]
+pub struct TraversableBooleanLiteral<'a, 't> {
+    pub span: Span,
+    pub value: bool,
+    pub parent: TraversableExpressionParent<'a, 't>,
+}
+
+assert_size_align_match!(
+    BooleanLiteral,
+    TraversableBooleanLiteral,
+    span,
+    value,
+    parent
+);
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct NullLiteral<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: ExpressionParent<'a>,
+}
+
+#[derive(Clone)]
+#[repr(C)]
+pub struct TraversableNullLiteral<'a, 't> {
+    pub span: Span,
+    pub parent: TraversableExpressionParent<'a, 't>,
+}
+
+assert_size_align_match!(NullLiteral, TraversableNullLiteral, span, parent);
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
 #[repr(C)]
 pub struct BinaryExpression<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
     pub left: Expression<'a>,
     pub operator: BinaryOperator,
     pub right: Expression<'a>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub parent: ExpressionParent<'a>,
 }
 
 #[derive(Clone)]
 #[repr(C)]
 pub struct TraversableBinaryExpression<'a, 't> {
+    pub span: Span,
     pub left: TraversableExpression<'a, 't>,
     pub operator: BinaryOperator,
     pub right: TraversableExpression<'a, 't>,
     pub parent: TraversableExpressionParent<'a, 't>,
 }
 
-assert_size_align_match!(BinaryExpression, TraversableBinaryExpression);
+assert_size_align_match!(
+    BinaryExpression,
+    TraversableBinaryExpression,
+    span,
+    left,
+    operator,
+    right,
+    parent
+);
+
+/// Slot in a `BinaryExpression` which holds a child expression.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum BinarySlot {
+    Left,
+    Right,
+}
+
+impl<'a, 't> GCell<'t, TraversableBinaryExpression<'a, 't>> {
+    /// Replace the child in `slot` with `new`, and return the old child.
+    ///
+    /// Parent link of `new` is updated to point to this binary expression.
+    /// Parent link of the old child is not altered. Caller must update it if they re-insert
+    /// the old child elsewhere in the AST.
+    pub fn set_expression_child(
+        &'a self,
+        slot: BinarySlot,
+        new: TraversableExpression<'a, 't>,
+        tk: &mut Token<'t>,
+    ) -> TraversableExpression<'a, 't> {
+        let parent = match slot {
+            BinarySlot::Left => TraversableExpressionParent::BinaryExpressionLeft(self),
+            BinarySlot::Right => TraversableExpressionParent::BinaryExpressionRight(self),
+        };
+        new.set_parent(parent, tk);
+        let bin_expr = self.borrow_mut(tk);
+        let field = match slot {
+            BinarySlot::Left => &mut bin_expr.left,
+            BinarySlot::Right => &mut bin_expr.right,
+        };
+        core::mem::replace(field, new)
+    }
+}
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 #[repr(u8)]
 pub enum BinaryOperator {
     Equality = 0,
     StrictEquality = 1,
+    Inequality = 2,
+    StrictInequality = 3,
+    LessThan = 4,
+    LessEqual = 5,
+    GreaterThan = 6,
+    GreaterEqual = 7,
+    In = 8,
+    Instanceof = 9,
+    ShiftLeft = 10,
+    ShiftRight = 11,
+    ShiftRightZeroFill = 12,
+    Add = 13,
+    Subtract = 14,
+    Multiply = 15,
+    Divide = 16,
+    Remainder = 17,
+    Exponent = 18,
+    BitwiseOr = 19,
+    BitwiseXor = 20,
+    BitwiseAnd = 21,
 }
 
+impl BinaryOperator {
+    pub fn precedence(self) -> Precedence {
+        match self {
+            Self::Equality | Self::StrictEquality | Self::Inequality | Self::StrictInequality => {
+                Precedence::Equality
+            }
+            Self::LessThan
+            | Self::LessEqual
+            | Self::GreaterThan
+            | Self::GreaterEqual
+            | Self::In
+            | Self::Instanceof => Precedence::Relational,
+            Self::ShiftLeft | Self::ShiftRight | Self::ShiftRightZeroFill => Precedence::Shift,
+            Self::Add | Self::Subtract => Precedence::Additive,
+            Self::Multiply | Self::Divide | Self::Remainder => Precedence::Multiplicative,
+            Self::Exponent => Precedence::Exponentiation,
+            Self::BitwiseOr => Precedence::BitwiseOr,
+            Self::BitwiseXor => Precedence::BitwiseXor,
+            Self::BitwiseAnd => Precedence::BitwiseAnd,
+        }
+    }
+}
+
+operator_strings!(BinaryOperator {
+    Equality => "==",
+    StrictEquality => "===",
+    Inequality => "!=",
+    StrictInequality => "!==",
+    LessThan => "<",
+    LessEqual => "<=",
+    GreaterThan => ">",
+    GreaterEqual => ">=",
+    In => "in",
+    Instanceof => "instanceof",
+    ShiftLeft => "<<",
+    ShiftRight => ">>",
+    ShiftRightZeroFill => ">>>",
+    Add => "+",
+    Subtract => "-",
+    Multiply => "*",
+    Divide => "/",
+    Remainder => "%",
+    Exponent => "**",
+    BitwiseOr => "|",
+    BitwiseXor => "^",
+    BitwiseAnd => "&",
+});
+
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
 #[repr(C)]
 pub struct UnaryExpression<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
     pub operator: UnaryOperator,
     pub argument: Expression<'a>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub parent: ExpressionParent<'a>,
 }
 
 #[derive(Clone)]
 #[repr(C)]
 pub struct TraversableUnaryExpression<'a, 't> {
+    pub span: Span,
     pub operator: UnaryOperator,
     pub argument: TraversableExpression<'a, 't>,
     pub parent: TraversableExpressionParent<'a, 't>,
 }
 
-assert_size_align_match!(UnaryExpression, TraversableUnaryExpression);
+assert_size_align_match!(
+    UnaryExpression,
+    TraversableUnaryExpression,
+    span,
+    operator,
+    argument,
+    parent
+);
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 #[repr(u8)]
@@ -232,14 +1209,1147 @@ pub enum UnaryOperator {
     Delete = 6,
 }
 
+impl UnaryOperator {
+    pub fn precedence(self) -> Precedence {
+        Precedence::Unary
+    }
+
+    /// Whether operator is a keyword (`typeof`, `void`, `delete`), which is printed with
+    /// a space between it and its argument.
+    pub fn needs_trailing_space(self) -> bool {
+        matches!(self, Self::Typeof | Self::Void | Self::Delete)
+    }
+
+    /// Whether operator comes before its argument. All unary operators currently supported are.
+    pub fn is_prefix(self) -> bool {
+        true
+    }
+}
+
+operator_strings!(UnaryOperator {
+    UnaryNegation => "-",
+    UnaryPlus => "+",
+    LogicalNot => "!",
+    BitwiseNot => "~",
+    Typeof => "typeof",
+    Void => "void",
+    Delete => "delete",
+});
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct CallExpression<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    pub callee: Expression<'a>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::json::serialize_vec")
+    )]
+    pub arguments: Vec<'a, Expression<'a>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: ExpressionParent<'a>,
+}
+
+#[repr(C)]
+pub struct TraversableCallExpression<'a, 't> {
+    pub span: Span,
+    pub callee: TraversableExpression<'a, 't>,
+    pub arguments: shared_vec!(TraversableExpression<'a, 't>),
+    pub parent: TraversableExpressionParent<'a, 't>,
+}
+
+assert_size_align_match!(
+    CallExpression,
+    TraversableCallExpression,
+    span,
+    callee,
+    arguments,
+    parent
+);
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct MemberExpression<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    pub object: Expression<'a>,
+    pub property: Expression<'a>,
+    /// `true` for `object[property]`, `false` for `object.property`
+    pub computed: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: ExpressionParent<'a>,
+}
+
+#[derive(Clone)]
+#[repr(C)]
+pub struct TraversableMemberExpression<'a, 't> {
+    pub span: Span,
+    pub object: TraversableExpression<'a, 't>,
+    pub property: TraversableExpression<'a, 't>,
+    pub computed: bool,
+    pub parent: TraversableExpressionParent<'a, 't>,
+}
+
+assert_size_align_match!(
+    MemberExpression,
+    TraversableMemberExpression,
+    span,
+    object,
+    property,
+    computed,
+    parent
+);
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct LogicalExpression<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    pub left: Expression<'a>,
+    pub operator: LogicalOperator,
+    pub right: Expression<'a>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: ExpressionParent<'a>,
+}
+
+#[derive(Clone)]
+#[repr(C)]
+pub struct TraversableLogicalExpression<'a, 't> {
+    pub span: Span,
+    pub left: TraversableExpression<'a, 't>,
+    pub operator: LogicalOperator,
+    pub right: TraversableExpression<'a, 't>,
+    pub parent: TraversableExpressionParent<'a, 't>,
+}
+
+assert_size_align_match!(
+    LogicalExpression,
+    TraversableLogicalExpression,
+    span,
+    left,
+    operator,
+    right,
+    parent
+);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[repr(u8)]
+pub enum LogicalOperator {
+    And = 0,
+    Or = 1,
+    NullishCoalescing = 2,
+}
+
+impl LogicalOperator {
+    pub fn precedence(self) -> Precedence {
+        match self {
+            Self::And => Precedence::LogicalAnd,
+            Self::Or | Self::NullishCoalescing => Precedence::LogicalOr,
+        }
+    }
+}
+
+operator_strings!(LogicalOperator {
+    And => "&&",
+    Or => "||",
+    NullishCoalescing => "??",
+});
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct ConditionalExpression<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    pub test: Expression<'a>,
+    pub consequent: Expression<'a>,
+    pub alternate: Expression<'a>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: ExpressionParent<'a>,
+}
+
+#[derive(Clone)]
+#[repr(C)]
+pub struct TraversableConditionalExpression<'a, 't> {
+    pub span: Span,
+    pub test: TraversableExpression<'a, 't>,
+    pub consequent: TraversableExpression<'a, 't>,
+    pub alternate: TraversableExpression<'a, 't>,
+    pub parent: TraversableExpressionParent<'a, 't>,
+}
+
+assert_size_align_match!(
+    ConditionalExpression,
+    TraversableConditionalExpression,
+    span,
+    test,
+    consequent,
+    alternate,
+    parent
+);
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct AssignmentExpression<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    /// Either an `Identifier` or a `MemberExpression`
+    #[cfg_attr(feature = "serde", serde(rename = "left"))]
+    pub target: Expression<'a>,
+    pub operator: AssignmentOperator,
+    #[cfg_attr(feature = "serde", serde(rename = "right"))]
+    pub value: Expression<'a>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: ExpressionParent<'a>,
+}
+
+#[derive(Clone)]
+#[repr(C)]
+pub struct TraversableAssignmentExpression<'a, 't> {
+    pub span: Span,
+    pub target: TraversableExpression<'a, 't>,
+    pub operator: AssignmentOperator,
+    pub value: TraversableExpression<'a, 't>,
+    pub parent: TraversableExpressionParent<'a, 't>,
+}
+
+assert_size_align_match!(
+    AssignmentExpression,
+    TraversableAssignmentExpression,
+    span,
+    target,
+    operator,
+    value,
+    parent
+);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[repr(u8)]
+pub enum AssignmentOperator {
+    Assign = 0,
+    AddAssign = 1,
+    SubtractAssign = 2,
+    MultiplyAssign = 3,
+    DivideAssign = 4,
+    RemainderAssign = 5,
+    LogicalAndAssign = 6,
+    LogicalOrAssign = 7,
+    NullishAssign = 8,
+}
+
+operator_strings!(AssignmentOperator {
+    Assign => "=",
+    AddAssign => "+=",
+    SubtractAssign => "-=",
+    MultiplyAssign => "*=",
+    DivideAssign => "/=",
+    RemainderAssign => "%=",
+    LogicalAndAssign => "&&=",
+    LogicalOrAssign => "||=",
+    NullishAssign => "??=",
+});
+
+/// Template literal e.g. `` `a${b}c` ``.
+///
+/// `quasis` are the raw text of the string parts, as in source (escapes are not processed),
+/// and `expressions` are the embedded expressions. Quasis and expressions alternate, starting
+/// and ending with a quasi, so there is always 1 more quasi than expressions.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct TemplateLiteral<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::json::serialize_vec")
+    )]
+    pub quasis: Vec<'a, &'a str>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::json::serialize_vec")
+    )]
+    pub expressions: Vec<'a, Expression<'a>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: ExpressionParent<'a>,
+}
+
+#[repr(C)]
+pub struct TraversableTemplateLiteral<'a, 't> {
+    pub span: Span,
+    pub quasis: Vec<'a, &'a str>,
+    pub expressions: shared_vec!(TraversableExpression<'a, 't>),
+    pub parent: TraversableExpressionParent<'a, 't>,
+}
+
+assert_size_align_match!(
+    TemplateLiteral,
+    TraversableTemplateLiteral,
+    span,
+    quasis,
+    expressions,
+    parent
+);
+
+/// Array literal e.g. `[1, , 3]`.
+///
+/// `None` in `elements` is a hole (elision), as in `[1, , 3]`.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct ArrayExpression<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::json::serialize_vec")
+    )]
+    pub elements: Vec<'a, Option<Expression<'a>>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: ExpressionParent<'a>,
+}
+
+#[repr(C)]
+pub struct TraversableArrayExpression<'a, 't> {
+    pub span: Span,
+    pub elements: SharedVec<'a, 't, Option<TraversableExpression<'a, 't>>>,
+    pub parent: TraversableExpressionParent<'a, 't>,
+}
+
+assert_size_align_match!(
+    ArrayExpression,
+    TraversableArrayExpression,
+    span,
+    elements,
+    parent
+);
+
+/// Object literal e.g. `{ a: 1, [b]: 2, c }`.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct ObjectExpression<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::json::serialize_boxes")
+    )]
+    pub properties: Vec<'a, Box<'a, Property<'a>>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: ExpressionParent<'a>,
+}
+
+#[repr(C)]
+pub struct TraversableObjectExpression<'a, 't> {
+    pub span: Span,
+    pub properties: SharedVec<'a, 't, shared_box!(TraversableProperty<'a, 't>)>,
+    pub parent: TraversableExpressionParent<'a, 't>,
+}
+
+assert_size_align_match!(
+    ObjectExpression,
+    TraversableObjectExpression,
+    span,
+    properties,
+    parent
+);
+
+/// Property in an object literal.
+///
+/// If `computed` is `false`, `key` is an identifier, string literal or numeric literal
+/// (`a` in `{ a: 1 }`). Identifier keys are property names, not references to variables.
+///
+/// `shorthand` properties (`{ c }`) have separate `key` and `value` identifiers, both with
+/// the same name and span.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct Property<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    pub key: Expression<'a>,
+    pub value: Expression<'a>,
+    pub computed: bool,
+    pub shorthand: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: PropertyParent<'a>,
+}
+
+#[repr(C)]
+pub struct TraversableProperty<'a, 't> {
+    pub span: Span,
+    pub key: TraversableExpression<'a, 't>,
+    pub value: TraversableExpression<'a, 't>,
+    pub computed: bool,
+    pub shorthand: bool,
+    pub parent: TraversablePropertyParent<'a, 't>,
+}
+
+assert_size_align_match!(
+    Property,
+    TraversableProperty,
+    span,
+    key,
+    value,
+    computed,
+    shorthand,
+    parent
+);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(C, u8)]
+pub enum PropertyParent<'a> {
+    None = 0,
+    ObjectExpression(*const ObjectExpression<'a>) = 1,
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, u8)]
+pub enum TraversablePropertyParent<'a, 't> {
+    None = 0,
+    ObjectExpression(shared_box!(TraversableObjectExpression<'a, 't>)) = 1,
+}
+
+assert_size_align_match!(PropertyParent, TraversablePropertyParent);
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct BlockStatement<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::json::serialize_vec")
+    )]
+    pub body: Vec<'a, Statement<'a>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: StatementParent<'a>,
+}
+
+#[repr(C)]
+pub struct TraversableBlockStatement<'a, 't> {
+    pub span: Span,
+    pub body: shared_vec!(TraversableStatement<'a, 't>),
+    pub parent: TraversableStatementParent<'a, 't>,
+}
+
+assert_size_align_match!(
+    BlockStatement,
+    TraversableBlockStatement,
+    span,
+    body,
+    parent
+);
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct IfStatement<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    pub test: Expression<'a>,
+    pub consequent: Statement<'a>,
+    pub alternate: Option<Statement<'a>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: StatementParent<'a>,
+}
+
+#[derive(Clone)]
+#[repr(C)]
+pub struct TraversableIfStatement<'a, 't> {
+    pub span: Span,
+    pub test: TraversableExpression<'a, 't>,
+    pub consequent: TraversableStatement<'a, 't>,
+    pub alternate: Option<TraversableStatement<'a, 't>>,
+    pub parent: TraversableStatementParent<'a, 't>,
+}
+
+assert_size_align_match!(
+    IfStatement,
+    TraversableIfStatement,
+    span,
+    test,
+    consequent,
+    alternate,
+    parent
+);
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct ReturnStatement<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    pub argument: Option<Expression<'a>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: StatementParent<'a>,
+}
+
+#[derive(Clone)]
+#[repr(C)]
+pub struct TraversableReturnStatement<'a, 't> {
+    pub span: Span,
+    pub argument: Option<TraversableExpression<'a, 't>>,
+    pub parent: TraversableStatementParent<'a, 't>,
+}
+
+assert_size_align_match!(
+    ReturnStatement,
+    TraversableReturnStatement,
+    span,
+    argument,
+    parent
+);
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct VariableDeclaration<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    pub kind: VarKind,
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::json::serialize_boxes")
+    )]
+    pub declarations: Vec<'a, Box<'a, VariableDeclarator<'a>>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: StatementParent<'a>,
+}
+
+#[repr(C)]
+pub struct TraversableVariableDeclaration<'a, 't> {
+    pub span: Span,
+    pub kind: VarKind,
+    pub declarations: SharedVec<'a, 't, shared_box!(TraversableVariableDeclarator<'a, 't>)>,
+    pub parent: TraversableStatementParent<'a, 't>,
+}
+
+assert_size_align_match!(
+    VariableDeclaration,
+    TraversableVariableDeclaration,
+    span,
+    kind,
+    declarations,
+    parent
+);
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(u8)]
+pub enum VarKind {
+    #[cfg_attr(feature = "serde", serde(rename = "var"))]
+    Var = 0,
+    #[cfg_attr(feature = "serde", serde(rename = "let"))]
+    Let = 1,
+    #[cfg_attr(feature = "serde", serde(rename = "const"))]
+    Const = 2,
+}
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize), serde(tag = "type"))]
+#[repr(C)]
+pub struct VariableDeclarator<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    pub id: BindingIdentifier<'a>,
+    pub init: Option<Expression<'a>>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub parent: VariableDeclaratorParent<'a>,
+}
+
+#[derive(Clone)]
+#[repr(C)]
+pub struct TraversableVariableDeclarator<'a, 't> {
+    pub span: Span,
+    pub id: BindingIdentifier<'a>,
+    pub init: Option<TraversableExpression<'a, 't>>,
+    pub parent: TraversableVariableDeclaratorParent<'a, 't>,
+}
+
+assert_size_align_match!(
+    VariableDeclarator,
+    TraversableVariableDeclarator,
+    span,
+    id,
+    init,
+    parent
+);
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[repr(C, u8)]
+pub enum VariableDeclaratorParent<'a> {
+    None = 0,
+    VariableDeclaration(*const VariableDeclaration<'a>) = 1,
+}
+
+#[derive(Clone, Copy)]
+#[repr(C, u8)]
+pub enum TraversableVariableDeclaratorParent<'a, 't> {
+    None = 0,
+    VariableDeclaration(shared_box!(TraversableVariableDeclaration<'a, 't>)) = 1,
+}
+
+assert_size_align_match!(
+    VariableDeclaratorParent,
+    TraversableVariableDeclaratorParent
+);
+
+/// Ancestor of a node in traversable AST.
+///
+/// Each ancestor is the parent link of the node below it, so records both the parent node,
+/// and which of the parent's fields contains the node below.
+#[derive(Clone, Copy)]
+pub enum TraversableAncestorKind<'a, 't> {
+    ExpressionParent(TraversableExpressionParent<'a, 't>),
+    StatementParent(TraversableStatementParent<'a, 't>),
+    VariableDeclaratorParent(TraversableVariableDeclaratorParent<'a, 't>),
+    PropertyParent(TraversablePropertyParent<'a, 't>),
+}
+
+/// Iterator over ancestors of a node in traversable AST, from parent up to the `Program` root.
+/// Created by `TraversableExpression::ancestors`.
+pub struct TraversableAncestors<'a, 't, 'b> {
+    next: Option<TraversableAncestorKind<'a, 't>>,
+    tk: &'b Token<'t>,
+}
+
+impl<'a, 't, 'b> Iterator for TraversableAncestors<'a, 't, 'b> {
+    type Item = TraversableAncestorKind<'a, 't>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use TraversableAncestorKind as Kind;
+        use TraversableExpressionParent as EP;
+        use TraversablePropertyParent as PP;
+        use TraversableStatementParent as SP;
+        use TraversableVariableDeclaratorParent as VDP;
+
+        let ancestor = self.next.take()?;
+        let tk = self.tk;
+        self.next = match ancestor {
+            Kind::ExpressionParent(parent) => match parent {
+                EP::None => return None,
+                EP::ExpressionStatement(expr_stmt) => {
+                    Some(Kind::StatementParent(expr_stmt.borrow(tk).parent))
+                }
+                EP::IfStatementTest(if_stmt) => {
+                    Some(Kind::StatementParent(if_stmt.borrow(tk).parent))
+                }
+                EP::ReturnStatementArgument(return_stmt) => {
+                    Some(Kind::StatementParent(return_stmt.borrow(tk).parent))
+                }
+                EP::VariableDeclaratorInit(declarator) => {
+                    Some(Kind::VariableDeclaratorParent(declarator.borrow(tk).parent))
+                }
+                EP::PropertyKey(prop) | EP::PropertyValue(prop) => {
+                    Some(Kind::PropertyParent(prop.borrow(tk).parent))
+                }
+                // All other parents are expressions
+                _ => parent
+                    .as_expression()
+                    .map(|expr| Kind::ExpressionParent(expr.parent(tk))),
+            },
+            Kind::StatementParent(parent) => match parent {
+                SP::None => return None,
+                // Reached root
+                SP::Program(_) => None,
+                SP::BlockStatement(block) => Some(Kind::StatementParent(block.borrow(tk).parent)),
+                SP::IfStatementConsequent(if_stmt) | SP::IfStatementAlternate(if_stmt) => {
+                    Some(Kind::StatementParent(if_stmt.borrow(tk).parent))
+                }
+            },
+            Kind::VariableDeclaratorParent(parent) => match parent {
+                VDP::None => return None,
+                VDP::VariableDeclaration(var_decl) => {
+                    Some(Kind::StatementParent(var_decl.borrow(tk).parent))
+                }
+            },
+            Kind::PropertyParent(parent) => match parent {
+                PP::None => return None,
+                PP::ObjectExpression(object_expr) => {
+                    Some(Kind::ExpressionParent(object_expr.borrow(tk).parent))
+                }
+            },
+        };
+        Some(ancestor)
+    }
+}
+
+/// Name of a variable being declared.
+/// Contains no references to other nodes, so is shared by both versions of the AST.
+#[derive(Clone, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize),
+    serde(tag = "type", rename = "Identifier")
+)]
+#[repr(C)]
+pub struct BindingIdentifier<'a> {
+    #[cfg_attr(feature = "serde", serde(flatten))]
+    pub span: Span,
+    pub name: &'a str,
+}
+
 pub mod traversable {
     pub type Program<'a, 't> = super::TraversableProgram<'a, 't>;
     pub type Statement<'a, 't> = super::TraversableStatement<'a, 't>;
     pub type ExpressionStatement<'a, 't> = super::TraversableExpressionStatement<'a, 't>;
     pub type Expression<'a, 't> = super::TraversableExpression<'a, 't>;
     pub type ExpressionParent<'a, 't> = super::TraversableExpressionParent<'a, 't>;
+    pub type StatementParent<'a, 't> = super::TraversableStatementParent<'a, 't>;
+    pub type AncestorKind<'a, 't> = super::TraversableAncestorKind<'a, 't>;
+    pub type Ancestors<'a, 't, 'b> = super::TraversableAncestors<'a, 't, 'b>;
     pub type IdentifierReference<'a, 't> = super::TraversableIdentifierReference<'a, 't>;
     pub type StringLiteral<'a, 't> = super::TraversableStringLiteral<'a, 't>;
+    pub type NumericLiteral<'a, 't> = super::TraversableNumericLiteral<'a, 't>;
+    pub type BooleanLiteral<'a, 't> = super::TraversableBooleanLiteral<'a, 't>;
+    pub type NullLiteral<'a, 't> = super::TraversableNullLiteral<'a, 't>;
     pub type BinaryExpression<'a, 't> = super::TraversableBinaryExpression<'a, 't>;
     pub type UnaryExpression<'a, 't> = super::TraversableUnaryExpression<'a, 't>;
+    pub type CallExpression<'a, 't> = super::TraversableCallExpression<'a, 't>;
+    pub type MemberExpression<'a, 't> = super::TraversableMemberExpression<'a, 't>;
+    pub type LogicalExpression<'a, 't> = super::TraversableLogicalExpression<'a, 't>;
+    pub type ConditionalExpression<'a, 't> = super::TraversableConditionalExpression<'a, 't>;
+    pub type AssignmentExpression<'a, 't> = super::TraversableAssignmentExpression<'a, 't>;
+    pub type TemplateLiteral<'a, 't> = super::TraversableTemplateLiteral<'a, 't>;
+    pub type ArrayExpression<'a, 't> = super::TraversableArrayExpression<'a, 't>;
+    pub type ObjectExpression<'a, 't> = super::TraversableObjectExpression<'a, 't>;
+    pub type Property<'a, 't> = super::TraversableProperty<'a, 't>;
+    pub type BlockStatement<'a, 't> = super::TraversableBlockStatement<'a, 't>;
+    pub type IfStatement<'a, 't> = super::TraversableIfStatement<'a, 't>;
+    pub type ReturnStatement<'a, 't> = super::TraversableReturnStatement<'a, 't>;
+    pub type VariableDeclaration<'a, 't> = super::TraversableVariableDeclaration<'a, 't>;
+    pub type VariableDeclarator<'a, 't> = super::TraversableVariableDeclarator<'a, 't>;
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use oxc_allocator::Allocator;
+
+    use super::*;
+    use crate::{
+        builder::AstBuilder,
+        cell::gcell,
+        parents::validate_tree,
+        parser::parse_str,
+        print::Printer,
+        traverse::{transform, Traverse, WalkControl},
+    };
+
+    /// Check every spelling parses to an operator which prints with the same spelling.
+    fn assert_round_trip<T: FromStr<Err = OperatorParseError>>(
+        spellings: &[&str],
+        as_str: fn(T) -> &'static str,
+    ) {
+        for &spelling in spellings {
+            let operator = T::from_str(spelling).unwrap();
+            assert_eq!(as_str(operator), spelling);
+        }
+    }
+
+    #[test]
+    fn operator_round_trip() {
+        assert_round_trip(
+            &[
+                "==",
+                "===",
+                "!=",
+                "!==",
+                "<",
+                "<=",
+                ">",
+                ">=",
+                "in",
+                "instanceof",
+                "<<",
+                ">>",
+                ">>>",
+                "+",
+                "-",
+                "*",
+                "/",
+                "%",
+                "**",
+                "|",
+                "^",
+                "&",
+            ],
+            BinaryOperator::as_str,
+        );
+        assert_round_trip(
+            &["-", "+", "!", "~", "typeof", "void", "delete"],
+            UnaryOperator::as_str,
+        );
+        assert_round_trip(&["&&", "||", "??"], LogicalOperator::as_str);
+        assert_round_trip(
+            &["=", "+=", "-=", "*=", "/=", "%=", "&&=", "||=", "??="],
+            AssignmentOperator::as_str,
+        );
+    }
+
+    #[test]
+    fn replace_identifier_with_string_literal() {
+        let alloc = Allocator::default();
+        let ast = AstBuilder::new(&alloc);
+        let program = parse_str("a === b;", &alloc).unwrap();
+        let Statement::ExpressionStatement(expr_stmt) = &mut program.body[0] else {
+            unreachable!();
+        };
+        let Expression::BinaryExpression(bin_expr) = &mut expr_stmt.expression else {
+            unreachable!();
+        };
+        let bin_expr_ptr = &**bin_expr as *const _;
+        let old = bin_expr
+            .left
+            .replace_with(ast.string_literal(Span::default(), "replaced", None));
+        assert!(matches!(old, Expression::Identifier(id) if id.name == "a"));
+        assert_eq!(
+            bin_expr.left.parent(),
+            ExpressionParent::BinaryExpressionLeft(bin_expr_ptr)
+        );
+        assert_eq!(Printer::print(program), "'replaced' === b;");
+    }
+
+    /// Replaces identifiers on left side of binary expressions with a string literal.
+    struct ReplaceLeft<'a> {
+        alloc: &'a Allocator,
+    }
+
+    impl<'a, 't> Traverse<'a, 't> for ReplaceLeft<'a> {
+        fn exit_binary_expression(
+            &mut self,
+            bin_expr: &gcell!(TraversableBinaryExpression<'a, 't>),
+            tk: &mut Token<'t>,
+        ) {
+            let left = bin_expr.borrow(tk).left.clone();
+            if let TraversableExpression::Identifier(_) = left {
+                let str_lit = self.alloc.alloc(GCell::new(TraversableStringLiteral {
+                    span: Span::default(),
+                    value: "replaced",
+                    raw: None,
+                    parent: TraversableExpressionParent::None,
+                }));
+                let old = left.replace_with(TraversableExpression::StringLiteral(str_lit), tk);
+                assert!(old.is_same(&left));
+                assert!(matches!(
+                    str_lit.borrow(tk).parent,
+                    TraversableExpressionParent::BinaryExpressionLeft(parent)
+                        if core::ptr::eq(parent, bin_expr)
+                ));
+            }
+        }
+    }
+
+    #[test]
+    fn traversable_replace_identifier_with_string_literal() {
+        let alloc = Allocator::default();
+        let program = parse_str("a === b; -(c === d);", &alloc).unwrap();
+        transform(&mut ReplaceLeft { alloc: &alloc }, program);
+        assert_eq!(
+            Printer::print(program),
+            "'replaced' === b;-('replaced' === d);"
+        );
+    }
+
+    #[test]
+    fn unknown_operator() {
+        let err = BinaryOperator::from_str("<=>").unwrap_err();
+        assert_eq!(err.to_string(), "Unknown operator `<=>`");
+        assert!(UnaryOperator::from_str("===").is_err());
+    }
+
+    /// Records name of each identifier, and type of its parent expression, if it has one.
+    #[derive(Default)]
+    struct ParentExpressions {
+        found: std::vec::Vec<(String, Option<&'static str>)>,
+    }
+
+    impl<'a, 't> Traverse<'a, 't> for ParentExpressions {
+        fn visit_expression(
+            &mut self,
+            expr: &TraversableExpression<'a, 't>,
+            tk: &mut Token<'t>,
+        ) -> WalkControl {
+            match expr {
+                TraversableExpression::Identifier(id) => {
+                    let parent = expr.parent_expression(tk).map(|parent| match parent {
+                        TraversableExpression::BinaryExpression(_) => "BinaryExpression",
+                        TraversableExpression::UnaryExpression(_) => "UnaryExpression",
+                        TraversableExpression::CallExpression(_) => "CallExpression",
+                        _ => "other",
+                    });
+                    self.found.push((id.borrow(tk).name.to_string(), parent));
+                }
+                TraversableExpression::UnaryExpression(unary_expr) => {
+                    let argument = unary_expr.borrow(tk).argument.clone();
+                    assert!(argument.parent_expression(tk).unwrap().is_same(expr));
+                }
+                _ => {}
+            }
+            self.walk_expression(expr, tk)
+        }
+    }
+
+    #[test]
+    fn traversable_parent_expression() {
+        let alloc = Allocator::default();
+        let program = parse_str("a === -b; f(c); if (d) { e; }", &alloc).unwrap();
+        let mut collector = ParentExpressions::default();
+        transform(&mut collector, program);
+        let found = collector
+            .found
+            .iter()
+            .map(|(name, parent)| (name.as_str(), *parent))
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                ("a", Some("BinaryExpression")),
+                ("b", Some("UnaryExpression")),
+                ("f", Some("CallExpression")),
+                ("c", Some("CallExpression")),
+                // Parents are statements, not expressions
+                ("d", None),
+                ("e", None),
+            ]
+        );
+    }
+
+    /// Records name of each identifier, and its ancestors.
+    #[derive(Default)]
+    struct CollectAncestors {
+        found: std::vec::Vec<(String, std::vec::Vec<&'static str>)>,
+    }
+
+    impl<'a, 't> Traverse<'a, 't> for CollectAncestors {
+        fn visit_expression(
+            &mut self,
+            expr: &TraversableExpression<'a, 't>,
+            tk: &mut Token<'t>,
+        ) -> WalkControl {
+            use TraversableAncestorKind as Kind;
+            use TraversableExpressionParent as EP;
+            use TraversableStatementParent as SP;
+
+            if let TraversableExpression::Identifier(id) = expr {
+                let ancestors = expr
+                    .ancestors(tk)
+                    .map(|ancestor| match ancestor {
+                        Kind::ExpressionParent(EP::BinaryExpressionLeft(_)) => {
+                            "BinaryExpressionLeft"
+                        }
+                        Kind::ExpressionParent(EP::BinaryExpressionRight(_)) => {
+                            "BinaryExpressionRight"
+                        }
+                        Kind::ExpressionParent(EP::UnaryExpression(_)) => "UnaryExpression",
+                        Kind::ExpressionParent(EP::ExpressionStatement(_)) => "ExpressionStatement",
+                        Kind::ExpressionParent(EP::IfStatementTest(_)) => "IfStatementTest",
+                        Kind::ExpressionParent(EP::VariableDeclaratorInit(_)) => {
+                            "VariableDeclaratorInit"
+                        }
+                        Kind::StatementParent(SP::Program(_)) => "Program",
+                        Kind::StatementParent(SP::BlockStatement(_)) => "BlockStatement",
+                        Kind::StatementParent(SP::IfStatementConsequent(_)) => {
+                            "IfStatementConsequent"
+                        }
+                        Kind::VariableDeclaratorParent(_) => "VariableDeclaration",
+                        _ => "other",
+                    })
+                    .collect();
+                self.found.push((id.borrow(tk).name.to_string(), ancestors));
+            }
+            self.walk_expression(expr, tk)
+        }
+    }
+
+    #[test]
+    fn traversable_ancestors() {
+        let alloc = Allocator::default();
+        let program = parse_str("if (x) { a === -b; } let y = z;", &alloc).unwrap();
+        let mut collector = CollectAncestors::default();
+        transform(&mut collector, program);
+        let found = collector
+            .found
+            .iter()
+            .map(|(name, ancestors)| (name.as_str(), ancestors.as_slice()))
+            .collect::<std::vec::Vec<_>>();
+        assert_eq!(
+            found,
+            [
+                ("x", &["IfStatementTest", "Program"][..]),
+                (
+                    "a",
+                    &[
+                        "BinaryExpressionLeft",
+                        "ExpressionStatement",
+                        "BlockStatement",
+                        "IfStatementConsequent",
+                        "Program"
+                    ]
+                ),
+                (
+                    "b",
+                    &[
+                        "UnaryExpression",
+                        "BinaryExpressionRight",
+                        "ExpressionStatement",
+                        "BlockStatement",
+                        "IfStatementConsequent",
+                        "Program"
+                    ]
+                ),
+                (
+                    "z",
+                    &["VariableDeclaratorInit", "VariableDeclaration", "Program"]
+                ),
+            ]
+        );
+    }
+
+    /// Records type of each top-level statement, on entering the `Program`.
+    #[derive(Default)]
+    struct CollectStatements {
+        found: std::vec::Vec<&'static str>,
+    }
+
+    impl<'a, 't> Traverse<'a, 't> for CollectStatements {
+        fn enter_program(
+            &mut self,
+            program: &gcell!(TraversableProgram<'a, 't>),
+            tk: &mut Token<'t>,
+        ) -> WalkControl {
+            self.found = program
+                .statements(tk)
+                .map(|stmt| match stmt {
+                    TraversableStatement::ExpressionStatement(_) => "ExpressionStatement",
+                    TraversableStatement::BlockStatement(_) => "BlockStatement",
+                    TraversableStatement::IfStatement(_) => "IfStatement",
+                    TraversableStatement::ReturnStatement(_) => "ReturnStatement",
+                    TraversableStatement::VariableDeclaration(_) => "VariableDeclaration",
+                })
+                .collect();
+            WalkControl::Skip
+        }
+    }
+
+    #[test]
+    fn traversable_statements() {
+        let alloc = Allocator::default();
+        let program = parse_str("a; { b; c; } if (d) e; let f;", &alloc).unwrap();
+        let mut collector = CollectStatements::default();
+        transform(&mut collector, program);
+        // Only top-level statements, not statements nested in them
+        assert_eq!(
+            collector.found,
+            [
+                "ExpressionStatement",
+                "BlockStatement",
+                "IfStatement",
+                "VariableDeclaration"
+            ]
+        );
+    }
+
+    /// Inserts and removes statements while visiting them, and records the names of
+    /// statements and identifiers visited.
+    struct EditStatements<'a> {
+        alloc: &'a Allocator,
+        statements: std::vec::Vec<&'a str>,
+        identifiers: std::vec::Vec<&'a str>,
+    }
+
+    impl<'a> EditStatements<'a> {
+        /// Create expression statement `name;`.
+        fn statement<'t>(&self, name: &'a str, tk: &mut Token<'t>) -> TraversableStatement<'a, 't> {
+            let id = self.alloc.alloc(GCell::new(TraversableIdentifierReference {
+                span: Span::default(),
+                name,
+                parent: TraversableExpressionParent::None,
+            }));
+            let expr = TraversableExpression::Identifier(id);
+            let expr_stmt = self.alloc.alloc(GCell::new(TraversableExpressionStatement {
+                span: Span::default(),
+                expression: expr.clone(),
+                parent: TraversableStatementParent::None,
+            }));
+            expr.set_parent(
+                TraversableExpressionParent::ExpressionStatement(expr_stmt),
+                tk,
+            );
+            TraversableStatement::ExpressionStatement(expr_stmt)
+        }
+    }
+
+    impl<'a, 't> Traverse<'a, 't> for EditStatements<'a> {
+        fn visit_statement(
+            &mut self,
+            stmt: &TraversableStatement<'a, 't>,
+            tk: &mut Token<'t>,
+        ) -> WalkControl {
+            let name = match stmt {
+                TraversableStatement::ExpressionStatement(expr_stmt) => {
+                    match &expr_stmt.borrow(tk).expression {
+                        TraversableExpression::Identifier(id) => id.borrow(tk).name,
+                        _ => unreachable!(),
+                    }
+                }
+                _ => "{}",
+            };
+            self.statements.push(name);
+            match name {
+                "a" => {
+                    stmt.insert_before(self.statement("before_a", tk), tk);
+                    stmt.insert_after(self.statement("after_a", tk), tk);
+                }
+                "b" | "e" => stmt.remove(tk),
+                "d" => stmt.insert_before(self.statement("before_d", tk), tk),
+                _ => {}
+            }
+            self.walk_statement(stmt, tk)
+        }
+
+        fn enter_identifier_reference(
+            &mut self,
+            id: &gcell!(TraversableIdentifierReference<'a, 't>),
+            tk: &mut Token<'t>,
+        ) -> WalkControl {
+            self.identifiers.push(id.borrow(tk).name);
+            WalkControl::Continue
+        }
+    }
+
+    #[test]
+    fn traversable_insert_and_remove_statements() {
+        let alloc = Allocator::default();
+        let program = parse_str("a; b; c; { d; e; f; }", &alloc).unwrap();
+        let mut editor = EditStatements {
+            alloc: &alloc,
+            statements: vec![],
+            identifiers: vec![],
+        };
+        transform(&mut editor, program);
+        // Statements inserted before are skipped, statements inserted after are visited.
+        // After removing a statement, its descendants are still visited, and then the walk
+        // continues with the statement which followed it.
+        assert_eq!(
+            editor.statements,
+            ["a", "after_a", "b", "c", "{}", "d", "e", "f"]
+        );
+        assert_eq!(
+            editor.identifiers,
+            ["a", "after_a", "b", "c", "d", "e", "f"]
+        );
+        assert_eq!(
+            Printer::print(program),
+            "before_a;a;after_a;c;{ before_d; d; f; }"
+        );
+        assert!(validate_tree(program).is_ok());
+    }
 }
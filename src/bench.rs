@@ -0,0 +1,239 @@
+//! Benchmarks comparing a transform on the standard AST via `VisitMut`
+//! against the same transform on the traversable AST via `Traverse`,
+//! and traversing / printing large synthetic ASTs.
+//!
+//! Requires nightly: `cargo +nightly bench --features bench`
+
+extern crate test;
+
+use std::mem;
+
+use oxc_allocator::Allocator;
+use test::{black_box, Bencher};
+
+use crate::{
+    ast::{
+        BinaryExpression, BinaryOperator, Expression, ExpressionParent, Program, Span,
+        UnaryOperator,
+    },
+    builder::AstBuilder,
+    parser,
+    print::Printer,
+    stats::node_type_counts,
+    transforms::TransformTypeof,
+    traverse::transform,
+    visit_mut::VisitMut,
+};
+
+const SOURCE: &str = "typeof foo === 'object'";
+
+/// Equivalent of `TransformTypeof` for the standard AST.
+struct TransformTypeofMut;
+
+impl<'a> VisitMut<'a> for TransformTypeofMut {
+    fn visit_binary_expression(&mut self, bin_expr: &mut BinaryExpression<'a>) {
+        self.walk_binary_expression(bin_expr);
+        if matches!(
+            bin_expr.operator,
+            BinaryOperator::Equality | BinaryOperator::StrictEquality
+        ) && matches!(
+            &bin_expr.left,
+            Expression::UnaryExpression(unary_expr) if unary_expr.operator == UnaryOperator::Typeof
+        ) && matches!(bin_expr.right, Expression::StringLiteral(_))
+        {
+            mem::swap(&mut bin_expr.left, &mut bin_expr.right);
+            let bin_expr_ptr = &*bin_expr as *const _;
+            bin_expr
+                .left
+                .set_parent(ExpressionParent::BinaryExpressionLeft(bin_expr_ptr));
+            bin_expr
+                .right
+                .set_parent(ExpressionParent::BinaryExpressionRight(bin_expr_ptr));
+        }
+    }
+}
+
+/// Baseline: parsing only. Subtract from the other benchmarks to get cost of the transform.
+#[bench]
+fn parse(b: &mut Bencher) {
+    b.iter(|| {
+        let alloc = Allocator::default();
+        let program = parser::parse_str(black_box(SOURCE), &alloc).unwrap();
+        black_box(program);
+    });
+}
+
+#[bench]
+fn transform_typeof_visit_mut(b: &mut Bencher) {
+    b.iter(|| {
+        let alloc = Allocator::default();
+        let program = parser::parse_str(black_box(SOURCE), &alloc).unwrap();
+        TransformTypeofMut.visit_program(program);
+        black_box(program);
+    });
+}
+
+#[bench]
+fn transform_typeof_traverse(b: &mut Bencher) {
+    b.iter(|| {
+        let alloc = Allocator::default();
+        let program = parser::parse_str(black_box(SOURCE), &alloc).unwrap();
+        transform(&mut TransformTypeof, program);
+        black_box(program);
+    });
+}
+
+/// Build AST for `typeof typeof ... foo;` with `n` nested unary expressions.
+fn build_deep_chain<'a>(n: usize, alloc: &'a Allocator) -> &'a mut Program<'a> {
+    let ast = AstBuilder::new(alloc);
+    let span = Span::default();
+    let mut expr = ast.identifier_reference(span, "foo");
+    for _ in 0..n {
+        expr = ast.unary_expression(span, UnaryOperator::Typeof, expr);
+    }
+    let mut body = ast.vec();
+    body.push(ast.expression_statement(span, expr));
+    ast.program(body)
+}
+
+/// Build AST for `foo === foo === ... foo;` with `n` binary expressions.
+fn build_wide_binary<'a>(n: usize, alloc: &'a Allocator) -> &'a mut Program<'a> {
+    let ast = AstBuilder::new(alloc);
+    let span = Span::default();
+    let mut expr = ast.identifier_reference(span, "foo");
+    for _ in 0..n {
+        let right = ast.identifier_reference(span, "foo");
+        expr = ast.binary_expression(span, expr, BinaryOperator::StrictEquality, right);
+    }
+    let mut body = ast.vec();
+    body.push(ast.expression_statement(span, expr));
+    ast.program(body)
+}
+
+// Visitors and printer recurse once per level of nesting, so 1k and 10k sizes run on the thread
+// running the benchmark, but 100k sizes need a bigger stack. See `with_big_stack`.
+
+/// Stack size for benchmarks on 100k-node ASTs.
+/// Only the pages which are actually used are allocated, so this can be generous.
+const BIG_STACK_SIZE: usize = 1024 * 1024 * 1024;
+
+/// Run benchmark `f` on a thread with a stack big enough to visit deeply nested ASTs.
+fn with_big_stack(b: &mut Bencher, f: impl FnOnce(&mut Bencher) + Send) {
+    std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .stack_size(BIG_STACK_SIZE)
+            .spawn_scoped(scope, || f(b))
+            .unwrap()
+            .join()
+            .unwrap();
+    });
+}
+
+fn bench_visit(b: &mut Bencher, build: fn(usize, &Allocator) -> &mut Program, n: usize) {
+    let alloc = Allocator::default();
+    let program = build(n, &alloc);
+    b.iter(|| black_box(node_type_counts(program)));
+}
+
+fn bench_traverse(b: &mut Bencher, build: fn(usize, &Allocator) -> &mut Program, n: usize) {
+    let alloc = Allocator::default();
+    let program = build(n, &alloc);
+    // `TransformTypeof` does not alter these ASTs, so can be run repeatedly on the same one
+    b.iter(|| transform(&mut TransformTypeof, black_box(&mut *program)));
+}
+
+fn bench_print(b: &mut Bencher, build: fn(usize, &Allocator) -> &mut Program, n: usize) {
+    let alloc = Allocator::default();
+    let program = build(n, &alloc);
+    b.iter(|| black_box(Printer::print(program)));
+}
+
+#[bench]
+fn deep_chain_visit_1k(b: &mut Bencher) {
+    bench_visit(b, build_deep_chain, 1_000);
+}
+
+#[bench]
+fn deep_chain_visit_10k(b: &mut Bencher) {
+    bench_visit(b, build_deep_chain, 10_000);
+}
+
+#[bench]
+fn deep_chain_visit_100k(b: &mut Bencher) {
+    with_big_stack(b, |b| bench_visit(b, build_deep_chain, 100_000));
+}
+
+#[bench]
+fn deep_chain_traverse_1k(b: &mut Bencher) {
+    bench_traverse(b, build_deep_chain, 1_000);
+}
+
+#[bench]
+fn deep_chain_traverse_10k(b: &mut Bencher) {
+    bench_traverse(b, build_deep_chain, 10_000);
+}
+
+#[bench]
+fn deep_chain_traverse_100k(b: &mut Bencher) {
+    with_big_stack(b, |b| bench_traverse(b, build_deep_chain, 100_000));
+}
+
+#[bench]
+fn deep_chain_print_1k(b: &mut Bencher) {
+    bench_print(b, build_deep_chain, 1_000);
+}
+
+#[bench]
+fn deep_chain_print_10k(b: &mut Bencher) {
+    bench_print(b, build_deep_chain, 10_000);
+}
+
+#[bench]
+fn deep_chain_print_100k(b: &mut Bencher) {
+    with_big_stack(b, |b| bench_print(b, build_deep_chain, 100_000));
+}
+
+#[bench]
+fn wide_binary_visit_1k(b: &mut Bencher) {
+    bench_visit(b, build_wide_binary, 1_000);
+}
+
+#[bench]
+fn wide_binary_visit_10k(b: &mut Bencher) {
+    bench_visit(b, build_wide_binary, 10_000);
+}
+
+#[bench]
+fn wide_binary_visit_100k(b: &mut Bencher) {
+    with_big_stack(b, |b| bench_visit(b, build_wide_binary, 100_000));
+}
+
+#[bench]
+fn wide_binary_traverse_1k(b: &mut Bencher) {
+    bench_traverse(b, build_wide_binary, 1_000);
+}
+
+#[bench]
+fn wide_binary_traverse_10k(b: &mut Bencher) {
+    bench_traverse(b, build_wide_binary, 10_000);
+}
+
+#[bench]
+fn wide_binary_traverse_100k(b: &mut Bencher) {
+    with_big_stack(b, |b| bench_traverse(b, build_wide_binary, 100_000));
+}
+
+#[bench]
+fn wide_binary_print_1k(b: &mut Bencher) {
+    bench_print(b, build_wide_binary, 1_000);
+}
+
+#[bench]
+fn wide_binary_print_10k(b: &mut Bencher) {
+    bench_print(b, build_wide_binary, 10_000);
+}
+
+#[bench]
+fn wide_binary_print_100k(b: &mut Bencher) {
+    with_big_stack(b, |b| bench_print(b, build_wide_binary, 100_000));
+}
@@ -0,0 +1,637 @@
+use oxc_allocator::{Allocator, Box, Vec};
+
+use crate::ast::{
+    ArrayExpression, AssignmentExpression, AssignmentOperator, BinaryExpression, BinaryOperator,
+    BindingIdentifier, BlockStatement, BooleanLiteral, CallExpression, ConditionalExpression,
+    Expression, ExpressionParent, ExpressionStatement, IdentifierReference, IfStatement,
+    LogicalExpression, LogicalOperator, MemberExpression, NullLiteral, NumericLiteral,
+    ObjectExpression, Program, Property, PropertyParent, ReturnStatement, Span, Statement,
+    StatementParent, StringLiteral, TemplateLiteral, UnaryExpression, UnaryOperator, VarKind,
+    VariableDeclaration, VariableDeclarator, VariableDeclaratorParent,
+};
+
+/// Builder for AST nodes.
+///
+/// Each method allocates a node in the arena and returns it.
+/// "Back-links" from the new node's children to the new node are set automatically.
+/// The new node's own `parent` is left as `None`, to be set when it is inserted into its parent
+/// (by a later builder call, or `Expression::set_parent` / `Statement::set_parent`).
+#[derive(Clone, Copy)]
+pub struct AstBuilder<'a> {
+    pub alloc: &'a Allocator,
+}
+
+impl<'a> AstBuilder<'a> {
+    pub fn new(alloc: &'a Allocator) -> Self {
+        Self { alloc }
+    }
+
+    #[inline]
+    pub fn vec<T>(self) -> Vec<'a, T> {
+        Vec::new_in(self.alloc)
+    }
+
+    #[inline]
+    fn alloc<T>(self, value: T) -> Box<'a, T> {
+        Box(self.alloc.alloc(value))
+    }
+
+    /// Create `Program`. This is the root of the AST, so it's returned as a `&mut` reference.
+    pub fn program(self, body: Vec<'a, Statement<'a>>) -> &'a mut Program<'a> {
+        let program = self.alloc.alloc(Program {
+            body,
+            comments: self.vec(),
+        });
+        let program_ptr = program as *const _;
+        for stmt in program.body.iter_mut() {
+            stmt.set_parent(StatementParent::Program(program_ptr));
+        }
+        program
+    }
+
+    pub fn expression_statement(self, span: Span, expression: Expression<'a>) -> Statement<'a> {
+        let mut expr_stmt = self.alloc(ExpressionStatement {
+            span,
+            expression,
+            parent: StatementParent::None,
+        });
+        let expr_stmt_ptr = &*expr_stmt as *const _;
+        expr_stmt
+            .expression
+            .set_parent(ExpressionParent::ExpressionStatement(expr_stmt_ptr));
+        Statement::ExpressionStatement(expr_stmt)
+    }
+
+    pub fn block_statement(self, span: Span, body: Vec<'a, Statement<'a>>) -> Statement<'a> {
+        let mut block = self.alloc(BlockStatement {
+            span,
+            body,
+            parent: StatementParent::None,
+        });
+        let block_ptr = &*block as *const _;
+        for stmt in block.body.iter_mut() {
+            stmt.set_parent(StatementParent::BlockStatement(block_ptr));
+        }
+        Statement::BlockStatement(block)
+    }
+
+    /// Create `BlockStatement` with body collected from `stmts` into a `Vec` in the arena.
+    /// If `stmts` is empty, the block is empty (`{}`).
+    pub fn block(
+        self,
+        span: Span,
+        stmts: impl IntoIterator<Item = Statement<'a>>,
+    ) -> Statement<'a> {
+        let mut body = self.vec();
+        body.extend(stmts);
+        self.block_statement(span, body)
+    }
+
+    pub fn if_statement(
+        self,
+        span: Span,
+        test: Expression<'a>,
+        consequent: Statement<'a>,
+        alternate: Option<Statement<'a>>,
+    ) -> Statement<'a> {
+        let mut if_stmt = self.alloc(IfStatement {
+            span,
+            test,
+            consequent,
+            alternate,
+            parent: StatementParent::None,
+        });
+        let if_stmt_ptr = &*if_stmt as *const _;
+        if_stmt
+            .test
+            .set_parent(ExpressionParent::IfStatementTest(if_stmt_ptr));
+        if_stmt
+            .consequent
+            .set_parent(StatementParent::IfStatementConsequent(if_stmt_ptr));
+        if let Some(alternate) = &mut if_stmt.alternate {
+            alternate.set_parent(StatementParent::IfStatementAlternate(if_stmt_ptr));
+        }
+        Statement::IfStatement(if_stmt)
+    }
+
+    pub fn return_statement(self, span: Span, argument: Option<Expression<'a>>) -> Statement<'a> {
+        let mut return_stmt = self.alloc(ReturnStatement {
+            span,
+            argument,
+            parent: StatementParent::None,
+        });
+        let return_stmt_ptr = &*return_stmt as *const _;
+        if let Some(argument) = &mut return_stmt.argument {
+            argument.set_parent(ExpressionParent::ReturnStatementArgument(return_stmt_ptr));
+        }
+        Statement::ReturnStatement(return_stmt)
+    }
+
+    pub fn variable_declaration(
+        self,
+        span: Span,
+        kind: VarKind,
+        declarations: Vec<'a, Box<'a, VariableDeclarator<'a>>>,
+    ) -> Statement<'a> {
+        let mut var_decl = self.alloc(VariableDeclaration {
+            span,
+            kind,
+            declarations,
+            parent: StatementParent::None,
+        });
+        let var_decl_ptr = &*var_decl as *const _;
+        for declarator in var_decl.declarations.iter_mut() {
+            declarator.parent = VariableDeclaratorParent::VariableDeclaration(var_decl_ptr);
+        }
+        Statement::VariableDeclaration(var_decl)
+    }
+
+    pub fn variable_declarator(
+        self,
+        span: Span,
+        id: BindingIdentifier<'a>,
+        init: Option<Expression<'a>>,
+    ) -> Box<'a, VariableDeclarator<'a>> {
+        let mut declarator = self.alloc(VariableDeclarator {
+            span,
+            id,
+            init,
+            parent: VariableDeclaratorParent::None,
+        });
+        let declarator_ptr = &*declarator as *const _;
+        if let Some(init) = &mut declarator.init {
+            init.set_parent(ExpressionParent::VariableDeclaratorInit(declarator_ptr));
+        }
+        declarator
+    }
+
+    pub fn binding_identifier(self, span: Span, name: &'a str) -> BindingIdentifier<'a> {
+        BindingIdentifier { span, name }
+    }
+
+    pub fn identifier_reference(self, span: Span, name: &'a str) -> Expression<'a> {
+        Expression::Identifier(self.alloc(IdentifierReference {
+            span,
+            name,
+            parent: ExpressionParent::None,
+        }))
+    }
+
+    pub fn string_literal(
+        self,
+        span: Span,
+        value: &'a str,
+        raw: Option<&'a str>,
+    ) -> Expression<'a> {
+        Expression::StringLiteral(self.alloc(StringLiteral {
+            span,
+            value,
+            raw,
+            parent: ExpressionParent::None,
+        }))
+    }
+
+    pub fn numeric_literal(self, span: Span, value: f64) -> Expression<'a> {
+        Expression::NumericLiteral(self.alloc(NumericLiteral {
+            span,
+            value,
+            parent: ExpressionParent::None,
+        }))
+    }
+
+    pub fn boolean_literal(self, span: Span, value: bool) -> Expression<'a> {
+        Expression::BooleanLiteral(self.alloc(BooleanLiteral {
+            span,
+            value,
+            parent: ExpressionParent::None,
+        }))
+    }
+
+    pub fn null_literal(self, span: Span) -> Expression<'a> {
+        Expression::NullLiteral(self.alloc(NullLiteral {
+            span,
+            parent: ExpressionParent::None,
+        }))
+    }
+
+    pub fn binary_expression(
+        self,
+        span: Span,
+        left: Expression<'a>,
+        operator: BinaryOperator,
+        right: Expression<'a>,
+    ) -> Expression<'a> {
+        let mut bin_expr = self.alloc(BinaryExpression {
+            span,
+            left,
+            operator,
+            right,
+            parent: ExpressionParent::None,
+        });
+        let bin_expr_ptr = &*bin_expr as *const _;
+        bin_expr
+            .left
+            .set_parent(ExpressionParent::BinaryExpressionLeft(bin_expr_ptr));
+        bin_expr
+            .right
+            .set_parent(ExpressionParent::BinaryExpressionRight(bin_expr_ptr));
+        Expression::BinaryExpression(bin_expr)
+    }
+
+    pub fn unary_expression(
+        self,
+        span: Span,
+        operator: UnaryOperator,
+        argument: Expression<'a>,
+    ) -> Expression<'a> {
+        let mut unary_expr = self.alloc(UnaryExpression {
+            span,
+            operator,
+            argument,
+            parent: ExpressionParent::None,
+        });
+        let unary_expr_ptr = &*unary_expr as *const _;
+        unary_expr
+            .argument
+            .set_parent(ExpressionParent::UnaryExpression(unary_expr_ptr));
+        Expression::UnaryExpression(unary_expr)
+    }
+
+    pub fn call_expression(
+        self,
+        span: Span,
+        callee: Expression<'a>,
+        arguments: Vec<'a, Expression<'a>>,
+    ) -> Expression<'a> {
+        let mut call_expr = self.alloc(CallExpression {
+            span,
+            callee,
+            arguments,
+            parent: ExpressionParent::None,
+        });
+        let call_expr_ptr = &*call_expr as *const _;
+        call_expr
+            .callee
+            .set_parent(ExpressionParent::CallExpressionCallee(call_expr_ptr));
+        for arg in call_expr.arguments.iter_mut() {
+            arg.set_parent(ExpressionParent::CallExpressionArgument(call_expr_ptr));
+        }
+        Expression::CallExpression(call_expr)
+    }
+
+    pub fn member_expression(
+        self,
+        span: Span,
+        object: Expression<'a>,
+        property: Expression<'a>,
+        computed: bool,
+    ) -> Expression<'a> {
+        let mut member_expr = self.alloc(MemberExpression {
+            span,
+            object,
+            property,
+            computed,
+            parent: ExpressionParent::None,
+        });
+        let member_expr_ptr = &*member_expr as *const _;
+        member_expr
+            .object
+            .set_parent(ExpressionParent::MemberExpressionObject(member_expr_ptr));
+        member_expr
+            .property
+            .set_parent(ExpressionParent::MemberExpressionProperty(member_expr_ptr));
+        Expression::MemberExpression(member_expr)
+    }
+
+    pub fn logical_expression(
+        self,
+        span: Span,
+        left: Expression<'a>,
+        operator: LogicalOperator,
+        right: Expression<'a>,
+    ) -> Expression<'a> {
+        let mut logical_expr = self.alloc(LogicalExpression {
+            span,
+            left,
+            operator,
+            right,
+            parent: ExpressionParent::None,
+        });
+        let logical_expr_ptr = &*logical_expr as *const _;
+        logical_expr
+            .left
+            .set_parent(ExpressionParent::LogicalExpressionLeft(logical_expr_ptr));
+        logical_expr
+            .right
+            .set_parent(ExpressionParent::LogicalExpressionRight(logical_expr_ptr));
+        Expression::LogicalExpression(logical_expr)
+    }
+
+    pub fn conditional_expression(
+        self,
+        span: Span,
+        test: Expression<'a>,
+        consequent: Expression<'a>,
+        alternate: Expression<'a>,
+    ) -> Expression<'a> {
+        let mut cond_expr = self.alloc(ConditionalExpression {
+            span,
+            test,
+            consequent,
+            alternate,
+            parent: ExpressionParent::None,
+        });
+        let cond_expr_ptr = &*cond_expr as *const _;
+        cond_expr
+            .test
+            .set_parent(ExpressionParent::ConditionalExpressionTest(cond_expr_ptr));
+        cond_expr
+            .consequent
+            .set_parent(ExpressionParent::ConditionalExpressionConsequent(
+                cond_expr_ptr,
+            ));
+        cond_expr
+            .alternate
+            .set_parent(ExpressionParent::ConditionalExpressionAlternate(
+                cond_expr_ptr,
+            ));
+        Expression::ConditionalExpression(cond_expr)
+    }
+
+    pub fn assignment_expression(
+        self,
+        span: Span,
+        target: Expression<'a>,
+        operator: AssignmentOperator,
+        value: Expression<'a>,
+    ) -> Expression<'a> {
+        let mut assign_expr = self.alloc(AssignmentExpression {
+            span,
+            target,
+            operator,
+            value,
+            parent: ExpressionParent::None,
+        });
+        let assign_expr_ptr = &*assign_expr as *const _;
+        assign_expr
+            .target
+            .set_parent(ExpressionParent::AssignmentExpressionTarget(
+                assign_expr_ptr,
+            ));
+        assign_expr
+            .value
+            .set_parent(ExpressionParent::AssignmentExpressionValue(assign_expr_ptr));
+        Expression::AssignmentExpression(assign_expr)
+    }
+
+    /// `quasis` are raw text of the string parts, which alternate with `expressions`,
+    /// starting and ending with a quasi.
+    ///
+    /// # Panics
+    /// Panics if there is not exactly 1 more quasi than expressions.
+    pub fn template_literal(
+        self,
+        span: Span,
+        quasis: Vec<'a, &'a str>,
+        expressions: Vec<'a, Expression<'a>>,
+    ) -> Expression<'a> {
+        assert_eq!(
+            quasis.len(),
+            expressions.len() + 1,
+            "Template literal must have 1 more quasi than expressions"
+        );
+        let mut template_lit = self.alloc(TemplateLiteral {
+            span,
+            quasis,
+            expressions,
+            parent: ExpressionParent::None,
+        });
+        let template_lit_ptr = &*template_lit as *const _;
+        for expr in template_lit.expressions.iter_mut() {
+            expr.set_parent(ExpressionParent::TemplateLiteralExpression(
+                template_lit_ptr,
+            ));
+        }
+        Expression::TemplateLiteral(template_lit)
+    }
+
+    /// `None` in `elements` is a hole, as in `[1, , 3]`.
+    pub fn array_expression(
+        self,
+        span: Span,
+        elements: Vec<'a, Option<Expression<'a>>>,
+    ) -> Expression<'a> {
+        let mut array_expr = self.alloc(ArrayExpression {
+            span,
+            elements,
+            parent: ExpressionParent::None,
+        });
+        let array_expr_ptr = &*array_expr as *const _;
+        for element in array_expr.elements.iter_mut().flatten() {
+            element.set_parent(ExpressionParent::ArrayExpressionElement(array_expr_ptr));
+        }
+        Expression::ArrayExpression(array_expr)
+    }
+
+    pub fn object_expression(
+        self,
+        span: Span,
+        properties: Vec<'a, Box<'a, Property<'a>>>,
+    ) -> Expression<'a> {
+        let mut object_expr = self.alloc(ObjectExpression {
+            span,
+            properties,
+            parent: ExpressionParent::None,
+        });
+        let object_expr_ptr = &*object_expr as *const _;
+        for prop in object_expr.properties.iter_mut() {
+            prop.parent = PropertyParent::ObjectExpression(object_expr_ptr);
+        }
+        Expression::ObjectExpression(object_expr)
+    }
+
+    /// For a `shorthand` property, `key` and `value` must be separate identifier nodes
+    /// with the same name.
+    pub fn property(
+        self,
+        span: Span,
+        key: Expression<'a>,
+        value: Expression<'a>,
+        computed: bool,
+        shorthand: bool,
+    ) -> Box<'a, Property<'a>> {
+        let mut prop = self.alloc(Property {
+            span,
+            key,
+            value,
+            computed,
+            shorthand,
+            parent: PropertyParent::None,
+        });
+        let prop_ptr = &*prop as *const _;
+        prop.key.set_parent(ExpressionParent::PropertyKey(prop_ptr));
+        prop.value
+            .set_parent(ExpressionParent::PropertyValue(prop_ptr));
+        prop
+    }
+
+    /// Deep-copy an expression, allocating fresh nodes for it and all its descendants.
+    ///
+    /// Parent links within the copy point to the new nodes. As with other builder methods,
+    /// the new root's `parent` is `None`, to be set by the caller when it is inserted into the AST.
+    /// Strings are shared with the original, as they are immutable.
+    pub fn clone_expression(self, expr: &Expression<'a>) -> Expression<'a> {
+        match expr {
+            Expression::Identifier(id) => self.identifier_reference(id.span, id.name),
+            Expression::StringLiteral(str_lit) => {
+                self.string_literal(str_lit.span, str_lit.value, str_lit.raw)
+            }
+            Expression::NumericLiteral(num_lit) => {
+                self.numeric_literal(num_lit.span, num_lit.value)
+            }
+            Expression::BooleanLiteral(bool_lit) => {
+                self.boolean_literal(bool_lit.span, bool_lit.value)
+            }
+            Expression::NullLiteral(null_lit) => self.null_literal(null_lit.span),
+            Expression::BinaryExpression(bin_expr) => self.binary_expression(
+                bin_expr.span,
+                self.clone_expression(&bin_expr.left),
+                bin_expr.operator,
+                self.clone_expression(&bin_expr.right),
+            ),
+            Expression::UnaryExpression(unary_expr) => self.unary_expression(
+                unary_expr.span,
+                unary_expr.operator,
+                self.clone_expression(&unary_expr.argument),
+            ),
+            Expression::CallExpression(call_expr) => {
+                let mut arguments = Vec::with_capacity_in(call_expr.arguments.len(), self.alloc);
+                for arg in call_expr.arguments.iter() {
+                    arguments.push(self.clone_expression(arg));
+                }
+                self.call_expression(
+                    call_expr.span,
+                    self.clone_expression(&call_expr.callee),
+                    arguments,
+                )
+            }
+            Expression::MemberExpression(member_expr) => self.member_expression(
+                member_expr.span,
+                self.clone_expression(&member_expr.object),
+                self.clone_expression(&member_expr.property),
+                member_expr.computed,
+            ),
+            Expression::LogicalExpression(logical_expr) => self.logical_expression(
+                logical_expr.span,
+                self.clone_expression(&logical_expr.left),
+                logical_expr.operator,
+                self.clone_expression(&logical_expr.right),
+            ),
+            Expression::ConditionalExpression(cond_expr) => self.conditional_expression(
+                cond_expr.span,
+                self.clone_expression(&cond_expr.test),
+                self.clone_expression(&cond_expr.consequent),
+                self.clone_expression(&cond_expr.alternate),
+            ),
+            Expression::AssignmentExpression(assign_expr) => self.assignment_expression(
+                assign_expr.span,
+                self.clone_expression(&assign_expr.target),
+                assign_expr.operator,
+                self.clone_expression(&assign_expr.value),
+            ),
+            Expression::TemplateLiteral(template_lit) => {
+                let mut quasis = Vec::with_capacity_in(template_lit.quasis.len(), self.alloc);
+                quasis.extend(template_lit.quasis.iter().copied());
+                let mut expressions =
+                    Vec::with_capacity_in(template_lit.expressions.len(), self.alloc);
+                for expr in template_lit.expressions.iter() {
+                    expressions.push(self.clone_expression(expr));
+                }
+                self.template_literal(template_lit.span, quasis, expressions)
+            }
+            Expression::ArrayExpression(array_expr) => {
+                let mut elements = Vec::with_capacity_in(array_expr.elements.len(), self.alloc);
+                for element in array_expr.elements.iter() {
+                    elements.push(element.as_ref().map(|expr| self.clone_expression(expr)));
+                }
+                self.array_expression(array_expr.span, elements)
+            }
+            Expression::ObjectExpression(object_expr) => {
+                let mut properties =
+                    Vec::with_capacity_in(object_expr.properties.len(), self.alloc);
+                for prop in object_expr.properties.iter() {
+                    properties.push(self.property(
+                        prop.span,
+                        self.clone_expression(&prop.key),
+                        self.clone_expression(&prop.value),
+                        prop.computed,
+                        prop.shorthand,
+                    ));
+                }
+                self.object_expression(object_expr.span, properties)
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{parents::validate_tree, parser::parse_str, print::Printer};
+
+    #[test]
+    fn block() {
+        let alloc = Allocator::default();
+        let ast = AstBuilder::new(&alloc);
+        let span = Span::default();
+        let stmt = |name| ast.expression_statement(span, ast.identifier_reference(span, name));
+        let if_stmt = ast.if_statement(
+            span,
+            ast.identifier_reference(span, "b"),
+            ast.block(span, [stmt("c")]),
+            None,
+        );
+        let block = ast.block(span, [stmt("a"), ast.block(span, []), if_stmt]);
+        let mut body = ast.vec();
+        body.push(block);
+        let program = ast.program(body);
+
+        assert_eq!(Printer::print(program), "{ a; {} if (b) { c; } }");
+        assert!(validate_tree(program).is_ok());
+    }
+
+    #[test]
+    fn clone_expression_is_independent() {
+        let alloc = Allocator::default();
+        let ast = AstBuilder::new(&alloc);
+        let program = parse_str("a === -b;", &alloc).unwrap();
+        let Statement::ExpressionStatement(expr_stmt) = &mut program.body[0] else {
+            unreachable!();
+        };
+        let clone = ast.clone_expression(&expr_stmt.expression);
+
+        // Mutate the original
+        let Expression::BinaryExpression(bin_expr) = &mut expr_stmt.expression else {
+            unreachable!();
+        };
+        let Expression::Identifier(id) = &mut bin_expr.left else {
+            unreachable!();
+        };
+        id.name = "changed";
+
+        // Clone is unaffected, and its parent links point to nodes in the clone
+        assert_eq!(clone.parent(), ExpressionParent::None);
+        let Expression::BinaryExpression(clone_bin_expr) = &clone else {
+            unreachable!();
+        };
+        let Expression::Identifier(clone_id) = &clone_bin_expr.left else {
+            unreachable!();
+        };
+        assert_eq!(clone_id.name, "a");
+        assert_eq!(
+            clone_id.parent,
+            ExpressionParent::BinaryExpressionLeft(&**clone_bin_expr)
+        );
+        assert!(!core::ptr::eq(&**clone_bin_expr, &**bin_expr));
+    }
+}
@@ -1,24 +1,15 @@
 //! Cell type and token for traversing AST.
 //! Just thin wrappers around `GhostCell` and `GhostToken`.
 //!
-//! Instead of using `GhostToken`'s method of creating tokens `GhostToken::new`, which then
-//! means all code has to be within a closure, we use an unsafe API `GToken::new_unchecked`.
-//! It is the caller's responsibility to ensure no more than 1 token is "in play" at any time.
+//! As with `GhostToken::new`, tokens are only handed out to closures which are generic over
+//! the token's brand `'t`, so every token has a brand of its own, and can only access cells
+//! created with that brand. `transform` does this internally, and `Token::with` does it for
+//! other uses.
 //!
-//! To block access to `GhostToken::new`, we have to wrap both `GhostToken` and `GhostCell`
-//! in newtype wrappers which just forward calls to the underlying `GhostCell`.
-//!
-//! Without this wrapper, it would be possible to violate the constraint that there must only
-//! ever be one token accessible to code at any time, with safe function calls, as follows:
-//! ```
-//! struct MyTransformer(GhostToken);
-//! GhostToken::new(|token|) {
-//!   let transformer = MyTransformer(token);
-//!   // `transform` creates a token internally and passes it to `Traverse` methods.
-//!   // so `transformer`'s methods now have access to 2 tokens, and can violate aliasing rules.
-//!   transform(transformer, &mut stmt);
-//! })
-//! ```
+//! `Token::new_unchecked` creates a token with a brand chosen by the caller, so is unsafe.
+//! It is the caller's responsibility to ensure no more than 1 token is "in play" for any cell.
+
+use core::{fmt, ops::Deref, panic::Location};
 
 use ghost_cell::{GhostCell, GhostToken};
 
@@ -35,6 +26,7 @@ impl<'t> Token<'t> {
     /// this guarantee can be broken, and may lead to undefined behavior.
     ///
     /// This function is used internally by `transform`, but probably should not be used elsewhere.
+    /// Use `Token::with` instead.
     ///
     /// It is permissable to create multiple tokens which are never used together on the same AST.
     /// In practice, this means it is possible to transform multiple ASTs on different threads
@@ -48,7 +40,16 @@ impl<'t> Token<'t> {
     #[inline]
     pub unsafe fn new_unchecked() -> Self {
         // Token is a ZST
-        std::mem::transmute(())
+        core::mem::transmute(())
+    }
+
+    /// Create a token with a new brand, and pass it to `f`.
+    ///
+    /// `f` must accept any brand, so the brand is unique to this call, and no other token
+    /// can access cells created inside `f`.
+    #[inline]
+    pub fn with<R>(f: impl for<'brand> FnOnce(Token<'brand>) -> R) -> R {
+        GhostToken::new(|token| f(Token(token)))
     }
 }
 
@@ -78,8 +79,25 @@ impl<'t, T: ?Sized> GCell<'t, T> {
         self.0.borrow(&tk.0)
     }
 
+    /// Same as `borrow`, but returns a `TrackedRef`, which in debug builds records where
+    /// the borrow was taken, until it's dropped.
+    ///
+    /// With a single token, a mutable borrow while this borrow is live is a compile error.
+    /// But if more than 1 token is in play (see `Token::new_unchecked`), the compiler can't
+    /// catch it. In debug builds, mutably borrowing this cell while the `TrackedRef` lives
+    /// panics instead, with the location of this call.
     #[inline]
+    #[track_caller]
+    pub fn borrow_tracked<'a>(&'a self, tk: &'a Token<'t>) -> TrackedRef<'a, T> {
+        TrackedRef::new(self.addr(), self.borrow(tk))
+    }
+
+    /// # Panics
+    /// In debug builds, panics if the cell is borrowed by a live `TrackedRef`.
+    #[inline]
+    #[track_caller]
     pub fn borrow_mut<'a>(&'a self, tk: &'a mut Token<'t>) -> &'a mut T {
+        self.assert_not_tracked();
         self.0.borrow_mut(&mut tk.0)
     }
 
@@ -97,7 +115,7 @@ impl<'t, T: ?Sized> GCell<'t, T> {
     pub fn from_mut(t: &mut T) -> &mut Self {
         // SAFETY: As this wrapper type is `#[repr(transparent)]`, it's safe to transmute
         let inner = GhostCell::from_mut(t);
-        unsafe { std::mem::transmute(inner) }
+        unsafe { core::mem::transmute(inner) }
     }
 }
 
@@ -112,17 +130,46 @@ impl<'t, T> GCell<'t, [T]> {
 #[allow(dead_code)]
 impl<'t, T> GCell<'t, T> {
     #[inline]
+    #[track_caller]
     pub fn replace(&self, value: T, tk: &mut Token<'t>) -> T {
+        self.assert_not_tracked();
         self.0.replace(value, &mut tk.0)
     }
 
     #[inline]
+    #[track_caller]
     pub fn take(&self, tk: &mut Token<'t>) -> T
     where
         T: Default,
     {
+        self.assert_not_tracked();
         self.0.take(&mut tk.0)
     }
+
+    /// Mutably borrow 2 cells at once, e.g. to swap their contents.
+    ///
+    /// # Panics
+    /// Panics if `a` and `b` are the same cell.
+    /// In debug builds, also panics if either cell is borrowed by a live `TrackedRef`.
+    #[track_caller]
+    pub fn borrow_mut_two<'a>(
+        a: &'a Self,
+        b: &'a Self,
+        _tk: &'a mut Token<'t>,
+    ) -> (&'a mut T, &'a mut T) {
+        // This must be a hard assert, not `debug_assert!`. Two `&mut` refs to the same cell
+        // would be UB.
+        assert!(
+            !core::ptr::eq(a, b) || core::mem::size_of::<T>() == 0,
+            "Cannot mutably borrow the same cell twice"
+        );
+        a.assert_not_tracked();
+        b.assert_not_tracked();
+        // SAFETY: We hold `&mut Token` for `'a`, so no other references to contents of any cell
+        // with brand `'t` can exist for `'a`. `a` and `b` are distinct cells, so they don't alias.
+        // (Zero-sized types can share an address, but references to ZSTs never alias any data.)
+        unsafe { (&mut *a.as_ptr(), &mut *b.as_ptr()) }
+    }
 }
 
 impl<'t, T: Default> Default for GCell<'t, T> {
@@ -146,10 +193,143 @@ impl<'t, T> From<T> for GCell<'t, T> {
     }
 }
 
+impl<'t, T: ?Sized> GCell<'t, T> {
+    /// Address of the cell, used as its key in the debug-build borrow tracker.
+    #[inline]
+    fn addr(&self) -> usize {
+        self as *const Self as *const () as usize
+    }
+
+    #[inline]
+    #[track_caller]
+    fn assert_not_tracked(&self) {
+        #[cfg(all(debug_assertions, feature = "std"))]
+        if let Some(location) = tracker::find(self.addr()) {
+            panic!("Cannot mutably borrow cell which is borrowed at {location}");
+        }
+    }
+}
+
 // SAFETY: `GhostCell` is `Send` + `Sync`, so this wrapper can be too
 unsafe impl<'t, T: ?Sized + Send> Send for GCell<'t, T> {}
 unsafe impl<'t, T: ?Sized + Send + Sync> Sync for GCell<'t, T> {}
 
+/// Shared borrow of the contents of a `GCell`, returned by `GCell::borrow_tracked`.
+///
+/// Dereferences to the contents, the same as the `&T` returned by `GCell::borrow`.
+/// In debug builds, the location it was created at is registered against the cell until it's
+/// dropped. In release builds, nothing is recorded, and this is just a `&T`.
+#[cfg_attr(not(all(debug_assertions, feature = "std")), repr(transparent))]
+pub struct TrackedRef<'a, T: ?Sized> {
+    value: &'a T,
+    #[cfg(all(debug_assertions, feature = "std"))]
+    addr: usize,
+    #[cfg(all(debug_assertions, feature = "std"))]
+    location: &'static Location<'static>,
+}
+
+impl<'a, T: ?Sized> TrackedRef<'a, T> {
+    #[inline]
+    #[track_caller]
+    #[cfg_attr(not(all(debug_assertions, feature = "std")), allow(unused_variables))]
+    fn new(addr: usize, value: &'a T) -> Self {
+        #[cfg(all(debug_assertions, feature = "std"))]
+        {
+            let location = Location::caller();
+            tracker::push(addr, location);
+            Self {
+                value,
+                addr,
+                location,
+            }
+        }
+        #[cfg(not(all(debug_assertions, feature = "std")))]
+        Self { value }
+    }
+
+    /// Get the contents, with the full lifetime of the borrow.
+    #[inline]
+    pub fn get(&self) -> &'a T {
+        self.value
+    }
+
+    /// Get location of the `borrow_tracked` call which created this borrow.
+    /// Always `None` in release builds.
+    #[inline]
+    pub fn location(&self) -> Option<&'static Location<'static>> {
+        #[cfg(all(debug_assertions, feature = "std"))]
+        return Some(self.location);
+        #[cfg(not(all(debug_assertions, feature = "std")))]
+        return None;
+    }
+}
+
+impl<'a, T: ?Sized> Deref for TrackedRef<'a, T> {
+    type Target = T;
+
+    #[inline]
+    fn deref(&self) -> &T {
+        self.value
+    }
+}
+
+#[cfg(all(debug_assertions, feature = "std"))]
+impl<'a, T: ?Sized> Drop for TrackedRef<'a, T> {
+    fn drop(&mut self) {
+        tracker::remove(self.addr, self.location);
+    }
+}
+
+impl<'a, T: ?Sized + fmt::Debug> fmt::Debug for TrackedRef<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("TrackedRef");
+        debug.field("value", &self.value);
+        if let Some(location) = self.location() {
+            debug.field("location", &format_args!("{location}"));
+        }
+        debug.finish()
+    }
+}
+
+/// Debug-build registry of live `TrackedRef`s on the current thread, keyed by cell address.
+#[cfg(all(debug_assertions, feature = "std"))]
+mod tracker {
+    use std::{cell::RefCell, panic::Location, vec::Vec};
+
+    type Entry = (usize, &'static Location<'static>);
+
+    std::thread_local! {
+        static TRACKED: RefCell<Vec<Entry>> = const { RefCell::new(Vec::new()) };
+    }
+
+    pub fn push(addr: usize, location: &'static Location<'static>) {
+        TRACKED.with(|tracked| tracked.borrow_mut().push((addr, location)));
+    }
+
+    pub fn remove(addr: usize, location: &'static Location<'static>) {
+        // Ignore failure to access the registry if the thread is shutting down
+        let _ = TRACKED.try_with(|tracked| {
+            let mut tracked = tracked.borrow_mut();
+            if let Some(index) = tracked
+                .iter()
+                .rposition(|&(a, l)| a == addr && core::ptr::eq(l, location))
+            {
+                tracked.swap_remove(index);
+            }
+        });
+    }
+
+    pub fn find(addr: usize) -> Option<&'static Location<'static>> {
+        TRACKED.with(|tracked| {
+            let tracked = tracked.borrow();
+            tracked
+                .iter()
+                .find(|&&(a, _)| a == addr)
+                .map(|&(_, location)| location)
+        })
+    }
+}
+
 /// Type alias for a shared ref to a `GCell`.
 /// This is the interior-mutable equivalent to `oxc_allocator::Box`.
 pub type SharedBox<'a, 't, T> = &'a GCell<'t, T>;
@@ -185,3 +365,68 @@ macro_rules! shared_vec {
     };
 }
 pub(crate) use shared_vec;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::{ExpressionParent, Span, StringLiteral};
+
+    fn string_literal(value: &str) -> StringLiteral<'_> {
+        StringLiteral {
+            span: Span::default(),
+            value,
+            raw: None,
+            parent: ExpressionParent::None,
+        }
+    }
+
+    #[test]
+    fn borrow_mut_two_swap() {
+        Token::with(|mut tk| {
+            let a = GCell::new(string_literal("a"));
+            let b = GCell::new(string_literal("b"));
+            let (a_mut, b_mut) = GCell::borrow_mut_two(&a, &b, &mut tk);
+            core::mem::swap(&mut a_mut.value, &mut b_mut.value);
+            assert_eq!(a.borrow(&tk).value, "b");
+            assert_eq!(b.borrow(&tk).value, "a");
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot mutably borrow the same cell twice")]
+    fn borrow_mut_two_same_cell() {
+        Token::with(|mut tk| {
+            let a = GCell::new(string_literal("a"));
+            GCell::borrow_mut_two(&a, &a, &mut tk);
+        });
+    }
+
+    #[test]
+    fn borrow_tracked() {
+        Token::with(|mut tk| {
+            let a = GCell::new(string_literal("a"));
+            {
+                let tracked = a.borrow_tracked(&tk);
+                assert_eq!(tracked.get().value, "a");
+                assert_eq!(
+                    tracked.location().is_some(),
+                    cfg!(all(debug_assertions, feature = "std"))
+                );
+            }
+            a.borrow_mut(&mut tk).value = "b";
+            assert_eq!(a.borrow(&tk).value, "b");
+        });
+    }
+
+    #[test]
+    #[cfg(all(debug_assertions, feature = "std"))]
+    #[should_panic(expected = "Cannot mutably borrow cell which is borrowed at src/cell.rs:")]
+    fn borrow_tracked_conflict() {
+        // 2 tokens in play for the same cell, which the compiler can't catch
+        // SAFETY: `borrow_mut` panics before a `&mut` aliasing the shared borrow is created
+        let (tk, mut other_tk) = unsafe { (Token::new_unchecked(), Token::new_unchecked()) };
+        let a: GCell<'static, _> = GCell::new(string_literal("a"));
+        let _tracked = a.borrow_tracked(&tk);
+        a.borrow_mut(&mut other_tk);
+    }
+}
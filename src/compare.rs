@@ -0,0 +1,174 @@
+//! Structural comparison of AST nodes.
+
+use crate::ast::{Expression, Statement};
+
+/// Compare two expressions for structural equality.
+///
+/// Operators, literal values and identifier names are compared, recursing into children.
+/// Spans and parent links are ignored, so two expressions from different places in the source
+/// (or different ASTs) are equal if they have the same shape.
+pub fn expr_eq(a: &Expression<'_>, b: &Expression<'_>) -> bool {
+    match (a, b) {
+        (Expression::Identifier(a), Expression::Identifier(b)) => a.name == b.name,
+        (Expression::StringLiteral(a), Expression::StringLiteral(b)) => a.value == b.value,
+        (Expression::NumericLiteral(a), Expression::NumericLiteral(b)) => a.value == b.value,
+        (Expression::BooleanLiteral(a), Expression::BooleanLiteral(b)) => a.value == b.value,
+        (Expression::NullLiteral(_), Expression::NullLiteral(_)) => true,
+        (Expression::BinaryExpression(a), Expression::BinaryExpression(b)) => {
+            a.operator == b.operator && expr_eq(&a.left, &b.left) && expr_eq(&a.right, &b.right)
+        }
+        (Expression::UnaryExpression(a), Expression::UnaryExpression(b)) => {
+            a.operator == b.operator && expr_eq(&a.argument, &b.argument)
+        }
+        (Expression::CallExpression(a), Expression::CallExpression(b)) => {
+            expr_eq(&a.callee, &b.callee)
+                && a.arguments.len() == b.arguments.len()
+                && a.arguments
+                    .iter()
+                    .zip(b.arguments.iter())
+                    .all(|(a, b)| expr_eq(a, b))
+        }
+        (Expression::ArrayExpression(a), Expression::ArrayExpression(b)) => {
+            a.elements.len() == b.elements.len()
+                && a.elements
+                    .iter()
+                    .zip(b.elements.iter())
+                    .all(|(a, b)| match (a, b) {
+                        (Some(a), Some(b)) => expr_eq(a, b),
+                        (None, None) => true,
+                        _ => false,
+                    })
+        }
+        (Expression::ObjectExpression(a), Expression::ObjectExpression(b)) => {
+            a.properties.len() == b.properties.len()
+                && a.properties.iter().zip(b.properties.iter()).all(|(a, b)| {
+                    a.computed == b.computed
+                        && a.shorthand == b.shorthand
+                        && expr_eq(&a.key, &b.key)
+                        && expr_eq(&a.value, &b.value)
+                })
+        }
+        (Expression::MemberExpression(a), Expression::MemberExpression(b)) => {
+            a.computed == b.computed
+                && expr_eq(&a.object, &b.object)
+                && expr_eq(&a.property, &b.property)
+        }
+        (Expression::LogicalExpression(a), Expression::LogicalExpression(b)) => {
+            a.operator == b.operator && expr_eq(&a.left, &b.left) && expr_eq(&a.right, &b.right)
+        }
+        (Expression::ConditionalExpression(a), Expression::ConditionalExpression(b)) => {
+            expr_eq(&a.test, &b.test)
+                && expr_eq(&a.consequent, &b.consequent)
+                && expr_eq(&a.alternate, &b.alternate)
+        }
+        (Expression::AssignmentExpression(a), Expression::AssignmentExpression(b)) => {
+            a.operator == b.operator && expr_eq(&a.target, &b.target) && expr_eq(&a.value, &b.value)
+        }
+        (Expression::TemplateLiteral(a), Expression::TemplateLiteral(b)) => {
+            a.quasis.as_slice() == b.quasis.as_slice()
+                && a.expressions.len() == b.expressions.len()
+                && a.expressions
+                    .iter()
+                    .zip(b.expressions.iter())
+                    .all(|(a, b)| expr_eq(a, b))
+        }
+        _ => false,
+    }
+}
+
+/// Compare two statements for structural equality, in the same way as `expr_eq`.
+///
+/// Expressions in statements are compared with `expr_eq`, and nested statements recursively.
+/// Comments are ignored.
+pub fn stmt_eq(a: &Statement<'_>, b: &Statement<'_>) -> bool {
+    match (a, b) {
+        (Statement::ExpressionStatement(a), Statement::ExpressionStatement(b)) => {
+            expr_eq(&a.expression, &b.expression)
+        }
+        (Statement::BlockStatement(a), Statement::BlockStatement(b)) => {
+            a.body.len() == b.body.len()
+                && a.body.iter().zip(b.body.iter()).all(|(a, b)| stmt_eq(a, b))
+        }
+        (Statement::IfStatement(a), Statement::IfStatement(b)) => {
+            expr_eq(&a.test, &b.test)
+                && stmt_eq(&a.consequent, &b.consequent)
+                && match (&a.alternate, &b.alternate) {
+                    (Some(a), Some(b)) => stmt_eq(a, b),
+                    (None, None) => true,
+                    _ => false,
+                }
+        }
+        (Statement::ReturnStatement(a), Statement::ReturnStatement(b)) => {
+            match (&a.argument, &b.argument) {
+                (Some(a), Some(b)) => expr_eq(a, b),
+                (None, None) => true,
+                _ => false,
+            }
+        }
+        (Statement::VariableDeclaration(a), Statement::VariableDeclaration(b)) => {
+            a.kind == b.kind
+                && a.declarations.len() == b.declarations.len()
+                && a.declarations
+                    .iter()
+                    .zip(b.declarations.iter())
+                    .all(|(a, b)| {
+                        a.id.name == b.id.name
+                            && match (&a.init, &b.init) {
+                                (Some(a), Some(b)) => expr_eq(a, b),
+                                (None, None) => true,
+                                _ => false,
+                            }
+                    })
+        }
+        _ => false,
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use oxc_allocator::Allocator;
+
+    use super::*;
+    use crate::{ast::Statement, parser::parse_str};
+
+    #[test]
+    fn structurally_identical_expressions() {
+        let alloc = Allocator::default();
+        let program = parse_str(
+            "f(a === -b); f(a === -b); f(a === -c); f(a !== -b);",
+            &alloc,
+        )
+        .unwrap();
+        let exprs = program
+            .body
+            .iter()
+            .map(|stmt| match stmt {
+                Statement::ExpressionStatement(expr_stmt) => &expr_stmt.expression,
+                _ => unreachable!(),
+            })
+            .collect::<Vec<_>>();
+        // Different nodes, same shape
+        assert!(expr_eq(exprs[0], exprs[1]));
+        // Different identifier name
+        assert!(!expr_eq(exprs[0], exprs[2]));
+        // Different operator
+        assert!(!expr_eq(exprs[0], exprs[3]));
+    }
+
+    #[test]
+    fn structurally_identical_statements() {
+        let alloc = Allocator::default();
+        let program = parse_str(
+            "if (a) { let x = 1; return x; } if (a) { let x = 1; return x; } \
+             if (a) { let y = 1; return x; } if (a) { let x = 1; return; }",
+            &alloc,
+        )
+        .unwrap();
+        let stmts = &program.body;
+        assert!(stmt_eq(&stmts[0], &stmts[1]));
+        // Different binding name
+        assert!(!stmt_eq(&stmts[0], &stmts[2]));
+        // Missing return argument
+        assert!(!stmt_eq(&stmts[0], &stmts[3]));
+    }
+}
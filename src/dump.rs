@@ -0,0 +1,463 @@
+//! Debug dumps of an AST's structure, as an indented tree or as a Graphviz DOT graph.
+//!
+//! Unlike `Printer`, which outputs source code, the dumps show the structure of the AST,
+//! including where each node's parent link points. This is useful for debugging transforms
+//! which corrupt parent links.
+
+use std::{collections::HashMap, fmt::Write};
+
+use crate::{
+    ast::{
+        Expression, ExpressionParent, Program, Property, PropertyParent, Statement,
+        StatementParent, VarKind, VariableDeclarator, VariableDeclaratorParent,
+    },
+    stats::AstType,
+};
+
+/// Dump AST as an indented tree, one line per node.
+///
+/// Each node is numbered in the order it's visited (pre-order). Each line shows the node's
+/// number, type, and operator / value (where it has one), followed by where the node's parent
+/// link points to, as `^#<number>.<field>`. e.g.:
+///
+/// ```txt
+/// #0 Program
+///   #1 ExpressionStatement ^#0.body
+///     #2 BinaryExpression === ^#1.expression
+///       #3 UnaryExpression typeof ^#2.left
+///         #4 IdentifierReference foo ^#3.argument
+///       #5 StringLiteral "object" ^#2.right
+/// ```
+///
+/// A parent link which is not set is shown as `^none`. A parent link which points to a node
+/// which is not in the AST is shown as `^?.<field>`. Any parent link which does not match
+/// the node's actual position in the AST is followed by `!`.
+pub fn dump(program: &Program<'_>) -> String {
+    let nodes = collect_nodes(program);
+    let mut out = String::new();
+    for node in &nodes.list {
+        let indent = node.depth * 2;
+        write!(out, "{:indent$}#{} {:?}", "", node.number, node.ty).unwrap();
+        if let Some(detail) = &node.detail {
+            write!(out, " {detail}").unwrap();
+        }
+        if node.ty != AstType::Program {
+            match node.link {
+                Some((parent_addr, field)) => match nodes.numbers.get(&parent_addr) {
+                    Some(parent_number) => write!(out, " ^#{parent_number}.{field}"),
+                    None => write!(out, " ^?.{field}"),
+                }
+                .unwrap(),
+                None => out.push_str(" ^none"),
+            }
+            if !nodes.link_is_correct(node) {
+                out.push('!');
+            }
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Export AST as a Graphviz DOT digraph, for rendering with e.g. `dot -Tpng`.
+///
+/// Each node is labeled with its type, and operator / value (where it has one).
+/// Edges from parent to child are labeled with the name of the field which holds the child.
+/// Parent links are shown as dashed edges from child to parent.
+///
+/// Parent links which do not match the node's actual position in the AST are drawn in red.
+/// Nodes whose parent link is not set, or points to a node which is not in the AST, are outlined
+/// in red.
+pub fn to_dot(program: &Program<'_>) -> String {
+    let nodes = collect_nodes(program);
+    let mut out = String::from("digraph AST {\n");
+    for node in &nodes.list {
+        let mut label = format!("{:?}", node.ty);
+        if let Some(detail) = &node.detail {
+            write!(label, " {detail}").unwrap();
+        }
+        let label = label.replace('\\', "\\\\").replace('"', "\\\"");
+        let link_target = node.link.and_then(|(addr, _)| nodes.numbers.get(&addr));
+        let color = if node.ty != AstType::Program && link_target.is_none() {
+            ", color=red"
+        } else {
+            ""
+        };
+        writeln!(out, "  n{} [label=\"{label}\"{color}];", node.number).unwrap();
+
+        if let Some((parent_number, field)) = node.parent {
+            writeln!(
+                out,
+                "  n{parent_number} -> n{} [label=\"{field}\"];",
+                node.number
+            )
+            .unwrap();
+        }
+        if let Some(link_target) = link_target {
+            let color = if nodes.link_is_correct(node) {
+                ""
+            } else {
+                ", color=red"
+            };
+            writeln!(
+                out,
+                "  n{} -> n{link_target} [style=dashed{color}];",
+                node.number
+            )
+            .unwrap();
+        }
+    }
+    out.push_str("}\n");
+    out
+}
+
+/// Parent link, as address of parent node and name of the field which holds the child.
+type ParentLink = Option<(usize, &'static str)>;
+
+/// Node in AST.
+struct Node {
+    number: usize,
+    depth: usize,
+    ty: AstType,
+    /// Operator or value of the node
+    detail: Option<String>,
+    /// Number of the node which contains this node, and the name of the field containing it
+    parent: Option<(usize, &'static str)>,
+    /// Where the node's parent link points to
+    link: ParentLink,
+}
+
+/// All nodes in an AST, in pre-order.
+struct Nodes {
+    list: Vec<Node>,
+    /// Mapping from address of node to its number
+    numbers: HashMap<usize, usize>,
+}
+
+impl Nodes {
+    /// Returns `true` if node's parent link points to the node and field which contain it.
+    fn link_is_correct(&self, node: &Node) -> bool {
+        let link = node
+            .link
+            .and_then(|(addr, field)| Some((*self.numbers.get(&addr)?, field)));
+        link == node.parent
+    }
+}
+
+fn collect_nodes(program: &Program<'_>) -> Nodes {
+    let mut collector = NodeCollector {
+        nodes: Nodes {
+            list: vec![],
+            numbers: HashMap::new(),
+        },
+        stack: vec![],
+    };
+    collector.program(program);
+    collector.nodes
+}
+
+struct NodeCollector {
+    nodes: Nodes,
+    /// Numbers of ancestors of the current node
+    stack: Vec<usize>,
+}
+
+impl NodeCollector {
+    /// Record a node, and enter it.
+    /// `exit` must be called once the node's children have been recorded.
+    fn enter(
+        &mut self,
+        addr: usize,
+        ty: AstType,
+        detail: Option<String>,
+        field: &'static str,
+        link: ParentLink,
+    ) {
+        let number = self.nodes.list.len();
+        self.nodes.numbers.insert(addr, number);
+        self.nodes.list.push(Node {
+            number,
+            depth: self.stack.len(),
+            ty,
+            detail,
+            parent: self.stack.last().map(|&parent| (parent, field)),
+            link,
+        });
+        self.stack.push(number);
+    }
+
+    fn exit(&mut self) {
+        self.stack.pop();
+    }
+
+    fn program(&mut self, program: &Program<'_>) {
+        self.enter(addr(program), AstType::Program, None, "", None);
+        for stmt in &program.body {
+            self.statement(stmt, "body");
+        }
+        self.exit();
+    }
+
+    fn statement(&mut self, stmt: &Statement<'_>, field: &'static str) {
+        let link = statement_parent_link(stmt.parent());
+        let ty = AstType::of_statement(stmt);
+        match stmt {
+            Statement::ExpressionStatement(expr_stmt) => {
+                self.enter(addr(&**expr_stmt), ty, None, field, link);
+                self.expression(&expr_stmt.expression, "expression");
+            }
+            Statement::BlockStatement(block) => {
+                self.enter(addr(&**block), ty, None, field, link);
+                for stmt in &block.body {
+                    self.statement(stmt, "body");
+                }
+            }
+            Statement::IfStatement(if_stmt) => {
+                self.enter(addr(&**if_stmt), ty, None, field, link);
+                self.expression(&if_stmt.test, "test");
+                self.statement(&if_stmt.consequent, "consequent");
+                if let Some(alternate) = &if_stmt.alternate {
+                    self.statement(alternate, "alternate");
+                }
+            }
+            Statement::ReturnStatement(return_stmt) => {
+                self.enter(addr(&**return_stmt), ty, None, field, link);
+                if let Some(argument) = &return_stmt.argument {
+                    self.expression(argument, "argument");
+                }
+            }
+            Statement::VariableDeclaration(var_decl) => {
+                let kind = match var_decl.kind {
+                    VarKind::Var => "var",
+                    VarKind::Let => "let",
+                    VarKind::Const => "const",
+                };
+                self.enter(addr(&**var_decl), ty, Some(kind.to_string()), field, link);
+                for declarator in &var_decl.declarations {
+                    self.variable_declarator(declarator);
+                }
+            }
+        }
+        self.exit();
+    }
+
+    fn variable_declarator(&mut self, declarator: &VariableDeclarator<'_>) {
+        let link = match declarator.parent {
+            VariableDeclaratorParent::None => None,
+            VariableDeclaratorParent::VariableDeclaration(var_decl) => {
+                Some((var_decl as usize, "declarations"))
+            }
+        };
+        self.enter(
+            addr(declarator),
+            AstType::VariableDeclarator,
+            Some(declarator.id.name.to_string()),
+            "declarations",
+            link,
+        );
+        if let Some(init) = &declarator.init {
+            self.expression(init, "init");
+        }
+        self.exit();
+    }
+
+    fn property(&mut self, prop: &Property<'_>) {
+        let link = match prop.parent {
+            PropertyParent::None => None,
+            PropertyParent::ObjectExpression(object_expr) => {
+                Some((object_expr as usize, "properties"))
+            }
+        };
+        let detail = match (prop.computed, prop.shorthand) {
+            (true, _) => Some("computed".to_string()),
+            (false, true) => Some("shorthand".to_string()),
+            (false, false) => None,
+        };
+        self.enter(addr(prop), AstType::Property, detail, "properties", link);
+        self.expression(&prop.key, "key");
+        self.expression(&prop.value, "value");
+        self.exit();
+    }
+
+    fn expression(&mut self, expr: &Expression<'_>, field: &'static str) {
+        let link = expression_parent_link(expr.parent());
+        let ty = AstType::of_expression(expr);
+        match expr {
+            Expression::Identifier(id) => {
+                self.enter(addr(&**id), ty, Some(id.name.to_string()), field, link);
+            }
+            Expression::StringLiteral(str_lit) => {
+                let detail = format!("{:?}", str_lit.value);
+                self.enter(addr(&**str_lit), ty, Some(detail), field, link);
+            }
+            Expression::NumericLiteral(num_lit) => {
+                let detail = num_lit.value.to_string();
+                self.enter(addr(&**num_lit), ty, Some(detail), field, link);
+            }
+            Expression::BooleanLiteral(bool_lit) => {
+                let detail = bool_lit.value.to_string();
+                self.enter(addr(&**bool_lit), ty, Some(detail), field, link);
+            }
+            Expression::NullLiteral(null_lit) => {
+                self.enter(addr(&**null_lit), ty, None, field, link);
+            }
+            Expression::BinaryExpression(bin_expr) => {
+                let detail = bin_expr.operator.as_str().to_string();
+                self.enter(addr(&**bin_expr), ty, Some(detail), field, link);
+                self.expression(&bin_expr.left, "left");
+                self.expression(&bin_expr.right, "right");
+            }
+            Expression::UnaryExpression(unary_expr) => {
+                let detail = unary_expr.operator.as_str().to_string();
+                self.enter(addr(&**unary_expr), ty, Some(detail), field, link);
+                self.expression(&unary_expr.argument, "argument");
+            }
+            Expression::CallExpression(call_expr) => {
+                self.enter(addr(&**call_expr), ty, None, field, link);
+                self.expression(&call_expr.callee, "callee");
+                for arg in &call_expr.arguments {
+                    self.expression(arg, "arguments");
+                }
+            }
+            Expression::MemberExpression(member_expr) => {
+                let detail = member_expr.computed.then(|| "computed".to_string());
+                self.enter(addr(&**member_expr), ty, detail, field, link);
+                self.expression(&member_expr.object, "object");
+                self.expression(&member_expr.property, "property");
+            }
+            Expression::LogicalExpression(logical_expr) => {
+                let detail = logical_expr.operator.as_str().to_string();
+                self.enter(addr(&**logical_expr), ty, Some(detail), field, link);
+                self.expression(&logical_expr.left, "left");
+                self.expression(&logical_expr.right, "right");
+            }
+            Expression::ConditionalExpression(cond_expr) => {
+                self.enter(addr(&**cond_expr), ty, None, field, link);
+                self.expression(&cond_expr.test, "test");
+                self.expression(&cond_expr.consequent, "consequent");
+                self.expression(&cond_expr.alternate, "alternate");
+            }
+            Expression::AssignmentExpression(assign_expr) => {
+                let detail = assign_expr.operator.as_str().to_string();
+                self.enter(addr(&**assign_expr), ty, Some(detail), field, link);
+                self.expression(&assign_expr.target, "target");
+                self.expression(&assign_expr.value, "value");
+            }
+            Expression::TemplateLiteral(template_lit) => {
+                let detail = format!("{:?}", template_lit.quasis.as_slice());
+                self.enter(addr(&**template_lit), ty, Some(detail), field, link);
+                for expr in &template_lit.expressions {
+                    self.expression(expr, "expressions");
+                }
+            }
+            Expression::ArrayExpression(array_expr) => {
+                let detail = format!("length {}", array_expr.elements.len());
+                self.enter(addr(&**array_expr), ty, Some(detail), field, link);
+                for element in array_expr.elements.iter().flatten() {
+                    self.expression(element, "elements");
+                }
+            }
+            Expression::ObjectExpression(object_expr) => {
+                self.enter(addr(&**object_expr), ty, None, field, link);
+                for prop in &object_expr.properties {
+                    self.property(prop);
+                }
+            }
+        }
+        self.exit();
+    }
+}
+
+fn addr<T>(node: &T) -> usize {
+    node as *const T as usize
+}
+
+fn statement_parent_link(parent: StatementParent<'_>) -> ParentLink {
+    match parent {
+        StatementParent::None => None,
+        StatementParent::Program(program) => Some((program as usize, "body")),
+        StatementParent::BlockStatement(block) => Some((block as usize, "body")),
+        StatementParent::IfStatementConsequent(if_stmt) => Some((if_stmt as usize, "consequent")),
+        StatementParent::IfStatementAlternate(if_stmt) => Some((if_stmt as usize, "alternate")),
+    }
+}
+
+fn expression_parent_link(parent: ExpressionParent<'_>) -> ParentLink {
+    use ExpressionParent as EP;
+
+    match parent {
+        EP::None => None,
+        EP::ExpressionStatement(expr_stmt) => Some((expr_stmt as usize, "expression")),
+        EP::BinaryExpressionLeft(bin_expr) => Some((bin_expr as usize, "left")),
+        EP::BinaryExpressionRight(bin_expr) => Some((bin_expr as usize, "right")),
+        EP::UnaryExpression(unary_expr) => Some((unary_expr as usize, "argument")),
+        EP::CallExpressionCallee(call_expr) => Some((call_expr as usize, "callee")),
+        EP::CallExpressionArgument(call_expr) => Some((call_expr as usize, "arguments")),
+        EP::MemberExpressionObject(member_expr) => Some((member_expr as usize, "object")),
+        EP::MemberExpressionProperty(member_expr) => Some((member_expr as usize, "property")),
+        EP::LogicalExpressionLeft(logical_expr) => Some((logical_expr as usize, "left")),
+        EP::LogicalExpressionRight(logical_expr) => Some((logical_expr as usize, "right")),
+        EP::ConditionalExpressionTest(cond_expr) => Some((cond_expr as usize, "test")),
+        EP::ConditionalExpressionConsequent(cond_expr) => Some((cond_expr as usize, "consequent")),
+        EP::ConditionalExpressionAlternate(cond_expr) => Some((cond_expr as usize, "alternate")),
+        EP::AssignmentExpressionTarget(assign_expr) => Some((assign_expr as usize, "target")),
+        EP::AssignmentExpressionValue(assign_expr) => Some((assign_expr as usize, "value")),
+        EP::TemplateLiteralExpression(template_lit) => Some((template_lit as usize, "expressions")),
+        EP::ArrayExpressionElement(array_expr) => Some((array_expr as usize, "elements")),
+        EP::PropertyKey(prop) => Some((prop as usize, "key")),
+        EP::PropertyValue(prop) => Some((prop as usize, "value")),
+        EP::IfStatementTest(if_stmt) => Some((if_stmt as usize, "test")),
+        EP::ReturnStatementArgument(return_stmt) => Some((return_stmt as usize, "argument")),
+        EP::VariableDeclaratorInit(declarator) => Some((declarator as usize, "init")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use oxc_allocator::Allocator;
+
+    use super::*;
+    use crate::{parser::parse_str, transforms::TransformTypeof, traverse::transform};
+
+    #[test]
+    fn dump_program() {
+        let alloc = Allocator::default();
+        let program = parse_str(
+            "let x = [1, , a.b]; if (x) { f(x, { y: 'z' }); } else x = !x;",
+            &alloc,
+        )
+        .unwrap();
+        insta::assert_snapshot!(dump(program));
+    }
+
+    #[test]
+    fn dump_transform_typeof() {
+        let alloc = Allocator::default();
+        let program = parse_str("typeof foo === 'object'", &alloc).unwrap();
+        transform(&mut TransformTypeof, program);
+        insta::assert_snapshot!(dump(program));
+    }
+
+    #[test]
+    fn dump_stale_parents() {
+        let alloc = Allocator::default();
+        let program = parse_str("typeof foo === 'object'", &alloc).unwrap();
+        // Swap sides without updating parent links
+        let Statement::ExpressionStatement(expr_stmt) = &mut program.body[0] else {
+            unreachable!();
+        };
+        let Expression::BinaryExpression(bin_expr) = &mut expr_stmt.expression else {
+            unreachable!();
+        };
+        let bin_expr = &mut **bin_expr;
+        core::mem::swap(&mut bin_expr.left, &mut bin_expr.right);
+        insta::assert_snapshot!(dump(program));
+    }
+
+    #[test]
+    fn dot() {
+        let alloc = Allocator::default();
+        let program = parse_str("typeof foo === 'object'", &alloc).unwrap();
+        insta::assert_snapshot!(to_dot(program));
+    }
+}
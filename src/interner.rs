@@ -0,0 +1,115 @@
+//! Deduplication of strings in the arena.
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+};
+
+use oxc_allocator::Allocator;
+
+/// String interner, which ensures equal strings share one allocation in the arena.
+///
+/// Used by the parser for identifier names and string literal values, so e.g. every
+/// `"use strict"` in a file refers to the same string.
+pub struct Interner<'a> {
+    alloc: &'a Allocator,
+    strings: RefCell<HashMap<&'a str, &'a str>>,
+    lookups: Cell<usize>,
+    hits: Cell<usize>,
+}
+
+/// Statistics on strings interned by an `Interner`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InternStats {
+    /// Number of strings interned
+    pub lookups: usize,
+    /// Number of strings which were already in the interner, so were deduplicated
+    pub hits: usize,
+}
+
+impl InternStats {
+    /// Proportion of strings which were deduplicated, between 0 and 1.
+    /// Returns 0 if no strings were interned.
+    pub fn hit_rate(&self) -> f64 {
+        if self.lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / self.lookups as f64
+        }
+    }
+}
+
+impl<'a> Interner<'a> {
+    pub fn new(alloc: &'a Allocator) -> Self {
+        Self {
+            alloc,
+            strings: RefCell::new(HashMap::new()),
+            lookups: Cell::new(0),
+            hits: Cell::new(0),
+        }
+    }
+
+    /// Get interned copy of `s`. If `s` has not been interned before, it's copied into the arena.
+    pub fn intern(&self, s: &str) -> &'a str {
+        self.lookup(s)
+            .unwrap_or_else(|| self.insert(self.alloc.alloc_str(s)))
+    }
+
+    /// Get interned copy of `s`, which already lives as long as the arena (e.g. a slice of
+    /// source text). If `s` has not been interned before, it's stored without copying.
+    pub fn intern_borrowed(&self, s: &'a str) -> &'a str {
+        self.lookup(s).unwrap_or_else(|| self.insert(s))
+    }
+
+    pub fn stats(&self) -> InternStats {
+        InternStats {
+            lookups: self.lookups.get(),
+            hits: self.hits.get(),
+        }
+    }
+
+    fn lookup(&self, s: &str) -> Option<&'a str> {
+        self.lookups.set(self.lookups.get() + 1);
+        let interned = self.strings.borrow().get(s).copied();
+        if interned.is_some() {
+            self.hits.set(self.hits.get() + 1);
+        }
+        interned
+    }
+
+    fn insert(&self, s: &'a str) -> &'a str {
+        self.strings.borrow_mut().insert(s, s);
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deduplicates() {
+        let alloc = Allocator::default();
+        let interner = Interner::new(&alloc);
+        assert_eq!(interner.stats().hit_rate(), 0.0);
+
+        let source = String::from("foo bar");
+        let a = interner.intern("foo");
+        let b = interner.intern_borrowed(alloc.alloc_str(&source[4..]));
+        let c = interner.intern(&source[..3]);
+        let d = interner.intern_borrowed(alloc.alloc_str("bar"));
+        assert!(core::ptr::eq(a, c));
+        assert!(core::ptr::eq(b, d));
+        assert!(!core::ptr::eq(a, b));
+
+        let stats = interner.stats();
+        assert_eq!(
+            stats,
+            InternStats {
+                lookups: 4,
+                hits: 2
+            }
+        );
+        assert_eq!(stats.hit_rate(), 0.5);
+    }
+}
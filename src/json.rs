@@ -0,0 +1,485 @@
+//! Conversion of AST to and from ESTree-style JSON.
+//! Only available with `serde` feature.
+//!
+//! Each node is serialized as an object with a `type` field, and its span flattened into
+//! `start` and `end` fields, e.g.:
+//! `{"type":"BinaryExpression","start":0,"end":5,"left":{...},"operator":"===","right":{...}}`.
+//! Children are inlined. `parent` back-links are omitted.
+
+use std::fmt;
+
+use oxc_allocator::{Allocator, Box, Vec};
+use serde::{de::DeserializeOwned, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+use crate::{
+    ast::{Expression, Program, Property, Span, Statement, VariableDeclarator},
+    builder::AstBuilder,
+    interner::Interner,
+};
+
+/// Serialize AST to JSON.
+pub fn to_json(program: &Program<'_>) -> String {
+    // Cannot fail - AST contains no maps with non-string keys, and no types which refuse to serialize
+    serde_json::to_string(program).unwrap()
+}
+
+// `oxc_allocator` 0.12 does not implement `Serialize` for `Box` or `Vec`, so AST types use these
+// via `serialize_with`, and `Statement` / `Expression` (untagged) are serialized by hand below.
+
+pub(crate) fn serialize_vec<T: Serialize, S: Serializer>(
+    vec: &Vec<'_, T>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(vec.iter())
+}
+
+pub(crate) fn serialize_boxes<T: Serialize, S: Serializer>(
+    vec: &Vec<'_, Box<'_, T>>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(vec.iter().map(|value| &**value))
+}
+
+impl<'a> Serialize for Statement<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Statement::ExpressionStatement(stmt) => (**stmt).serialize(serializer),
+            Statement::BlockStatement(stmt) => (**stmt).serialize(serializer),
+            Statement::IfStatement(stmt) => (**stmt).serialize(serializer),
+            Statement::ReturnStatement(stmt) => (**stmt).serialize(serializer),
+            Statement::VariableDeclaration(stmt) => (**stmt).serialize(serializer),
+        }
+    }
+}
+
+impl<'a> Serialize for Expression<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Expression::StringLiteral(expr) => (**expr).serialize(serializer),
+            Expression::Identifier(expr) => (**expr).serialize(serializer),
+            Expression::BinaryExpression(expr) => (**expr).serialize(serializer),
+            Expression::UnaryExpression(expr) => (**expr).serialize(serializer),
+            Expression::NumericLiteral(expr) => (**expr).serialize(serializer),
+            Expression::BooleanLiteral(expr) => (**expr).serialize(serializer),
+            Expression::NullLiteral(expr) => (**expr).serialize(serializer),
+            Expression::CallExpression(expr) => (**expr).serialize(serializer),
+            Expression::MemberExpression(expr) => (**expr).serialize(serializer),
+            Expression::LogicalExpression(expr) => (**expr).serialize(serializer),
+            Expression::ConditionalExpression(expr) => (**expr).serialize(serializer),
+            Expression::AssignmentExpression(expr) => (**expr).serialize(serializer),
+            Expression::TemplateLiteral(expr) => (**expr).serialize(serializer),
+            Expression::ArrayExpression(expr) => (**expr).serialize(serializer),
+            Expression::ObjectExpression(expr) => (**expr).serialize(serializer),
+        }
+    }
+}
+
+/// Error produced when JSON cannot be converted to AST.
+#[derive(Debug)]
+pub struct DeserializeError {
+    pub message: String,
+}
+
+impl fmt::Display for DeserializeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for DeserializeError {}
+
+impl From<serde_json::Error> for DeserializeError {
+    fn from(err: serde_json::Error) -> Self {
+        Self {
+            message: format!("Invalid JSON: {err}"),
+        }
+    }
+}
+
+/// Deserialize AST from ESTree-style JSON, in the format produced by `to_json`.
+///
+/// Nodes are allocated in `alloc`, and "back-links" to parents are set on each node.
+pub fn from_json<'a>(
+    json: &str,
+    alloc: &'a Allocator,
+) -> Result<&'a mut Program<'a>, DeserializeError> {
+    let value: Value = serde_json::from_str(json)?;
+    let deserializer = Deserializer {
+        ast: AstBuilder::new(alloc),
+        interner: Interner::new(alloc),
+    };
+    deserializer.program(&value)
+}
+
+type Object = Map<String, Value>;
+
+struct Deserializer<'a> {
+    ast: AstBuilder<'a>,
+    interner: Interner<'a>,
+}
+
+impl<'a> Deserializer<'a> {
+    fn program(&self, value: &Value) -> Result<&'a mut Program<'a>, DeserializeError> {
+        let (ty, obj) = node(value, "Program")?;
+        if ty != "Program" {
+            return Err(unknown_type("Program", ty));
+        }
+        let body = self.statements(array(obj, "Program", "body")?)?;
+        Ok(self.ast.program(body))
+    }
+
+    fn statements(&self, values: &[Value]) -> Result<Vec<'a, Statement<'a>>, DeserializeError> {
+        let mut stmts = self.ast.vec();
+        for value in values {
+            stmts.push(self.statement(value)?);
+        }
+        Ok(stmts)
+    }
+
+    fn statement(&self, value: &Value) -> Result<Statement<'a>, DeserializeError> {
+        let (ty, obj) = node(value, "statement")?;
+        let span = span(obj, ty)?;
+        let stmt = match ty {
+            "ExpressionStatement" => {
+                let expression = self.expression(field(obj, ty, "expression")?)?;
+                self.ast.expression_statement(span, expression)
+            }
+            "BlockStatement" => {
+                let body = self.statements(array(obj, ty, "body")?)?;
+                self.ast.block_statement(span, body)
+            }
+            "IfStatement" => {
+                let test = self.expression(field(obj, ty, "test")?)?;
+                let consequent = self.statement(field(obj, ty, "consequent")?)?;
+                let alternate = match optional(obj, "alternate") {
+                    Some(alternate) => Some(self.statement(alternate)?),
+                    None => None,
+                };
+                self.ast.if_statement(span, test, consequent, alternate)
+            }
+            "ReturnStatement" => {
+                let argument = match optional(obj, "argument") {
+                    Some(argument) => Some(self.expression(argument)?),
+                    None => None,
+                };
+                self.ast.return_statement(span, argument)
+            }
+            "VariableDeclaration" => {
+                let kind = enum_field(obj, ty, "kind")?;
+                let mut declarations = self.ast.vec();
+                for declarator in array(obj, ty, "declarations")? {
+                    declarations.push(self.variable_declarator(declarator)?);
+                }
+                self.ast.variable_declaration(span, kind, declarations)
+            }
+            _ => return Err(unknown_type("statement", ty)),
+        };
+        Ok(stmt)
+    }
+
+    fn variable_declarator(
+        &self,
+        value: &Value,
+    ) -> Result<Box<'a, VariableDeclarator<'a>>, DeserializeError> {
+        let (ty, obj) = node(value, "VariableDeclarator")?;
+        if ty != "VariableDeclarator" {
+            return Err(unknown_type("VariableDeclarator", ty));
+        }
+        let span = span(obj, ty)?;
+
+        let (id_ty, id_obj) = node(field(obj, ty, "id")?, "Identifier")?;
+        if id_ty != "Identifier" {
+            return Err(unknown_type("Identifier", id_ty));
+        }
+        let id = self.ast.binding_identifier(
+            self::span(id_obj, id_ty)?,
+            self.str_field(id_obj, id_ty, "name")?,
+        );
+
+        let init = match optional(obj, "init") {
+            Some(init) => Some(self.expression(init)?),
+            None => None,
+        };
+        Ok(self.ast.variable_declarator(span, id, init))
+    }
+
+    fn property(&self, value: &Value) -> Result<Box<'a, Property<'a>>, DeserializeError> {
+        let (ty, obj) = node(value, "Property")?;
+        if ty != "Property" {
+            return Err(unknown_type("Property", ty));
+        }
+        let span = span(obj, ty)?;
+        let computed = field(obj, ty, "computed")?
+            .as_bool()
+            .ok_or_else(|| invalid_field(ty, "computed"))?;
+        let shorthand = field(obj, ty, "shorthand")?
+            .as_bool()
+            .ok_or_else(|| invalid_field(ty, "shorthand"))?;
+        Ok(self.ast.property(
+            span,
+            self.expression(field(obj, ty, "key")?)?,
+            self.expression(field(obj, ty, "value")?)?,
+            computed,
+            shorthand,
+        ))
+    }
+
+    fn expression(&self, value: &Value) -> Result<Expression<'a>, DeserializeError> {
+        let (ty, obj) = node(value, "expression")?;
+        let span = span(obj, ty)?;
+        let expr = match ty {
+            "Identifier" => self
+                .ast
+                .identifier_reference(span, self.str_field(obj, ty, "name")?),
+            "StringLiteral" => {
+                let raw = match optional(obj, "raw") {
+                    Some(_) => Some(self.str_field(obj, ty, "raw")?),
+                    None => None,
+                };
+                self.ast
+                    .string_literal(span, self.str_field(obj, ty, "value")?, raw)
+            }
+            "NumericLiteral" => {
+                let value = field(obj, ty, "value")?
+                    .as_f64()
+                    .ok_or_else(|| invalid_field(ty, "value"))?;
+                self.ast.numeric_literal(span, value)
+            }
+            "BooleanLiteral" => {
+                let value = field(obj, ty, "value")?
+                    .as_bool()
+                    .ok_or_else(|| invalid_field(ty, "value"))?;
+                self.ast.boolean_literal(span, value)
+            }
+            "NullLiteral" => self.ast.null_literal(span),
+            "BinaryExpression" => self.ast.binary_expression(
+                span,
+                self.expression(field(obj, ty, "left")?)?,
+                enum_field(obj, ty, "operator")?,
+                self.expression(field(obj, ty, "right")?)?,
+            ),
+            "UnaryExpression" => self.ast.unary_expression(
+                span,
+                enum_field(obj, ty, "operator")?,
+                self.expression(field(obj, ty, "argument")?)?,
+            ),
+            "CallExpression" => {
+                let callee = self.expression(field(obj, ty, "callee")?)?;
+                let mut arguments = self.ast.vec();
+                for arg in array(obj, ty, "arguments")? {
+                    arguments.push(self.expression(arg)?);
+                }
+                self.ast.call_expression(span, callee, arguments)
+            }
+            "MemberExpression" => {
+                let computed = field(obj, ty, "computed")?
+                    .as_bool()
+                    .ok_or_else(|| invalid_field(ty, "computed"))?;
+                self.ast.member_expression(
+                    span,
+                    self.expression(field(obj, ty, "object")?)?,
+                    self.expression(field(obj, ty, "property")?)?,
+                    computed,
+                )
+            }
+            "LogicalExpression" => self.ast.logical_expression(
+                span,
+                self.expression(field(obj, ty, "left")?)?,
+                enum_field(obj, ty, "operator")?,
+                self.expression(field(obj, ty, "right")?)?,
+            ),
+            "ConditionalExpression" => self.ast.conditional_expression(
+                span,
+                self.expression(field(obj, ty, "test")?)?,
+                self.expression(field(obj, ty, "consequent")?)?,
+                self.expression(field(obj, ty, "alternate")?)?,
+            ),
+            "AssignmentExpression" => self.ast.assignment_expression(
+                span,
+                self.expression(field(obj, ty, "left")?)?,
+                enum_field(obj, ty, "operator")?,
+                self.expression(field(obj, ty, "right")?)?,
+            ),
+            "TemplateLiteral" => {
+                let mut quasis = self.ast.vec();
+                for quasi in array(obj, ty, "quasis")? {
+                    let quasi = quasi.as_str().ok_or_else(|| invalid_field(ty, "quasis"))?;
+                    quasis.push(self.interner.intern(quasi));
+                }
+                let mut expressions = self.ast.vec();
+                for expr in array(obj, ty, "expressions")? {
+                    expressions.push(self.expression(expr)?);
+                }
+                if quasis.len() != expressions.len() + 1 {
+                    return Err(invalid_field(ty, "quasis"));
+                }
+                self.ast.template_literal(span, quasis, expressions)
+            }
+            "ArrayExpression" => {
+                let mut elements = self.ast.vec();
+                for element in array(obj, ty, "elements")? {
+                    // `null` is a hole
+                    let element = match element {
+                        Value::Null => None,
+                        element => Some(self.expression(element)?),
+                    };
+                    elements.push(element);
+                }
+                self.ast.array_expression(span, elements)
+            }
+            "ObjectExpression" => {
+                let mut properties = self.ast.vec();
+                for prop in array(obj, ty, "properties")? {
+                    properties.push(self.property(prop)?);
+                }
+                self.ast.object_expression(span, properties)
+            }
+            _ => return Err(unknown_type("expression", ty)),
+        };
+        Ok(expr)
+    }
+
+    /// Get string field, and copy it into arena.
+    fn str_field(&self, obj: &Object, ty: &str, name: &str) -> Result<&'a str, DeserializeError> {
+        let s = field(obj, ty, name)?
+            .as_str()
+            .ok_or_else(|| invalid_field(ty, name))?;
+        Ok(self.interner.intern(s))
+    }
+}
+
+/// Get node's `type` and its fields.
+/// `expected` describes what kind of node was expected, for error messages.
+fn node<'v>(value: &'v Value, expected: &str) -> Result<(&'v str, &'v Object), DeserializeError> {
+    let obj = value.as_object().ok_or_else(|| DeserializeError {
+        message: format!("Expected {expected} node, found `{value}`"),
+    })?;
+    let ty = obj
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or_else(|| DeserializeError {
+            message: format!("Expected {expected} node, found object with no `type` field"),
+        })?;
+    Ok((ty, obj))
+}
+
+fn field<'v>(obj: &'v Object, ty: &str, name: &str) -> Result<&'v Value, DeserializeError> {
+    obj.get(name).ok_or_else(|| DeserializeError {
+        message: format!("Missing field `{name}` in {ty}"),
+    })
+}
+
+/// Get optional field. Absent field and `null` are both treated as `None`.
+fn optional<'v>(obj: &'v Object, name: &str) -> Option<&'v Value> {
+    obj.get(name).filter(|value| !value.is_null())
+}
+
+fn array<'v>(obj: &'v Object, ty: &str, name: &str) -> Result<&'v [Value], DeserializeError> {
+    field(obj, ty, name)?
+        .as_array()
+        .map(|values| values.as_slice())
+        .ok_or_else(|| invalid_field(ty, name))
+}
+
+fn span(obj: &Object, ty: &str) -> Result<Span, DeserializeError> {
+    let offset = |name| {
+        field(obj, ty, name)?
+            .as_u64()
+            .and_then(|offset| u32::try_from(offset).ok())
+            .ok_or_else(|| invalid_field(ty, name))
+    };
+    Ok(Span {
+        start: offset("start")?,
+        end: offset("end")?,
+    })
+}
+
+/// Deserialize an operator or `VarKind` field.
+fn enum_field<T: DeserializeOwned>(
+    obj: &Object,
+    ty: &str,
+    name: &str,
+) -> Result<T, DeserializeError> {
+    let value = field(obj, ty, name)?;
+    T::deserialize(value).map_err(|_| DeserializeError {
+        message: format!("Invalid `{name}` in {ty}: {value}"),
+    })
+}
+
+fn unknown_type(expected: &str, ty: &str) -> DeserializeError {
+    DeserializeError {
+        message: format!("Unknown {expected} type `{ty}`"),
+    }
+}
+
+fn invalid_field(ty: &str, name: &str) -> DeserializeError {
+    DeserializeError {
+        message: format!("Invalid value for field `{name}` in {ty}"),
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+    use crate::{compare::stmt_eq, parents::validate_tree, parser::parse_str, print::Printer};
+
+    #[test]
+    fn round_trip() {
+        let source =
+            "let x = a + 1; if (!x) { f(`a${b}`, [1, , 'c'], { d: e }); } else y ??= z || null;";
+        let alloc = Allocator::default();
+        let program = parse_str(source, &alloc).unwrap();
+        let json = to_json(program);
+        assert!(json.contains(r#""type":"BinaryExpression","start":8,"end":13,"#));
+        assert!(json.contains(r#""operator":"+""#));
+        assert!(json.contains(r#""operator":"!""#));
+        assert!(json.contains(r#""operator":"??=""#));
+        assert!(json.contains(r#""operator":"||""#));
+
+        let deserialized = from_json(&json, &alloc).unwrap();
+        assert_eq!(deserialized.body.len(), program.body.len());
+        assert!(program
+            .body
+            .iter()
+            .zip(deserialized.body.iter())
+            .all(|(a, b)| stmt_eq(a, b)));
+        assert!(validate_tree(deserialized).is_ok());
+        assert_eq!(Printer::print(deserialized), Printer::print(program));
+        assert_eq!(to_json(deserialized), json);
+    }
+
+    #[test]
+    fn malformed() {
+        let error = |json: &str| {
+            let alloc = Allocator::default();
+            from_json(json, &alloc).unwrap_err().to_string()
+        };
+        assert!(error(r#"{"type":"Program","body":["#).starts_with("Invalid JSON: "));
+        assert_eq!(
+            error(r#"{"type":"Statement"}"#),
+            "Unknown Program type `Statement`"
+        );
+        assert_eq!(
+            error(r#"{"type":"Program"}"#),
+            "Missing field `body` in Program"
+        );
+        assert_eq!(
+            error(r#"{"type":"Program","body":[{"type":"Foo","start":0,"end":1}]}"#),
+            "Unknown statement type `Foo`"
+        );
+        assert_eq!(
+            error(
+                r#"{"type":"Program","body":[{"type":"ExpressionStatement","start":0,"end":7,
+                "expression":{"type":"BinaryExpression","start":0,"end":7,
+                "left":{"type":"Identifier","start":0,"end":1,"name":"a"},"operator":"<=>",
+                "right":{"type":"Identifier","start":6,"end":7,"name":"b"}}}]}"#
+            ),
+            r#"Invalid `operator` in BinaryExpression: "<=>""#
+        );
+        assert_eq!(
+            error(r#"{"type":"Program","body":[{"type":"ReturnStatement","start":-1,"end":1}]}"#),
+            "Invalid value for field `start` in ReturnStatement"
+        );
+    }
+}
@@ -0,0 +1,450 @@
+//! Lexer for the subset of JS supported by the parser.
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum TokenKind {
+    Ident,
+    Str,
+    Number,
+    True,
+    False,
+    Null,
+    Typeof,
+    Void,
+    Delete,
+    In,
+    Instanceof,
+    If,
+    Else,
+    Return,
+    Var,
+    Let,
+    Const,
+    /// `!`
+    Bang,
+    /// `-`
+    Minus,
+    /// `+`
+    Plus,
+    /// `~`
+    Tilde,
+    /// `*`
+    Star,
+    /// `**`
+    StarStar,
+    /// `/`
+    Slash,
+    /// `%`
+    Percent,
+    /// `<`
+    Lt,
+    /// `<=`
+    LtEq,
+    /// `<<`
+    LtLt,
+    /// `>`
+    Gt,
+    /// `>=`
+    GtEq,
+    /// `>>`
+    GtGt,
+    /// `>>>`
+    GtGtGt,
+    /// `&`
+    Amp,
+    /// `|`
+    Pipe,
+    /// `^`
+    Caret,
+    /// `=`
+    Eq,
+    /// `+=`
+    PlusEq,
+    /// `-=`
+    MinusEq,
+    /// `*=`
+    StarEq,
+    /// `/=`
+    SlashEq,
+    /// `%=`
+    PercentEq,
+    /// `&&=`
+    AmpAmpEq,
+    /// `||=`
+    PipePipeEq,
+    /// `??=`
+    QuestionQuestionEq,
+    /// `==`
+    EqEq,
+    /// `===`
+    EqEqEq,
+    /// `!=`
+    BangEq,
+    /// `!==`
+    BangEqEq,
+    /// `&&`
+    AmpAmp,
+    /// `||`
+    PipePipe,
+    /// `??`
+    QuestionQuestion,
+    /// `?`
+    Question,
+    /// `:`
+    Colon,
+    /// `(`
+    LParen,
+    /// `)`
+    RParen,
+    /// `{`
+    LBrace,
+    /// `}`
+    RBrace,
+    /// `[`
+    LBracket,
+    /// `]`
+    RBracket,
+    /// `.`
+    Dot,
+    /// `;`
+    Semicolon,
+    /// `,`
+    Comma,
+    /// Template literal with no substitutions, `` `...` ``
+    Template,
+    /// Start of template literal, up to first substitution, `` `...${ ``
+    TemplateHead,
+    /// Part of template literal between 2 substitutions, `}...${`
+    TemplateMiddle,
+    /// End of template literal, after last substitution, `` }...` ``
+    TemplateTail,
+    /// `// ...` comment. Only appears in comments list, not in tokens.
+    LineComment,
+    /// `/* ... */` comment. Only appears in comments list, not in tokens.
+    BlockComment,
+    /// Unexpected character, or unterminated string, template literal, or comment
+    Error,
+    /// End of source
+    Eof,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// Byte offset of start of token
+    pub start: usize,
+    /// Byte offset of end of token (exclusive)
+    pub end: usize,
+}
+
+/// Split source text into tokens, skipping whitespace.
+///
+/// Comments are skipped too, and returned separately as `LineComment` / `BlockComment` tokens,
+/// in source order.
+///
+/// Lexing does not stop at errors. Unexpected characters produce a `TokenKind::Error` token,
+/// and lexing continues after them. The last token is always `TokenKind::Eof`.
+pub fn tokenize(source: &str) -> (Vec<Token>, Vec<Token>) {
+    let mut lexer = Lexer {
+        source,
+        pos: 0,
+        braces: vec![],
+        comments: vec![],
+    };
+    let mut tokens = vec![];
+    loop {
+        let token = lexer.next_token();
+        tokens.push(token);
+        if token.kind == TokenKind::Eof {
+            return (tokens, lexer.comments);
+        }
+    }
+}
+
+struct Lexer<'a> {
+    source: &'a str,
+    pos: usize,
+    /// Open braces. `true` for `${` in a template literal, `false` for `{`.
+    /// A `}` closing a `${` resumes lexing the template literal.
+    braces: Vec<bool>,
+    comments: Vec<Token>,
+}
+
+impl<'a> Lexer<'a> {
+    fn next_token(&mut self) -> Token {
+        self.skip_trivia();
+        let start = self.pos;
+        let kind = match self.peek() {
+            None => TokenKind::Eof,
+            Some(b'!') => self.punctuator(&[
+                ("!==", TokenKind::BangEqEq),
+                ("!=", TokenKind::BangEq),
+                ("!", TokenKind::Bang),
+            ]),
+            Some(b'-') => self.with_eq(1, TokenKind::Minus, TokenKind::MinusEq),
+            Some(b'+') => self.with_eq(1, TokenKind::Plus, TokenKind::PlusEq),
+            Some(b'*') => self.punctuator(&[
+                ("**", TokenKind::StarStar),
+                ("*=", TokenKind::StarEq),
+                ("*", TokenKind::Star),
+            ]),
+            Some(b'/') if self.source[self.pos..].starts_with("/*") => {
+                // Unterminated block comment. `skip_trivia` skips terminated ones.
+                self.pos = self.source.len();
+                TokenKind::Error
+            }
+            Some(b'/') => self.with_eq(1, TokenKind::Slash, TokenKind::SlashEq),
+            Some(b'%') => self.with_eq(1, TokenKind::Percent, TokenKind::PercentEq),
+            Some(b'~') => self.single(TokenKind::Tilde),
+            Some(b'^') => self.single(TokenKind::Caret),
+            Some(b'<') => self.punctuator(&[
+                ("<<", TokenKind::LtLt),
+                ("<=", TokenKind::LtEq),
+                ("<", TokenKind::Lt),
+            ]),
+            Some(b'>') => self.punctuator(&[
+                (">>>", TokenKind::GtGtGt),
+                (">>", TokenKind::GtGt),
+                (">=", TokenKind::GtEq),
+                (">", TokenKind::Gt),
+            ]),
+            Some(b'(') => self.single(TokenKind::LParen),
+            Some(b')') => self.single(TokenKind::RParen),
+            Some(b'{') => {
+                self.braces.push(false);
+                self.single(TokenKind::LBrace)
+            }
+            Some(b'}') => {
+                if self.braces.pop() == Some(true) {
+                    self.template(TokenKind::TemplateMiddle, TokenKind::TemplateTail)
+                } else {
+                    self.single(TokenKind::RBrace)
+                }
+            }
+            Some(b'[') => self.single(TokenKind::LBracket),
+            Some(b']') => self.single(TokenKind::RBracket),
+            Some(b'.') => self.single(TokenKind::Dot),
+            Some(b';') => self.single(TokenKind::Semicolon),
+            Some(b',') => self.single(TokenKind::Comma),
+            Some(b'=') => self.equals(),
+            Some(b'&') => self.punctuator(&[
+                ("&&=", TokenKind::AmpAmpEq),
+                ("&&", TokenKind::AmpAmp),
+                ("&", TokenKind::Amp),
+            ]),
+            Some(b'|') => self.punctuator(&[
+                ("||=", TokenKind::PipePipeEq),
+                ("||", TokenKind::PipePipe),
+                ("|", TokenKind::Pipe),
+            ]),
+            Some(b'?') => self.question(),
+            Some(b':') => self.single(TokenKind::Colon),
+            Some(quote @ (b'\'' | b'"')) => self.string(quote),
+            Some(b'`') => self.template(TokenKind::TemplateHead, TokenKind::Template),
+            Some(b'0'..=b'9') => self.number(),
+            Some(b) if is_identifier_start(b) => self.identifier(),
+            Some(_) => {
+                // Skip whole char, so token span is always on a char boundary
+                let ch = self.source[self.pos..].chars().next().unwrap();
+                self.pos += ch.len_utf8();
+                TokenKind::Error
+            }
+        };
+        Token {
+            kind,
+            start,
+            end: self.pos,
+        }
+    }
+
+    fn single(&mut self, kind: TokenKind) -> TokenKind {
+        self.pos += 1;
+        kind
+    }
+
+    /// Consume `len` bytes, plus a following `=` if there is one.
+    /// Returns `eq_kind` if `=` was consumed, otherwise `kind`.
+    fn with_eq(&mut self, len: usize, kind: TokenKind, eq_kind: TokenKind) -> TokenKind {
+        self.pos += len;
+        if self.peek() == Some(b'=') {
+            self.pos += 1;
+            eq_kind
+        } else {
+            kind
+        }
+    }
+
+    /// Lex the first of `options` which matches source at current position.
+    /// `options` must be ordered longest first, and the last must always match.
+    fn punctuator(&mut self, options: &[(&str, TokenKind)]) -> TokenKind {
+        let rest = &self.source[self.pos..];
+        let &(text, kind) = options
+            .iter()
+            .find(|(text, _)| rest.starts_with(text))
+            .unwrap();
+        self.pos += text.len();
+        kind
+    }
+
+    fn question(&mut self) -> TokenKind {
+        if self.source.as_bytes().get(self.pos + 1) == Some(&b'?') {
+            self.with_eq(
+                2,
+                TokenKind::QuestionQuestion,
+                TokenKind::QuestionQuestionEq,
+            )
+        } else {
+            self.pos += 1;
+            TokenKind::Question
+        }
+    }
+
+    fn equals(&mut self) -> TokenKind {
+        let rest = &self.source.as_bytes()[self.pos..];
+        if rest.starts_with(b"===") {
+            self.pos += 3;
+            TokenKind::EqEqEq
+        } else if rest.starts_with(b"==") {
+            self.pos += 2;
+            TokenKind::EqEq
+        } else {
+            self.pos += 1;
+            TokenKind::Eq
+        }
+    }
+
+    fn string(&mut self, quote: u8) -> TokenKind {
+        self.pos += 1;
+        loop {
+            match self.peek() {
+                None => {
+                    // Unterminated string
+                    self.pos = self.source.len();
+                    return TokenKind::Error;
+                }
+                Some(b'\\') => self.pos += 2,
+                Some(b) if b == quote => {
+                    self.pos += 1;
+                    return TokenKind::Str;
+                }
+                Some(_) => self.pos += 1,
+            }
+        }
+    }
+
+    /// Lex part of a template literal, starting at opening `` ` `` or `}`.
+    /// Returns `head_kind` if it ends with `${`, or `tail_kind` if it ends with `` ` ``.
+    fn template(&mut self, head_kind: TokenKind, tail_kind: TokenKind) -> TokenKind {
+        self.pos += 1;
+        loop {
+            match self.peek() {
+                None => {
+                    // Unterminated template literal
+                    self.pos = self.source.len();
+                    return TokenKind::Error;
+                }
+                Some(b'\\') => self.pos += 2,
+                Some(b'`') => {
+                    self.pos += 1;
+                    return tail_kind;
+                }
+                Some(b'$') if self.source.as_bytes().get(self.pos + 1) == Some(&b'{') => {
+                    self.pos += 2;
+                    self.braces.push(true);
+                    return head_kind;
+                }
+                Some(_) => self.pos += 1,
+            }
+        }
+    }
+
+    /// Lex a decimal number, with optional fraction and exponent, e.g. `1`, `1.5`, `1.5e-7`.
+    fn number(&mut self) -> TokenKind {
+        self.eat_digits();
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            self.eat_digits();
+        }
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            self.pos += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) {
+                self.pos += 1;
+            }
+            // Exponent must have at least 1 digit
+            if !matches!(self.peek(), Some(b'0'..=b'9')) {
+                return TokenKind::Error;
+            }
+            self.eat_digits();
+        }
+        TokenKind::Number
+    }
+
+    fn identifier(&mut self) -> TokenKind {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b) if is_identifier_part(b)) {
+            self.pos += 1;
+        }
+        match &self.source[start..self.pos] {
+            "true" => TokenKind::True,
+            "false" => TokenKind::False,
+            "null" => TokenKind::Null,
+            "typeof" => TokenKind::Typeof,
+            "void" => TokenKind::Void,
+            "delete" => TokenKind::Delete,
+            "in" => TokenKind::In,
+            "instanceof" => TokenKind::Instanceof,
+            "if" => TokenKind::If,
+            "else" => TokenKind::Else,
+            "return" => TokenKind::Return,
+            "var" => TokenKind::Var,
+            "let" => TokenKind::Let,
+            "const" => TokenKind::Const,
+            _ => TokenKind::Ident,
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.source.as_bytes().get(self.pos).copied()
+    }
+
+    fn eat_digits(&mut self) {
+        while matches!(self.peek(), Some(b'0'..=b'9')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skip whitespace and comments. Comments are added to `comments`.
+    fn skip_trivia(&mut self) {
+        loop {
+            match self.peek() {
+                Some(b' ' | b'\t' | b'\n' | b'\r') => self.pos += 1,
+                Some(b'/') => {
+                    let start = self.pos;
+                    let rest = &self.source[start..];
+                    let kind = if rest.starts_with("//") {
+                        self.pos += rest.find(['\n', '\r']).unwrap_or(rest.len());
+                        TokenKind::LineComment
+                    } else if let Some(end) = rest.strip_prefix("/*").and_then(|r| r.find("*/")) {
+                        self.pos += end + 4;
+                        TokenKind::BlockComment
+                    } else {
+                        return;
+                    };
+                    self.comments.push(Token {
+                        kind,
+                        start,
+                        end: self.pos,
+                    });
+                }
+                _ => return,
+            }
+        }
+    }
+}
+
+fn is_identifier_start(b: u8) -> bool {
+    b.is_ascii_alphabetic() || b == b'_' || b == b'$'
+}
+
+fn is_identifier_part(b: u8) -> bool {
+    is_identifier_start(b) || b.is_ascii_digit()
+}
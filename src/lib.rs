@@ -0,0 +1,43 @@
+// Without the default `std` feature, the crate is `no_std`. The parser, and the modules which
+// use `std` collections, are left out. The AST core (`ast`, `builder`, `cell`, `print`, `visit`,
+// `visit_mut`, `traverse`) only needs `core` and `alloc`.
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(all(test, feature = "bench"), feature(test))]
+
+extern crate alloc;
+
+pub mod ast;
+#[cfg(all(test, feature = "bench"))]
+mod bench;
+pub mod builder;
+pub mod cell;
+pub mod compare;
+#[cfg(feature = "std")]
+pub mod dump;
+#[cfg(feature = "std")]
+pub mod interner;
+#[cfg(feature = "serde")]
+pub mod json;
+#[cfg(feature = "std")]
+pub mod lexer;
+#[cfg(feature = "std")]
+pub mod parents;
+#[cfg(feature = "std")]
+pub mod parser;
+pub mod print;
+pub mod source_text;
+pub mod stats;
+pub mod transforms;
+pub mod traverse;
+pub mod visit;
+pub mod visit_mut;
+#[cfg(feature = "std")]
+pub mod visitors;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use visit::Visit;
+
+// TODO: Implement semantic as a `Traverse` to set parents on nodes, rather than doing it in parser.
+// Doing it in parser is unsound, as the pointers are incorrectly tagged. Miri says it's UB.
+// TODO: Make `parent` fields inaccessible in standard AST, so user cannot alter them.
@@ -1,62 +1,59 @@
-use oxc_allocator::Allocator;
-
-mod ast;
-mod cell;
-mod parser;
-mod print;
-mod traverse;
-mod visit;
-use ast::{
-    traversable::{Expression, ExpressionParent, UnaryExpression},
-    BinaryOperator, UnaryOperator,
+#[cfg(feature = "arena_stats")]
+use ast_cell_test::stats;
+use ast_cell_test::{
+    ast::{self, UnaryOperator},
+    builder::AstBuilder,
+    parser,
+    print::Printer,
+    source_text::SourceText,
+    transforms::{TransformConstantFold, TransformStrictEquality, TransformTypeof},
+    traverse::transform,
+    visit_mut::VisitMut,
+    visitors::{ExpressionFinder, FreeIdentifiers},
 };
-use cell::{gcell, Token};
-use print::Printer;
-use traverse::{transform, Traverse};
-use visit::Visit;
+use oxc_allocator::Allocator;
 
-// TODO: Implement semantic as a `Traverse` to set parents on nodes, rather than doing it in parser.
-// Doing it in parser is unsound, as the pointers are incorrectly tagged. Miri says it's UB.
-// TODO: Make `parent` fields inaccessible in standard AST, so user cannot alter them.
+/// Parse `source`, or print the error with its location and exit.
+fn parse<'a>(source: &'a str, alloc: &'a Allocator) -> &'a mut ast::Program<'a> {
+    parser::parse_str(source, alloc).unwrap_or_else(|err| {
+        eprintln!("{}", err.render(&SourceText::new(source)));
+        std::process::exit(1);
+    })
+}
 
 fn main() {
     let alloc = Allocator::default();
-    let program = parser::parse(&alloc);
+    let source = "typeof foo === 'object'";
+    let program = parse(source, &alloc);
     println!("before: {}", Printer::print(program));
 
+    let first_typeof = ExpressionFinder::find(
+        |expr| {
+            matches!(expr, ast::Expression::UnaryExpression(unary_expr)
+                if unary_expr.operator == UnaryOperator::Typeof)
+        },
+        program,
+    );
+    println!("first typeof: {first_typeof:?}");
+    println!("free identifiers: {:?}", FreeIdentifiers::collect(program));
+
     transform(&mut TransformTypeof, program);
     println!("after: {}", Printer::print(program));
-}
-
-/// Transformer for `typeof x === 'y'` to `'y' === typeof x`
-struct TransformTypeof;
+    #[cfg(feature = "arena_stats")]
+    println!("arena bytes: {}", stats::arena_bytes_allocated(&alloc));
 
-impl<'a, 't> Traverse<'a, 't> for TransformTypeof {
-    fn visit_unary_expression(
-        &mut self,
-        unary_expr: &gcell!(UnaryExpression<'a, 't>),
-        tk: &mut Token<'t>,
-    ) {
-        self.walk_unary_expression(unary_expr, tk);
+    let source = "!true; -5; !!'x'; 'a' === 'b'; typeof null";
+    let program = parse(source, &alloc);
+    println!("before fold: {}", Printer::print(program));
 
-        if unary_expr.borrow(tk).operator == UnaryOperator::Typeof {
-            if let ExpressionParent::BinaryExpressionLeft(bin_expr) = unary_expr.borrow(tk).parent {
-                if matches!(
-                    bin_expr.borrow(tk).operator,
-                    BinaryOperator::Equality | BinaryOperator::StrictEquality
-                ) {
-                    if let Expression::StringLiteral(str_lit) = bin_expr.borrow(tk).right {
-                        // Swap left and right of binary expression
-                        let bin_expr_mut = bin_expr.borrow_mut(tk);
-                        std::mem::swap(&mut bin_expr_mut.left, &mut bin_expr_mut.right);
-
-                        // Update parent links of left and right
-                        let temp = str_lit.borrow(tk).parent;
-                        str_lit.borrow_mut(tk).parent = unary_expr.borrow(tk).parent;
-                        unary_expr.borrow_mut(tk).parent = temp;
-                    }
-                }
-            }
-        }
+    TransformConstantFold {
+        ast: AstBuilder::new(&alloc),
     }
+    .visit_program(program);
+    println!("after fold: {}", Printer::print(program));
+
+    let source = "typeof foo == 'object'";
+    let program = parse(source, &alloc);
+    TransformStrictEquality.visit_program(program);
+    println!("strict equality: {}", Printer::print(program));
 }
@@ -0,0 +1,870 @@
+//! Validation of parent links ("back-links") in AST.
+
+use std::fmt;
+
+use crate::{
+    ast::{
+        traversable::{
+            Expression as TraversableExpression, ExpressionParent as TraversableExpressionParent,
+            Program as TraversableProgram, Statement as TraversableStatement,
+            StatementParent as TraversableStatementParent,
+        },
+        ArrayExpression, AssignmentExpression, BinaryExpression, BlockStatement, CallExpression,
+        ConditionalExpression, Expression, ExpressionParent, ExpressionStatement, IfStatement,
+        LogicalExpression, MemberExpression, ObjectExpression, Program, Property, PropertyParent,
+        ReturnStatement, Span, Statement, StatementParent, TemplateLiteral,
+        TraversablePropertyParent, TraversableVariableDeclaratorParent, UnaryExpression,
+        VariableDeclaration, VariableDeclarator, VariableDeclaratorParent,
+    },
+    cell::{GCell, Token},
+    source_text::SourceText,
+    stats::AstType,
+    visit_mut::VisitMut,
+    Visit,
+};
+
+/// Error produced when a node's parent link does not point to the node which contains it.
+#[derive(Debug)]
+pub struct TreeError {
+    /// Type of the node with incorrect parent link
+    pub node: AstType,
+    /// Span of the node with incorrect parent link
+    pub span: Span,
+}
+
+impl fmt::Display for TreeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Incorrect parent link on {:?} at {}..{}",
+            self.node, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for TreeError {}
+
+impl TreeError {
+    /// Format error with location of the node as `line:column` in `source`,
+    /// instead of as byte offsets, followed by the line of source with a caret under the node.
+    pub fn render(&self, source: &SourceText<'_>) -> String {
+        let (line, column) = source.line_col(self.span.start);
+        format!(
+            "Incorrect parent link on {:?} at {line}:{column}\n{}",
+            self.node,
+            source.caret(self.span.start)
+        )
+    }
+}
+
+/// Check every node's `parent` link points to the node which contains it,
+/// and refers to the field of that node which contains it.
+///
+/// Returns the first incorrect link found, in traversal order.
+///
+/// Nodes are checked top-down from the root, so if this returns `Ok`, then following
+/// `parent` links upwards from any node always terminates at the `Program`. i.e. There are no cycles.
+pub fn validate_tree(program: &Program<'_>) -> Result<(), TreeError> {
+    let mut validator = ParentValidator { error: None };
+    validator.visit_program(program);
+    match validator.error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
+
+struct ParentValidator {
+    error: Option<TreeError>,
+}
+
+impl ParentValidator {
+    fn check_statement<'a>(&mut self, stmt: &Statement<'a>, expected: StatementParent<'a>) {
+        if self.error.is_none() && stmt.parent() != expected {
+            self.error = Some(TreeError {
+                node: AstType::of_statement(stmt),
+                span: stmt.span(),
+            });
+        }
+    }
+
+    fn check_expression<'a>(&mut self, expr: &Expression<'a>, expected: ExpressionParent<'a>) {
+        if self.error.is_none() && expr.parent() != expected {
+            self.error = Some(TreeError {
+                node: AstType::of_expression(expr),
+                span: expr.span(),
+            });
+        }
+    }
+}
+
+impl<'a> Visit<'a> for ParentValidator {
+    fn visit_program(&mut self, program: &Program<'a>) {
+        for stmt in &program.body {
+            self.check_statement(stmt, StatementParent::Program(program));
+        }
+        self.walk_program(program);
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement<'a>) {
+        // Stop once an error has been found
+        if self.error.is_none() {
+            self.walk_statement(stmt);
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'a>) {
+        if self.error.is_none() {
+            self.walk_expression(expr);
+        }
+    }
+
+    fn visit_expression_statement(&mut self, expr_stmt: &ExpressionStatement<'a>) {
+        self.check_expression(
+            &expr_stmt.expression,
+            ExpressionParent::ExpressionStatement(expr_stmt),
+        );
+        self.walk_expression_statement(expr_stmt);
+    }
+
+    fn visit_block_statement(&mut self, block: &BlockStatement<'a>) {
+        for stmt in &block.body {
+            self.check_statement(stmt, StatementParent::BlockStatement(block));
+        }
+        self.walk_block_statement(block);
+    }
+
+    fn visit_if_statement(&mut self, if_stmt: &IfStatement<'a>) {
+        self.check_expression(&if_stmt.test, ExpressionParent::IfStatementTest(if_stmt));
+        self.check_statement(
+            &if_stmt.consequent,
+            StatementParent::IfStatementConsequent(if_stmt),
+        );
+        if let Some(alternate) = &if_stmt.alternate {
+            self.check_statement(alternate, StatementParent::IfStatementAlternate(if_stmt));
+        }
+        self.walk_if_statement(if_stmt);
+    }
+
+    fn visit_return_statement(&mut self, return_stmt: &ReturnStatement<'a>) {
+        if let Some(argument) = &return_stmt.argument {
+            self.check_expression(
+                argument,
+                ExpressionParent::ReturnStatementArgument(return_stmt),
+            );
+        }
+        self.walk_return_statement(return_stmt);
+    }
+
+    fn visit_variable_declaration(&mut self, var_decl: &VariableDeclaration<'a>) {
+        for declarator in &var_decl.declarations {
+            if self.error.is_none()
+                && declarator.parent != VariableDeclaratorParent::VariableDeclaration(var_decl)
+            {
+                self.error = Some(TreeError {
+                    node: AstType::VariableDeclarator,
+                    span: declarator.span,
+                });
+            }
+        }
+        self.walk_variable_declaration(var_decl);
+    }
+
+    fn visit_variable_declarator(&mut self, declarator: &VariableDeclarator<'a>) {
+        if let Some(init) = &declarator.init {
+            self.check_expression(init, ExpressionParent::VariableDeclaratorInit(declarator));
+        }
+        self.walk_variable_declarator(declarator);
+    }
+
+    fn visit_binary_expression(&mut self, bin_expr: &BinaryExpression<'a>) {
+        self.check_expression(
+            &bin_expr.left,
+            ExpressionParent::BinaryExpressionLeft(bin_expr),
+        );
+        self.check_expression(
+            &bin_expr.right,
+            ExpressionParent::BinaryExpressionRight(bin_expr),
+        );
+        self.walk_binary_expression(bin_expr);
+    }
+
+    fn visit_unary_expression(&mut self, unary_expr: &UnaryExpression<'a>) {
+        self.check_expression(
+            &unary_expr.argument,
+            ExpressionParent::UnaryExpression(unary_expr),
+        );
+        self.walk_unary_expression(unary_expr);
+    }
+
+    fn visit_call_expression(&mut self, call_expr: &CallExpression<'a>) {
+        self.check_expression(
+            &call_expr.callee,
+            ExpressionParent::CallExpressionCallee(call_expr),
+        );
+        for arg in &call_expr.arguments {
+            self.check_expression(arg, ExpressionParent::CallExpressionArgument(call_expr));
+        }
+        self.walk_call_expression(call_expr);
+    }
+
+    fn visit_member_expression(&mut self, member_expr: &MemberExpression<'a>) {
+        self.check_expression(
+            &member_expr.object,
+            ExpressionParent::MemberExpressionObject(member_expr),
+        );
+        self.check_expression(
+            &member_expr.property,
+            ExpressionParent::MemberExpressionProperty(member_expr),
+        );
+        self.walk_member_expression(member_expr);
+    }
+
+    fn visit_logical_expression(&mut self, logical_expr: &LogicalExpression<'a>) {
+        self.check_expression(
+            &logical_expr.left,
+            ExpressionParent::LogicalExpressionLeft(logical_expr),
+        );
+        self.check_expression(
+            &logical_expr.right,
+            ExpressionParent::LogicalExpressionRight(logical_expr),
+        );
+        self.walk_logical_expression(logical_expr);
+    }
+
+    fn visit_conditional_expression(&mut self, cond_expr: &ConditionalExpression<'a>) {
+        self.check_expression(
+            &cond_expr.test,
+            ExpressionParent::ConditionalExpressionTest(cond_expr),
+        );
+        self.check_expression(
+            &cond_expr.consequent,
+            ExpressionParent::ConditionalExpressionConsequent(cond_expr),
+        );
+        self.check_expression(
+            &cond_expr.alternate,
+            ExpressionParent::ConditionalExpressionAlternate(cond_expr),
+        );
+        self.walk_conditional_expression(cond_expr);
+    }
+
+    fn visit_assignment_expression(&mut self, assign_expr: &AssignmentExpression<'a>) {
+        self.check_expression(
+            &assign_expr.target,
+            ExpressionParent::AssignmentExpressionTarget(assign_expr),
+        );
+        self.check_expression(
+            &assign_expr.value,
+            ExpressionParent::AssignmentExpressionValue(assign_expr),
+        );
+        self.walk_assignment_expression(assign_expr);
+    }
+
+    fn visit_template_literal(&mut self, template_lit: &TemplateLiteral<'a>) {
+        for expr in &template_lit.expressions {
+            self.check_expression(
+                expr,
+                ExpressionParent::TemplateLiteralExpression(template_lit),
+            );
+        }
+        self.walk_template_literal(template_lit);
+    }
+
+    fn visit_array_expression(&mut self, array_expr: &ArrayExpression<'a>) {
+        for element in array_expr.elements.iter().flatten() {
+            self.check_expression(
+                element,
+                ExpressionParent::ArrayExpressionElement(array_expr),
+            );
+        }
+        self.walk_array_expression(array_expr);
+    }
+
+    fn visit_object_expression(&mut self, object_expr: &ObjectExpression<'a>) {
+        for prop in &object_expr.properties {
+            if self.error.is_none() && prop.parent != PropertyParent::ObjectExpression(object_expr)
+            {
+                self.error = Some(TreeError {
+                    node: AstType::Property,
+                    span: prop.span,
+                });
+            }
+        }
+        self.walk_object_expression(object_expr);
+    }
+
+    fn visit_property(&mut self, prop: &Property<'a>) {
+        self.check_expression(&prop.key, ExpressionParent::PropertyKey(prop));
+        self.check_expression(&prop.value, ExpressionParent::PropertyValue(prop));
+        self.walk_property(prop);
+    }
+}
+
+/// Rewrite every node's `parent` link to point to the node which contains it.
+///
+/// Links are set top-down from the root, so after this `validate_tree` always succeeds.
+/// Use after a transform which moved nodes without updating their parent links.
+pub fn fix_parents(program: &mut Program<'_>) {
+    ParentFixer.visit_program(program);
+}
+
+struct ParentFixer;
+
+impl<'a> VisitMut<'a> for ParentFixer {
+    fn visit_program(&mut self, program: &mut Program<'a>) {
+        let program_ptr = &*program as *const _;
+        for stmt in program.body.iter_mut() {
+            stmt.set_parent(StatementParent::Program(program_ptr));
+        }
+        self.walk_program(program);
+    }
+
+    fn visit_expression_statement(&mut self, expr_stmt: &mut ExpressionStatement<'a>) {
+        let expr_stmt_ptr = &*expr_stmt as *const _;
+        expr_stmt
+            .expression
+            .set_parent(ExpressionParent::ExpressionStatement(expr_stmt_ptr));
+        self.walk_expression_statement(expr_stmt);
+    }
+
+    fn visit_block_statement(&mut self, block: &mut BlockStatement<'a>) {
+        let block_ptr = &*block as *const _;
+        for stmt in block.body.iter_mut() {
+            stmt.set_parent(StatementParent::BlockStatement(block_ptr));
+        }
+        self.walk_block_statement(block);
+    }
+
+    fn visit_if_statement(&mut self, if_stmt: &mut IfStatement<'a>) {
+        let if_stmt_ptr = &*if_stmt as *const _;
+        if_stmt
+            .test
+            .set_parent(ExpressionParent::IfStatementTest(if_stmt_ptr));
+        if_stmt
+            .consequent
+            .set_parent(StatementParent::IfStatementConsequent(if_stmt_ptr));
+        if let Some(alternate) = &mut if_stmt.alternate {
+            alternate.set_parent(StatementParent::IfStatementAlternate(if_stmt_ptr));
+        }
+        self.walk_if_statement(if_stmt);
+    }
+
+    fn visit_return_statement(&mut self, return_stmt: &mut ReturnStatement<'a>) {
+        let return_stmt_ptr = &*return_stmt as *const _;
+        if let Some(argument) = &mut return_stmt.argument {
+            argument.set_parent(ExpressionParent::ReturnStatementArgument(return_stmt_ptr));
+        }
+        self.walk_return_statement(return_stmt);
+    }
+
+    fn visit_variable_declaration(&mut self, var_decl: &mut VariableDeclaration<'a>) {
+        let var_decl_ptr = &*var_decl as *const _;
+        for declarator in var_decl.declarations.iter_mut() {
+            declarator.parent = VariableDeclaratorParent::VariableDeclaration(var_decl_ptr);
+        }
+        self.walk_variable_declaration(var_decl);
+    }
+
+    fn visit_variable_declarator(&mut self, declarator: &mut VariableDeclarator<'a>) {
+        let declarator_ptr = &*declarator as *const _;
+        if let Some(init) = &mut declarator.init {
+            init.set_parent(ExpressionParent::VariableDeclaratorInit(declarator_ptr));
+        }
+        self.walk_variable_declarator(declarator);
+    }
+
+    fn visit_binary_expression(&mut self, bin_expr: &mut BinaryExpression<'a>) {
+        let bin_expr_ptr = &*bin_expr as *const _;
+        bin_expr
+            .left
+            .set_parent(ExpressionParent::BinaryExpressionLeft(bin_expr_ptr));
+        bin_expr
+            .right
+            .set_parent(ExpressionParent::BinaryExpressionRight(bin_expr_ptr));
+        self.walk_binary_expression(bin_expr);
+    }
+
+    fn visit_unary_expression(&mut self, unary_expr: &mut UnaryExpression<'a>) {
+        let unary_expr_ptr = &*unary_expr as *const _;
+        unary_expr
+            .argument
+            .set_parent(ExpressionParent::UnaryExpression(unary_expr_ptr));
+        self.walk_unary_expression(unary_expr);
+    }
+
+    fn visit_call_expression(&mut self, call_expr: &mut CallExpression<'a>) {
+        let call_expr_ptr = &*call_expr as *const _;
+        call_expr
+            .callee
+            .set_parent(ExpressionParent::CallExpressionCallee(call_expr_ptr));
+        for arg in call_expr.arguments.iter_mut() {
+            arg.set_parent(ExpressionParent::CallExpressionArgument(call_expr_ptr));
+        }
+        self.walk_call_expression(call_expr);
+    }
+
+    fn visit_member_expression(&mut self, member_expr: &mut MemberExpression<'a>) {
+        let member_expr_ptr = &*member_expr as *const _;
+        member_expr
+            .object
+            .set_parent(ExpressionParent::MemberExpressionObject(member_expr_ptr));
+        member_expr
+            .property
+            .set_parent(ExpressionParent::MemberExpressionProperty(member_expr_ptr));
+        self.walk_member_expression(member_expr);
+    }
+
+    fn visit_logical_expression(&mut self, logical_expr: &mut LogicalExpression<'a>) {
+        let logical_expr_ptr = &*logical_expr as *const _;
+        logical_expr
+            .left
+            .set_parent(ExpressionParent::LogicalExpressionLeft(logical_expr_ptr));
+        logical_expr
+            .right
+            .set_parent(ExpressionParent::LogicalExpressionRight(logical_expr_ptr));
+        self.walk_logical_expression(logical_expr);
+    }
+
+    fn visit_conditional_expression(&mut self, cond_expr: &mut ConditionalExpression<'a>) {
+        let cond_expr_ptr = &*cond_expr as *const _;
+        cond_expr
+            .test
+            .set_parent(ExpressionParent::ConditionalExpressionTest(cond_expr_ptr));
+        cond_expr
+            .consequent
+            .set_parent(ExpressionParent::ConditionalExpressionConsequent(
+                cond_expr_ptr,
+            ));
+        cond_expr
+            .alternate
+            .set_parent(ExpressionParent::ConditionalExpressionAlternate(
+                cond_expr_ptr,
+            ));
+        self.walk_conditional_expression(cond_expr);
+    }
+
+    fn visit_assignment_expression(&mut self, assign_expr: &mut AssignmentExpression<'a>) {
+        let assign_expr_ptr = &*assign_expr as *const _;
+        assign_expr
+            .target
+            .set_parent(ExpressionParent::AssignmentExpressionTarget(
+                assign_expr_ptr,
+            ));
+        assign_expr
+            .value
+            .set_parent(ExpressionParent::AssignmentExpressionValue(assign_expr_ptr));
+        self.walk_assignment_expression(assign_expr);
+    }
+
+    fn visit_template_literal(&mut self, template_lit: &mut TemplateLiteral<'a>) {
+        let template_lit_ptr = &*template_lit as *const _;
+        for expr in template_lit.expressions.iter_mut() {
+            expr.set_parent(ExpressionParent::TemplateLiteralExpression(
+                template_lit_ptr,
+            ));
+        }
+        self.walk_template_literal(template_lit);
+    }
+
+    fn visit_array_expression(&mut self, array_expr: &mut ArrayExpression<'a>) {
+        let array_expr_ptr = &*array_expr as *const _;
+        for element in array_expr.elements.iter_mut().flatten() {
+            element.set_parent(ExpressionParent::ArrayExpressionElement(array_expr_ptr));
+        }
+        self.walk_array_expression(array_expr);
+    }
+
+    fn visit_object_expression(&mut self, object_expr: &mut ObjectExpression<'a>) {
+        let object_expr_ptr = &*object_expr as *const _;
+        for prop in object_expr.properties.iter_mut() {
+            prop.parent = PropertyParent::ObjectExpression(object_expr_ptr);
+        }
+        self.walk_object_expression(object_expr);
+    }
+
+    fn visit_property(&mut self, prop: &mut Property<'a>) {
+        let prop_ptr = &*prop as *const _;
+        prop.key.set_parent(ExpressionParent::PropertyKey(prop_ptr));
+        prop.value
+            .set_parent(ExpressionParent::PropertyValue(prop_ptr));
+        self.walk_property(prop);
+    }
+}
+
+/// Check every node's `parent` link points to the node which contains it, in traversable AST.
+///
+/// Same as `validate_tree`, but for use during a transform, e.g. to catch a transform which
+/// moves a node but forgets to update its parent link.
+pub fn check_parents<'a, 't>(
+    program: &GCell<'t, TraversableProgram<'a, 't>>,
+    tk: &Token<'t>,
+) -> Result<(), TreeError> {
+    let checker = TraversableParentChecker { tk };
+    for stmt in program.borrow(tk).body.as_slice() {
+        checker.check_statement(stmt.borrow(tk), StatementParent::Program(ptr(program)))?;
+    }
+    Ok(())
+}
+
+struct TraversableParentChecker<'b, 't> {
+    tk: &'b Token<'t>,
+}
+
+impl<'b, 'a: 'b, 't> TraversableParentChecker<'b, 't> {
+    fn check_statement(
+        &self,
+        stmt: &TraversableStatement<'a, 't>,
+        expected: StatementParent<'a>,
+    ) -> Result<(), TreeError> {
+        use ExpressionParent as EP;
+        use StatementParent as SP;
+        use TraversableStatement as S;
+
+        let tk = self.tk;
+        let (node, span, parent) = match stmt {
+            S::ExpressionStatement(expr_stmt) => {
+                let expr_stmt_ref = expr_stmt.borrow(tk);
+                self.check_expression(
+                    &expr_stmt_ref.expression,
+                    EP::ExpressionStatement(ptr(expr_stmt)),
+                )?;
+                let (span, parent) = (expr_stmt_ref.span, expr_stmt_ref.parent);
+                (AstType::ExpressionStatement, span, parent)
+            }
+            S::BlockStatement(block) => {
+                let block_ref = block.borrow(tk);
+                for child in block_ref.body.as_slice() {
+                    self.check_statement(child.borrow(tk), SP::BlockStatement(ptr(block)))?;
+                }
+                (AstType::BlockStatement, block_ref.span, block_ref.parent)
+            }
+            S::IfStatement(if_stmt) => {
+                let if_stmt_ref = if_stmt.borrow(tk);
+                self.check_expression(&if_stmt_ref.test, EP::IfStatementTest(ptr(if_stmt)))?;
+                self.check_statement(
+                    &if_stmt_ref.consequent,
+                    SP::IfStatementConsequent(ptr(if_stmt)),
+                )?;
+                if let Some(alternate) = &if_stmt_ref.alternate {
+                    self.check_statement(alternate, SP::IfStatementAlternate(ptr(if_stmt)))?;
+                }
+                (AstType::IfStatement, if_stmt_ref.span, if_stmt_ref.parent)
+            }
+            S::ReturnStatement(return_stmt) => {
+                let return_stmt_ref = return_stmt.borrow(tk);
+                if let Some(argument) = &return_stmt_ref.argument {
+                    self.check_expression(argument, EP::ReturnStatementArgument(ptr(return_stmt)))?;
+                }
+                let (span, parent) = (return_stmt_ref.span, return_stmt_ref.parent);
+                (AstType::ReturnStatement, span, parent)
+            }
+            S::VariableDeclaration(var_decl) => {
+                let var_decl_ref = var_decl.borrow(tk);
+                for declarator in var_decl_ref.declarations.as_slice() {
+                    let declarator = *declarator.borrow(tk);
+                    let declarator_ref = declarator.borrow(tk);
+                    let expected = VariableDeclaratorParent::VariableDeclaration(ptr(var_decl));
+                    if to_standard_variable_declarator_parent(declarator_ref.parent) != expected {
+                        return Err(TreeError {
+                            node: AstType::VariableDeclarator,
+                            span: declarator_ref.span,
+                        });
+                    }
+                    if let Some(init) = &declarator_ref.init {
+                        self.check_expression(init, EP::VariableDeclaratorInit(ptr(declarator)))?;
+                    }
+                }
+                (
+                    AstType::VariableDeclaration,
+                    var_decl_ref.span,
+                    var_decl_ref.parent,
+                )
+            }
+        };
+
+        if to_standard_statement_parent(parent) == expected {
+            Ok(())
+        } else {
+            Err(TreeError { node, span })
+        }
+    }
+
+    fn check_expression(
+        &self,
+        expr: &TraversableExpression<'a, 't>,
+        expected: ExpressionParent<'a>,
+    ) -> Result<(), TreeError> {
+        use ExpressionParent as EP;
+        use TraversableExpression as E;
+
+        let tk = self.tk;
+        let (node, span) = match expr {
+            E::Identifier(id) => (AstType::IdentifierReference, id.borrow(tk).span),
+            E::StringLiteral(str_lit) => (AstType::StringLiteral, str_lit.borrow(tk).span),
+            E::NumericLiteral(num_lit) => (AstType::NumericLiteral, num_lit.borrow(tk).span),
+            E::BooleanLiteral(bool_lit) => (AstType::BooleanLiteral, bool_lit.borrow(tk).span),
+            E::NullLiteral(null_lit) => (AstType::NullLiteral, null_lit.borrow(tk).span),
+            E::BinaryExpression(bin_expr) => {
+                let bin_expr_ref = bin_expr.borrow(tk);
+                self.check_expression(&bin_expr_ref.left, EP::BinaryExpressionLeft(ptr(bin_expr)))?;
+                self.check_expression(
+                    &bin_expr_ref.right,
+                    EP::BinaryExpressionRight(ptr(bin_expr)),
+                )?;
+                (AstType::BinaryExpression, bin_expr_ref.span)
+            }
+            E::UnaryExpression(unary_expr) => {
+                let unary_expr_ref = unary_expr.borrow(tk);
+                self.check_expression(
+                    &unary_expr_ref.argument,
+                    EP::UnaryExpression(ptr(unary_expr)),
+                )?;
+                (AstType::UnaryExpression, unary_expr_ref.span)
+            }
+            E::CallExpression(call_expr) => {
+                let call_expr_ref = call_expr.borrow(tk);
+                self.check_expression(
+                    &call_expr_ref.callee,
+                    EP::CallExpressionCallee(ptr(call_expr)),
+                )?;
+                for arg in call_expr_ref.arguments.as_slice() {
+                    self.check_expression(
+                        arg.borrow(tk),
+                        EP::CallExpressionArgument(ptr(call_expr)),
+                    )?;
+                }
+                (AstType::CallExpression, call_expr_ref.span)
+            }
+            E::MemberExpression(member_expr) => {
+                let member_expr_ref = member_expr.borrow(tk);
+                self.check_expression(
+                    &member_expr_ref.object,
+                    EP::MemberExpressionObject(ptr(member_expr)),
+                )?;
+                self.check_expression(
+                    &member_expr_ref.property,
+                    EP::MemberExpressionProperty(ptr(member_expr)),
+                )?;
+                (AstType::MemberExpression, member_expr_ref.span)
+            }
+            E::LogicalExpression(logical_expr) => {
+                let logical_expr_ref = logical_expr.borrow(tk);
+                self.check_expression(
+                    &logical_expr_ref.left,
+                    EP::LogicalExpressionLeft(ptr(logical_expr)),
+                )?;
+                self.check_expression(
+                    &logical_expr_ref.right,
+                    EP::LogicalExpressionRight(ptr(logical_expr)),
+                )?;
+                (AstType::LogicalExpression, logical_expr_ref.span)
+            }
+            E::ConditionalExpression(cond_expr) => {
+                let cond_expr_ref = cond_expr.borrow(tk);
+                self.check_expression(
+                    &cond_expr_ref.test,
+                    EP::ConditionalExpressionTest(ptr(cond_expr)),
+                )?;
+                self.check_expression(
+                    &cond_expr_ref.consequent,
+                    EP::ConditionalExpressionConsequent(ptr(cond_expr)),
+                )?;
+                self.check_expression(
+                    &cond_expr_ref.alternate,
+                    EP::ConditionalExpressionAlternate(ptr(cond_expr)),
+                )?;
+                (AstType::ConditionalExpression, cond_expr_ref.span)
+            }
+            E::AssignmentExpression(assign_expr) => {
+                let assign_expr_ref = assign_expr.borrow(tk);
+                self.check_expression(
+                    &assign_expr_ref.target,
+                    EP::AssignmentExpressionTarget(ptr(assign_expr)),
+                )?;
+                self.check_expression(
+                    &assign_expr_ref.value,
+                    EP::AssignmentExpressionValue(ptr(assign_expr)),
+                )?;
+                (AstType::AssignmentExpression, assign_expr_ref.span)
+            }
+            E::TemplateLiteral(template_lit) => {
+                let template_lit_ref = template_lit.borrow(tk);
+                for expr in template_lit_ref.expressions.as_slice() {
+                    self.check_expression(
+                        expr.borrow(tk),
+                        EP::TemplateLiteralExpression(ptr(template_lit)),
+                    )?;
+                }
+                (AstType::TemplateLiteral, template_lit_ref.span)
+            }
+            E::ArrayExpression(array_expr) => {
+                let array_expr_ref = array_expr.borrow(tk);
+                for element in array_expr_ref.elements.as_slice() {
+                    if let Some(element) = element.borrow(tk) {
+                        self.check_expression(
+                            element,
+                            EP::ArrayExpressionElement(ptr(array_expr)),
+                        )?;
+                    }
+                }
+                (AstType::ArrayExpression, array_expr_ref.span)
+            }
+            E::ObjectExpression(object_expr) => {
+                let object_expr_ref = object_expr.borrow(tk);
+                for prop in object_expr_ref.properties.as_slice() {
+                    let prop = *prop.borrow(tk);
+                    let prop_ref = prop.borrow(tk);
+                    let expected = PropertyParent::ObjectExpression(ptr(object_expr));
+                    if to_standard_property_parent(prop_ref.parent) != expected {
+                        return Err(TreeError {
+                            node: AstType::Property,
+                            span: prop_ref.span,
+                        });
+                    }
+                    self.check_expression(&prop_ref.key, EP::PropertyKey(ptr(prop)))?;
+                    self.check_expression(&prop_ref.value, EP::PropertyValue(ptr(prop)))?;
+                }
+                (AstType::ObjectExpression, object_expr_ref.span)
+            }
+        };
+
+        if to_standard_expression_parent(expr.parent(tk)) == expected {
+            Ok(())
+        } else {
+            Err(TreeError { node, span })
+        }
+    }
+}
+
+/// Get pointer to a node in traversable AST, as a pointer to the equivalent standard AST node.
+fn ptr<T, U>(cell: &GCell<'_, T>) -> *const U {
+    cell.as_ptr() as *const U
+}
+
+// Parent links in traversable AST are compared by converting them to the equivalent
+// standard AST types, which compare node pointers, rather than the nodes' contents.
+//
+// SAFETY: Standard and traversable parent types have identical layouts
+// (checked by `assert_size_align_match!`). References to `GCell`s are converted to raw pointers,
+// which are only compared, not dereferenced.
+
+fn to_standard_statement_parent<'a>(
+    parent: TraversableStatementParent<'a, '_>,
+) -> StatementParent<'a> {
+    unsafe { std::mem::transmute(parent) }
+}
+
+fn to_standard_expression_parent<'a>(
+    parent: TraversableExpressionParent<'a, '_>,
+) -> ExpressionParent<'a> {
+    unsafe { std::mem::transmute(parent) }
+}
+
+fn to_standard_variable_declarator_parent<'a>(
+    parent: TraversableVariableDeclaratorParent<'a, '_>,
+) -> VariableDeclaratorParent<'a> {
+    unsafe { std::mem::transmute(parent) }
+}
+
+fn to_standard_property_parent<'a>(
+    parent: TraversablePropertyParent<'a, '_>,
+) -> PropertyParent<'a> {
+    unsafe { std::mem::transmute(parent) }
+}
+
+#[cfg(test)]
+mod tests {
+    use oxc_allocator::Allocator;
+
+    use super::*;
+    use crate::{
+        ast::traversable::BinaryExpression as TraversableBinaryExpression,
+        cell::gcell,
+        parser::parse_str,
+        traverse::{transform, Traverse},
+    };
+
+    /// Swap sides of binary expression in first statement, without updating parent links.
+    fn swap_sides(program: &mut Program<'_>) {
+        let Statement::ExpressionStatement(expr_stmt) = &mut program.body[0] else {
+            unreachable!();
+        };
+        let Expression::BinaryExpression(bin_expr) = &mut expr_stmt.expression else {
+            unreachable!();
+        };
+        let bin_expr = &mut **bin_expr;
+        std::mem::swap(&mut bin_expr.left, &mut bin_expr.right);
+    }
+
+    #[test]
+    fn validate_tree_reports_broken_link() {
+        let alloc = Allocator::default();
+        let program = parse_str("a === -b;", &alloc).unwrap();
+        assert!(validate_tree(program).is_ok());
+
+        swap_sides(program);
+        let err = validate_tree(program).unwrap_err();
+        // `-b` is now on the left, but its parent link still says it's on the right
+        assert_eq!(err.node, AstType::UnaryExpression);
+        assert_eq!((err.span.start, err.span.end), (6, 8));
+        assert_eq!(
+            err.to_string(),
+            "Incorrect parent link on UnaryExpression at 6..8"
+        );
+        assert_eq!(
+            err.render(&SourceText::new("a === -b;")),
+            "Incorrect parent link on UnaryExpression at 1:7\na === -b;\n      ^"
+        );
+
+        fix_parents(program);
+        assert!(validate_tree(program).is_ok());
+    }
+
+    /// Optionally swaps sides of binary expressions without updating parent links,
+    /// then runs `check_parents` at end of traversal.
+    struct SwapAndCheck {
+        swap: bool,
+        result: Option<Result<(), TreeError>>,
+    }
+
+    impl<'a, 't> Traverse<'a, 't> for SwapAndCheck {
+        fn exit_binary_expression(
+            &mut self,
+            bin_expr: &gcell!(TraversableBinaryExpression<'a, 't>),
+            tk: &mut Token<'t>,
+        ) {
+            if self.swap {
+                let bin_expr = bin_expr.borrow_mut(tk);
+                std::mem::swap(&mut bin_expr.left, &mut bin_expr.right);
+            }
+        }
+
+        fn exit_program(
+            &mut self,
+            program: &gcell!(TraversableProgram<'a, 't>),
+            tk: &mut Token<'t>,
+        ) {
+            self.result = Some(check_parents(program, tk));
+        }
+    }
+
+    #[test]
+    fn check_parents_during_transform() {
+        let alloc = Allocator::default();
+        let program = parse_str("a === -b;", &alloc).unwrap();
+        let mut checker = SwapAndCheck {
+            swap: false,
+            result: None,
+        };
+        transform(&mut checker, program);
+        assert!(checker.result.unwrap().is_ok());
+
+        checker = SwapAndCheck {
+            swap: true,
+            result: None,
+        };
+        transform(&mut checker, program);
+        let err = checker.result.unwrap().unwrap_err();
+        assert_eq!(err.node, AstType::UnaryExpression);
+        assert_eq!((err.span.start, err.span.end), (6, 8));
+    }
+}
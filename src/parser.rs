@@ -1,77 +1,1032 @@
+use std::{fmt, iter::Peekable, str::Chars};
+
 use oxc_allocator::{Allocator, Box, Vec};
 
-use crate::ast::{
-    BinaryExpression, BinaryOperator, Expression, ExpressionParent, ExpressionStatement,
-    IdentifierReference, Program, Statement, StatementParent, StringLiteral, UnaryExpression,
-    UnaryOperator,
+use crate::{
+    ast::{
+        AssignmentOperator, BinaryOperator, Comment, CommentKind, Expression, LogicalOperator,
+        Precedence, Program, Property, Span, Statement, UnaryOperator, VarKind, VariableDeclarator,
+    },
+    builder::AstBuilder,
+    interner::{InternStats, Interner},
+    lexer::{tokenize, Token, TokenKind},
+    source_text::SourceText,
+    Visit,
 };
 
-/// Create AST for `typeof foo === 'object'`.
-/// Hard-coded here, but these are the steps actual parser would take to create the AST
-/// with "back-links" to parents on each node.
-pub fn parse(alloc: &Allocator) -> &mut Program {
-    // `foo`
-    let id = Box(alloc.alloc(IdentifierReference {
-        name: "foo",
-        parent: ExpressionParent::None,
-    }));
-
-    // `typeof foo`
-    let mut unary_expr = Box(alloc.alloc(UnaryExpression {
-        operator: UnaryOperator::Typeof,
-        argument: Expression::Identifier(id),
-        parent: ExpressionParent::None,
-    }));
-
-    let unary_expr_ptr = &*unary_expr as *const _;
-    if let Expression::Identifier(id) = &mut unary_expr.argument {
-        id.parent = ExpressionParent::UnaryExpression(unary_expr_ptr);
-    }
-
-    // `'object'`
-    let str_lit = Box(alloc.alloc(StringLiteral {
-        value: "object",
-        parent: ExpressionParent::None,
-    }));
-
-    // `typeof foo === 'object'` (as expression)
-    let mut binary_expr = Box(alloc.alloc(BinaryExpression {
-        operator: BinaryOperator::StrictEquality,
-        left: Expression::UnaryExpression(unary_expr),
-        right: Expression::StringLiteral(str_lit),
-        parent: ExpressionParent::None,
-    }));
-
-    let binary_expr_ptr = &*binary_expr as *const _;
-    if let Expression::UnaryExpression(unary_expr) = &mut binary_expr.left {
-        unary_expr.parent = ExpressionParent::BinaryExpressionLeft(binary_expr_ptr);
-    }
-    if let Expression::StringLiteral(str_lit) = &mut binary_expr.right {
-        str_lit.parent = ExpressionParent::BinaryExpressionRight(binary_expr_ptr);
-    }
-
-    // `typeof foo === 'object'` (as expression statement)
-    let mut expr_stmt = Box(alloc.alloc(ExpressionStatement {
-        expression: Expression::BinaryExpression(binary_expr),
-        parent: StatementParent::None,
-    }));
-
-    let expr_stmt_ptr = &*expr_stmt as *const _;
-    if let Expression::BinaryExpression(binary_expr) = &mut expr_stmt.expression {
-        binary_expr.parent = ExpressionParent::ExpressionStatement(expr_stmt_ptr);
-    }
-
-    // `typeof foo === 'object'` (as statement)
-    let stmt = Statement::ExpressionStatement(expr_stmt);
-
-    // `typeof foo === 'object'` (as program)
-    let mut body = Vec::new_in(alloc);
-    body.push(stmt);
-    let program = alloc.alloc(Program { body });
-
-    let program_ptr = program as *const _;
-    let Statement::ExpressionStatement(expr_stmt) = program.body.iter_mut().next().unwrap();
-    expr_stmt.parent = StatementParent::Program(program_ptr);
-
-    program
+/// Error produced when source text cannot be parsed.
+#[derive(Debug)]
+pub struct ParseError {
+    /// Byte offset in source text where the error occurred
+    pub offset: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at offset {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl ParseError {
+    /// Format error with its location as `line:column` in `source`,
+    /// instead of as a byte offset, followed by the line of source with a caret under the location.
+    pub fn render(&self, source: &SourceText<'_>) -> String {
+        let offset = self.offset as u32;
+        let (line, column) = source.line_col(offset);
+        format!(
+            "{} at {line}:{column}\n{}",
+            self.message,
+            source.caret(offset)
+        )
+    }
+}
+
+/// Parse source text into AST.
+///
+/// Only a small subset of JS is supported: a list of expression statements separated by `;`,
+/// block statements, `if` statements, `return` statements, and variable declarations.
+/// Expressions can be identifiers, string / number / boolean / `null` literals,
+/// unary expressions, binary expressions, `&&` / `||` / `??` logical expressions,
+/// conditional expressions, assignments, calls, member expressions, template literals,
+/// array literals, and object literals.
+/// Parentheses can be used for grouping.
+///
+/// Comments are collected in `Program::comments`. A comment directly before a statement
+/// (with no tokens between them) is attached to that statement as a leading comment.
+/// e.g. in `/* a */ x; /* b */ if (y) /* c */ z; w /* d */;`, `a` is attached to `x`,
+/// `b` to the `if` statement, and `c` to `z`. `d` is not attached to any statement.
+///
+/// "Back-links" to parents are set on each node as the AST is built.
+///
+/// Statements and expressions can be nested at most `MAX_DEPTH` levels deep.
+/// Deeper input (e.g. `((((x))))` with too many parentheses) is a parse error.
+pub fn parse_str<'a>(
+    source: &'a str,
+    alloc: &'a Allocator,
+) -> Result<&'a mut Program<'a>, ParseError> {
+    Parser::new(source, alloc).parse_program()
+}
+
+/// Parse source text into AST, the same as `parse_str`, and also get statistics on
+/// deduplication of identifier names and string values.
+pub fn parse_str_with_stats<'a>(
+    source: &'a str,
+    alloc: &'a Allocator,
+) -> Result<(&'a mut Program<'a>, InternStats), ParseError> {
+    let mut parser = Parser::new(source, alloc);
+    let program = parser.parse_program()?;
+    Ok((program, parser.interner.stats()))
+}
+
+/// Parse source text, running `visitor` on each top-level statement as soon as it's parsed,
+/// without waiting for the rest of the source to be parsed.
+///
+/// See `ParseStream` for the order statements are visited in, and the state of their
+/// parent links when visited. Once all statements have been parsed and visited,
+/// they are assembled into a `Program`, which is returned.
+///
+/// If a parse error occurs, statements before it have already been visited.
+pub fn parse_with_visitor<'a, V: Visit<'a>>(
+    source: &'a str,
+    alloc: &'a Allocator,
+    visitor: &mut V,
+) -> Result<&'a mut Program<'a>, ParseError> {
+    let ast = AstBuilder::new(alloc);
+    let mut body = ast.vec();
+    let mut stream = ParseStream::new(source, alloc);
+    for stmt in stream.by_ref() {
+        let stmt = stmt?;
+        visitor.visit_statement(&stmt);
+        body.push(stmt);
+    }
+    let program = ast.program(body);
+    program.comments = stream.take_comments();
+    Ok(program)
+}
+
+/// Iterator which parses source text one top-level statement at a time.
+///
+/// Statements are yielded in source order. Each statement is only yielded once it, and all
+/// nodes within it, have been fully parsed. Parsing of the next statement does not start until
+/// `next` is called again.
+///
+/// The `Program` which will contain the statements does not exist yet, so yielded statements'
+/// parent links are `StatementParent::None`. Parent links of all nodes within the statement
+/// are set as usual. Pass the statements to `AstBuilder::program` to create the `Program`,
+/// which sets the statements' parent links to it.
+///
+/// After a parse error, the iterator yields the error, and then ends.
+pub struct ParseStream<'a> {
+    parser: Parser<'a>,
+    done: bool,
+}
+
+impl<'a> ParseStream<'a> {
+    pub fn new(source: &'a str, alloc: &'a Allocator) -> Self {
+        Self {
+            parser: Parser::new(source, alloc),
+            done: false,
+        }
+    }
+
+    /// Take all comments in source text.
+    ///
+    /// Comments are only attached to statements which have been parsed, so call this after
+    /// the last statement has been yielded. Subsequent calls return an empty `Vec`.
+    pub fn take_comments(&mut self) -> Vec<'a, Comment<'a>> {
+        self.parser.take_comments()
+    }
+
+    /// Get statistics on deduplication of identifier names and string values parsed so far.
+    pub fn intern_stats(&self) -> InternStats {
+        self.parser.interner.stats()
+    }
+}
+
+impl<'a> Iterator for ParseStream<'a> {
+    type Item = Result<Statement<'a>, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        // Skip empty statements
+        while self.parser.eat(TokenKind::Semicolon) {}
+        if self.parser.peek().kind == TokenKind::Eof {
+            self.done = true;
+            return None;
+        }
+        let result = self.parser.parse_statement();
+        self.done = result.is_err();
+        Some(result)
+    }
+}
+
+/// Maximum nesting depth of statements and expressions.
+///
+/// Parser is recursive descent, so without a limit, deeply nested input would overflow the stack.
+/// In debug builds, each level of nesting can use up to 10 KiB of stack, so at this limit
+/// parsing still fits in the 2 MiB stack of a spawned thread.
+pub const MAX_DEPTH: u32 = 128;
+
+struct Parser<'a> {
+    source: &'a str,
+    tokens: std::vec::Vec<Token>,
+    index: usize,
+    /// Current nesting depth. See `MAX_DEPTH`.
+    depth: u32,
+    comments: Vec<'a, Comment<'a>>,
+    ast: AstBuilder<'a>,
+    interner: Interner<'a>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(source: &'a str, alloc: &'a Allocator) -> Self {
+        let (tokens, comment_tokens) = tokenize(source);
+        let ast = AstBuilder::new(alloc);
+        let mut comments = ast.vec();
+        for token in comment_tokens {
+            let (kind, text) = if token.kind == TokenKind::LineComment {
+                (CommentKind::Line, &source[token.start + 2..token.end])
+            } else {
+                (CommentKind::Block, &source[token.start + 2..token.end - 2])
+            };
+            comments.push(Comment {
+                span: Span::new(token.start as u32, token.end as u32),
+                kind,
+                text,
+                attached_to: None,
+            });
+        }
+        Self {
+            source,
+            tokens,
+            index: 0,
+            depth: 0,
+            comments,
+            ast,
+            interner: Interner::new(alloc),
+        }
+    }
+
+    fn parse_program(&mut self) -> Result<&'a mut Program<'a>, ParseError> {
+        let body = self.parse_statement_list(TokenKind::Eof)?;
+        let program = self.ast.program(body);
+        program.comments = self.take_comments();
+        Ok(program)
+    }
+
+    fn take_comments(&mut self) -> Vec<'a, Comment<'a>> {
+        std::mem::replace(&mut self.comments, self.ast.vec())
+    }
+
+    /// Attach comments between the previous token and the current one to the statement
+    /// starting at the current token.
+    fn attach_leading_comments(&mut self) {
+        let prev_end = if self.index == 0 {
+            0
+        } else {
+            self.tokens[self.index - 1].end as u32
+        };
+        let start = self.peek().start as u32;
+        let first = self
+            .comments
+            .partition_point(|comment| comment.span.start < prev_end);
+        for comment in self.comments[first..].iter_mut() {
+            if comment.span.end > start {
+                break;
+            }
+            comment.attached_to = Some(start);
+        }
+    }
+
+    /// Parse statements up to (but not including) `end` token.
+    /// Empty statements (`;`) are skipped.
+    fn parse_statement_list(
+        &mut self,
+        end: TokenKind,
+    ) -> Result<Vec<'a, Statement<'a>>, ParseError> {
+        let mut body = self.ast.vec();
+        loop {
+            match self.peek().kind {
+                kind if kind == end => return Ok(body),
+                TokenKind::Semicolon => self.index += 1,
+                TokenKind::Eof => return Err(self.error("Unexpected end of input")),
+                _ => body.push(self.parse_statement()?),
+            }
+        }
+    }
+
+    fn parse_statement(&mut self) -> Result<Statement<'a>, ParseError> {
+        self.attach_leading_comments();
+        self.nested(|parser| match parser.peek().kind {
+            TokenKind::LBrace => parser.parse_block_statement(),
+            TokenKind::If => parser.parse_if_statement(),
+            TokenKind::Return => parser.parse_return_statement(),
+            TokenKind::Var | TokenKind::Let | TokenKind::Const => {
+                parser.parse_variable_declaration()
+            }
+            _ => parser.parse_expression_statement(),
+        })
+    }
+
+    fn parse_block_statement(&mut self) -> Result<Statement<'a>, ParseError> {
+        let start = self.peek().start as u32;
+        self.index += 1;
+        let body = self.parse_statement_list(TokenKind::RBrace)?;
+        self.index += 1;
+        let span = Span::new(start, self.prev_token_end());
+
+        Ok(self.ast.block_statement(span, body))
+    }
+
+    fn parse_if_statement(&mut self) -> Result<Statement<'a>, ParseError> {
+        let start = self.peek().start as u32;
+        self.index += 1;
+        if !self.eat(TokenKind::LParen) {
+            return Err(self.error("Expected `(`"));
+        }
+        let test = self.parse_expression()?;
+        if !self.eat(TokenKind::RParen) {
+            return Err(self.error("Expected `)`"));
+        }
+        let consequent = self.parse_statement()?;
+        let alternate = if self.eat(TokenKind::Else) {
+            Some(self.parse_statement()?)
+        } else {
+            None
+        };
+        let span = Span::new(start, self.prev_token_end());
+
+        Ok(self.ast.if_statement(span, test, consequent, alternate))
+    }
+
+    fn parse_return_statement(&mut self) -> Result<Statement<'a>, ParseError> {
+        let start = self.peek().start as u32;
+        self.index += 1;
+        let argument = match self.peek().kind {
+            TokenKind::Semicolon | TokenKind::RBrace | TokenKind::Eof => None,
+            _ => Some(self.parse_expression()?),
+        };
+        self.expect_semicolon()?;
+        let span = Span::new(start, self.prev_token_end());
+
+        Ok(self.ast.return_statement(span, argument))
+    }
+
+    fn parse_variable_declaration(&mut self) -> Result<Statement<'a>, ParseError> {
+        let start = self.peek().start as u32;
+        let kind = match self.peek().kind {
+            TokenKind::Var => VarKind::Var,
+            TokenKind::Let => VarKind::Let,
+            _ => VarKind::Const,
+        };
+        self.index += 1;
+        let mut declarations = self.ast.vec();
+        loop {
+            declarations.push(self.parse_variable_declarator(kind)?);
+            if !self.eat(TokenKind::Comma) {
+                break;
+            }
+        }
+        self.expect_semicolon()?;
+        let span = Span::new(start, self.prev_token_end());
+
+        Ok(self.ast.variable_declaration(span, kind, declarations))
+    }
+
+    fn parse_variable_declarator(
+        &mut self,
+        kind: VarKind,
+    ) -> Result<Box<'a, VariableDeclarator<'a>>, ParseError> {
+        let token = self.peek();
+        if token.kind != TokenKind::Ident {
+            return Err(self.error("Expected variable name"));
+        }
+        self.index += 1;
+        let id = self.ast.binding_identifier(
+            Span::new(token.start as u32, token.end as u32),
+            self.name(token),
+        );
+        let init = if self.eat(TokenKind::Eq) {
+            Some(self.parse_expression()?)
+        } else if kind == VarKind::Const {
+            return Err(self.error("Missing initializer in const declaration"));
+        } else {
+            None
+        };
+        let span = Span::new(token.start as u32, self.prev_token_end());
+
+        Ok(self.ast.variable_declarator(span, id, init))
+    }
+
+    fn parse_expression_statement(&mut self) -> Result<Statement<'a>, ParseError> {
+        let start = self.peek().start as u32;
+        let expression = self.parse_expression()?;
+        self.expect_semicolon()?;
+        let span = Span::new(start, self.prev_token_end());
+
+        Ok(self.ast.expression_statement(span, expression))
+    }
+
+    fn parse_expression(&mut self) -> Result<Expression<'a>, ParseError> {
+        self.nested(Self::parse_assignment_expression)
+    }
+
+    fn parse_assignment_expression(&mut self) -> Result<Expression<'a>, ParseError> {
+        let start = self.peek().start as u32;
+        let target = self.parse_conditional_expression()?;
+        let operator = match self.peek().kind {
+            TokenKind::Eq => AssignmentOperator::Assign,
+            TokenKind::PlusEq => AssignmentOperator::AddAssign,
+            TokenKind::MinusEq => AssignmentOperator::SubtractAssign,
+            TokenKind::StarEq => AssignmentOperator::MultiplyAssign,
+            TokenKind::SlashEq => AssignmentOperator::DivideAssign,
+            TokenKind::PercentEq => AssignmentOperator::RemainderAssign,
+            TokenKind::AmpAmpEq => AssignmentOperator::LogicalAndAssign,
+            TokenKind::PipePipeEq => AssignmentOperator::LogicalOrAssign,
+            TokenKind::QuestionQuestionEq => AssignmentOperator::NullishAssign,
+            _ => return Ok(target),
+        };
+        if !matches!(
+            target,
+            Expression::Identifier(_) | Expression::MemberExpression(_)
+        ) {
+            return Err(self.error("Invalid left-hand side in assignment"));
+        }
+        self.index += 1;
+        // Assignment is right-associative
+        let value = self.nested(Self::parse_assignment_expression)?;
+        let span = Span::new(start, self.prev_token_end());
+        Ok(self
+            .ast
+            .assignment_expression(span, target, operator, value))
+    }
+
+    fn parse_conditional_expression(&mut self) -> Result<Expression<'a>, ParseError> {
+        let start = self.peek().start as u32;
+        let test = self.parse_logical_or_expression()?;
+        if !self.eat(TokenKind::Question) {
+            return Ok(test);
+        }
+        let consequent = self.nested(Self::parse_conditional_expression)?;
+        if !self.eat(TokenKind::Colon) {
+            return Err(self.error("Expected `:`"));
+        }
+        let alternate = self.nested(Self::parse_conditional_expression)?;
+        let span = Span::new(start, self.prev_token_end());
+        Ok(self
+            .ast
+            .conditional_expression(span, test, consequent, alternate))
+    }
+
+    /// Parse `||` and `??` expressions.
+    /// NB: Unlike JS, mixing `??` with `||` without parentheses is not a syntax error.
+    fn parse_logical_or_expression(&mut self) -> Result<Expression<'a>, ParseError> {
+        let start = self.peek().start as u32;
+        let mut left = self.parse_logical_and_expression()?;
+        loop {
+            let operator = match self.peek().kind {
+                TokenKind::PipePipe => LogicalOperator::Or,
+                TokenKind::QuestionQuestion => LogicalOperator::NullishCoalescing,
+                _ => break,
+            };
+            self.index += 1;
+            let right = self.parse_logical_and_expression()?;
+            let span = Span::new(start, self.prev_token_end());
+            left = self.ast.logical_expression(span, left, operator, right);
+        }
+        Ok(left)
+    }
+
+    fn parse_logical_and_expression(&mut self) -> Result<Expression<'a>, ParseError> {
+        let start = self.peek().start as u32;
+        let mut left = self.parse_binary_expression(Precedence::BitwiseOr)?;
+        while self.eat(TokenKind::AmpAmp) {
+            let right = self.parse_binary_expression(Precedence::BitwiseOr)?;
+            let span = Span::new(start, self.prev_token_end());
+            left = self
+                .ast
+                .logical_expression(span, left, LogicalOperator::And, right);
+        }
+        Ok(left)
+    }
+
+    /// Parse binary expressions whose operators bind at least as tightly as `min_precedence`,
+    /// by precedence climbing.
+    fn parse_binary_expression(
+        &mut self,
+        min_precedence: Precedence,
+    ) -> Result<Expression<'a>, ParseError> {
+        let start = self.peek().start as u32;
+        let mut left = self.parse_unary_expression()?;
+        while let Some(operator) = binary_operator(self.peek().kind) {
+            let precedence = operator.precedence();
+            if precedence < min_precedence {
+                break;
+            }
+            // `-a ** b` is a syntax error. `(-a) ** b` is fine.
+            // Span of a parenthesized expression excludes the parentheses, so starts after `start`.
+            let left_is_bare_unary = matches!(
+                &left,
+                Expression::UnaryExpression(unary_expr) if unary_expr.span.start == start
+            );
+            if operator == BinaryOperator::Exponent && left_is_bare_unary {
+                return Err(
+                    self.error("Unary operator used immediately before exponentiation expression")
+                );
+            }
+            self.index += 1;
+            // `**` is right-associative, all other binary operators are left-associative
+            let right_precedence = if operator == BinaryOperator::Exponent {
+                precedence
+            } else {
+                precedence.next()
+            };
+            let right = self.nested(|parser| parser.parse_binary_expression(right_precedence))?;
+            let span = Span::new(start, self.prev_token_end());
+            left = self.ast.binary_expression(span, left, operator, right);
+        }
+        Ok(left)
+    }
+
+    fn parse_unary_expression(&mut self) -> Result<Expression<'a>, ParseError> {
+        let operator = match self.peek().kind {
+            TokenKind::Minus => UnaryOperator::UnaryNegation,
+            TokenKind::Plus => UnaryOperator::UnaryPlus,
+            TokenKind::Bang => UnaryOperator::LogicalNot,
+            TokenKind::Tilde => UnaryOperator::BitwiseNot,
+            TokenKind::Typeof => UnaryOperator::Typeof,
+            TokenKind::Void => UnaryOperator::Void,
+            TokenKind::Delete => UnaryOperator::Delete,
+            _ => return self.parse_postfix_expression(),
+        };
+        let start = self.peek().start as u32;
+        self.index += 1;
+        let argument = self.nested(Self::parse_unary_expression)?;
+        let span = Span::new(start, self.prev_token_end());
+        Ok(self.ast.unary_expression(span, operator, argument))
+    }
+
+    /// Parse calls and member expressions, e.g. `foo.bar[baz](qux)`.
+    fn parse_postfix_expression(&mut self) -> Result<Expression<'a>, ParseError> {
+        let start = self.peek().start as u32;
+        let mut expr = self.parse_primary_expression()?;
+        loop {
+            match self.peek().kind {
+                TokenKind::LParen => {
+                    self.index += 1;
+                    let arguments = self.parse_arguments()?;
+                    let span = Span::new(start, self.prev_token_end());
+                    expr = self.ast.call_expression(span, expr, arguments);
+                }
+                TokenKind::Dot => {
+                    self.index += 1;
+                    let token = self.peek();
+                    if token.kind != TokenKind::Ident {
+                        return Err(self.error("Expected property name"));
+                    }
+                    self.index += 1;
+                    let property = self.ast.identifier_reference(
+                        Span::new(token.start as u32, token.end as u32),
+                        self.name(token),
+                    );
+                    let span = Span::new(start, self.prev_token_end());
+                    expr = self.ast.member_expression(span, expr, property, false);
+                }
+                TokenKind::LBracket => {
+                    self.index += 1;
+                    let property = self.parse_expression()?;
+                    if !self.eat(TokenKind::RBracket) {
+                        return Err(self.error("Expected `]`"));
+                    }
+                    let span = Span::new(start, self.prev_token_end());
+                    expr = self.ast.member_expression(span, expr, property, true);
+                }
+                _ => return Ok(expr),
+            }
+        }
+    }
+
+    /// Parse call arguments. Opening `(` must already have been consumed.
+    fn parse_arguments(&mut self) -> Result<Vec<'a, Expression<'a>>, ParseError> {
+        let mut arguments = self.ast.vec();
+        if self.eat(TokenKind::RParen) {
+            return Ok(arguments);
+        }
+        loop {
+            arguments.push(self.parse_expression()?);
+            if self.eat(TokenKind::RParen) {
+                return Ok(arguments);
+            }
+            if !self.eat(TokenKind::Comma) {
+                return Err(self.error("Expected `,` or `)`"));
+            }
+        }
+    }
+
+    fn parse_primary_expression(&mut self) -> Result<Expression<'a>, ParseError> {
+        let token = self.peek();
+        let span = Span::new(token.start as u32, token.end as u32);
+        let expr = match token.kind {
+            TokenKind::LParen => {
+                self.index += 1;
+                let expr = self.parse_expression()?;
+                if !self.eat(TokenKind::RParen) {
+                    return Err(self.error("Expected `)`"));
+                }
+                return Ok(expr);
+            }
+            TokenKind::Ident => self.ast.identifier_reference(span, self.name(token)),
+            TokenKind::Str => {
+                let raw = self.text(token);
+                // Strip quotes
+                let Some(value) = self.unescape(&raw[1..raw.len() - 1]) else {
+                    return Err(self.error("Invalid escape sequence"));
+                };
+                self.ast.string_literal(span, value, Some(raw))
+            }
+            TokenKind::Template => {
+                let raw = self.text(token);
+                // Strip backticks
+                let mut quasis = self.ast.vec();
+                quasis.push(&raw[1..raw.len() - 1]);
+                self.ast.template_literal(span, quasis, self.ast.vec())
+            }
+            TokenKind::TemplateHead => return self.parse_template_literal(),
+            TokenKind::LBracket => return self.parse_array_expression(),
+            TokenKind::LBrace => return self.parse_object_expression(),
+            TokenKind::Number => {
+                // Cannot fail - lexer only produces digits, with optional fraction and exponent
+                let value = self.text(token).parse::<f64>().unwrap();
+                self.ast.numeric_literal(span, value)
+            }
+            TokenKind::True | TokenKind::False => self
+                .ast
+                .boolean_literal(span, token.kind == TokenKind::True),
+            TokenKind::Null => self.ast.null_literal(span),
+            TokenKind::Eof => return Err(self.error("Unexpected end of input")),
+            _ => return Err(self.error("Unexpected token")),
+        };
+        self.index += 1;
+        Ok(expr)
+    }
+
+    /// Parse array expression, starting at `[`.
+    /// A `,` with no element before it is a hole. A single trailing `,` does not add a hole.
+    fn parse_array_expression(&mut self) -> Result<Expression<'a>, ParseError> {
+        let start = self.peek().start as u32;
+        self.index += 1;
+        let mut elements = self.ast.vec();
+        loop {
+            match self.peek().kind {
+                TokenKind::RBracket => break,
+                TokenKind::Comma => {
+                    self.index += 1;
+                    elements.push(None);
+                }
+                _ => {
+                    elements.push(Some(self.parse_expression()?));
+                    if self.peek().kind == TokenKind::RBracket {
+                        break;
+                    }
+                    if !self.eat(TokenKind::Comma) {
+                        return Err(self.error("Expected `,` or `]`"));
+                    }
+                }
+            }
+        }
+        self.index += 1;
+        let span = Span::new(start, self.prev_token_end());
+        Ok(self.ast.array_expression(span, elements))
+    }
+
+    /// Parse object expression, starting at `{`.
+    fn parse_object_expression(&mut self) -> Result<Expression<'a>, ParseError> {
+        let start = self.peek().start as u32;
+        self.index += 1;
+        let mut properties = self.ast.vec();
+        while !self.eat(TokenKind::RBrace) {
+            properties.push(self.parse_property()?);
+            if self.peek().kind != TokenKind::RBrace && !self.eat(TokenKind::Comma) {
+                return Err(self.error("Expected `,` or `}`"));
+            }
+        }
+        let span = Span::new(start, self.prev_token_end());
+        Ok(self.ast.object_expression(span, properties))
+    }
+
+    /// Parse property in an object expression.
+    /// Key can be an identifier, string or number, or a computed key `[expr]`.
+    fn parse_property(&mut self) -> Result<Box<'a, Property<'a>>, ParseError> {
+        let key_token = self.peek();
+        let start = key_token.start as u32;
+        let computed = key_token.kind == TokenKind::LBracket;
+        let key = match key_token.kind {
+            TokenKind::LBracket => {
+                self.index += 1;
+                let key = self.parse_expression()?;
+                if !self.eat(TokenKind::RBracket) {
+                    return Err(self.error("Expected `]`"));
+                }
+                key
+            }
+            TokenKind::Ident | TokenKind::Str | TokenKind::Number => {
+                self.parse_primary_expression()?
+            }
+            _ => return Err(self.error("Expected property key")),
+        };
+
+        let shorthand = key_token.kind == TokenKind::Ident
+            && matches!(self.peek().kind, TokenKind::Comma | TokenKind::RBrace);
+        let value = if shorthand {
+            // Value is a separate node from key, as each node can only have one parent
+            let span = Span::new(key_token.start as u32, key_token.end as u32);
+            self.ast.identifier_reference(span, self.name(key_token))
+        } else {
+            if !self.eat(TokenKind::Colon) {
+                return Err(self.error("Expected `:`"));
+            }
+            self.parse_expression()?
+        };
+        let span = Span::new(start, self.prev_token_end());
+        Ok(self.ast.property(span, key, value, computed, shorthand))
+    }
+
+    /// Parse template literal with substitutions, starting at `TokenKind::TemplateHead`.
+    fn parse_template_literal(&mut self) -> Result<Expression<'a>, ParseError> {
+        let start = self.peek().start as u32;
+        let mut quasis = self.ast.vec();
+        let mut expressions = self.ast.vec();
+        loop {
+            // Quasi is text between opening `` ` `` or `}`, and closing `${` or `` ` ``
+            let token = self.peek();
+            let raw = self.text(token);
+            let quasi = match token.kind {
+                TokenKind::TemplateHead | TokenKind::TemplateMiddle => &raw[1..raw.len() - 2],
+                _ => &raw[1..raw.len() - 1],
+            };
+            quasis.push(quasi);
+            self.index += 1;
+            if token.kind == TokenKind::TemplateTail {
+                break;
+            }
+
+            expressions.push(self.parse_expression()?);
+            if !matches!(
+                self.peek().kind,
+                TokenKind::TemplateMiddle | TokenKind::TemplateTail
+            ) {
+                return Err(self.error("Expected `}`"));
+            }
+        }
+        let span = Span::new(start, self.prev_token_end());
+        Ok(self.ast.template_literal(span, quasis, expressions))
+    }
+
+    /// Consume `;` at end of a statement.
+    /// `;` can be omitted before `}` or at end of input.
+    fn expect_semicolon(&mut self) -> Result<(), ParseError> {
+        if self.eat(TokenKind::Semicolon)
+            || matches!(self.peek().kind, TokenKind::RBrace | TokenKind::Eof)
+        {
+            Ok(())
+        } else {
+            Err(self.error("Expected `;`"))
+        }
+    }
+
+    /// Run `parse` one level of nesting deeper, or return an error if that exceeds `MAX_DEPTH`.
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Self) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        if self.depth == MAX_DEPTH {
+            return Err(self.error("Maximum nesting depth exceeded"));
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn error(&self, message: &str) -> ParseError {
+        let token = self.peek();
+        let message = if token.kind == TokenKind::Error {
+            "Invalid or unexpected token"
+        } else {
+            message
+        };
+        ParseError {
+            offset: token.start,
+            message: message.to_string(),
+        }
+    }
+
+    /// Get current token, without consuming it.
+    /// Last token is always `Eof`, so this never runs off the end.
+    fn peek(&self) -> Token {
+        self.tokens[self.index]
+    }
+
+    fn eat(&mut self, kind: TokenKind) -> bool {
+        if self.peek().kind == kind {
+            self.index += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Get end of the last token consumed.
+    fn prev_token_end(&self) -> u32 {
+        self.tokens[self.index - 1].end as u32
+    }
+
+    fn text(&self, token: Token) -> &'a str {
+        &self.source[token.start..token.end]
+    }
+
+    /// Get interned text of an identifier token.
+    fn name(&self, token: Token) -> &'a str {
+        self.interner.intern_borrowed(self.text(token))
+    }
+
+    /// Decode escape sequences in the body of a string literal, and intern the result.
+    /// If there are no escapes, and the string has not been seen before, `raw` is returned as is,
+    /// without copying.
+    /// Returns `None` if an escape sequence is invalid.
+    fn unescape(&self, raw: &'a str) -> Option<&'a str> {
+        if !raw.contains('\\') {
+            return Some(self.interner.intern_borrowed(raw));
+        }
+
+        let mut value = String::with_capacity(raw.len());
+        let mut chars = raw.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '\\' {
+                value.push(c);
+                continue;
+            }
+            match chars.next()? {
+                'n' => value.push('\n'),
+                'r' => value.push('\r'),
+                't' => value.push('\t'),
+                'b' => value.push('\u{8}'),
+                'v' => value.push('\u{b}'),
+                'f' => value.push('\u{c}'),
+                '0' if !chars.peek().is_some_and(char::is_ascii_digit) => value.push('\0'),
+                // Legacy octal escapes are not supported
+                '0'..='9' => return None,
+                'x' => value.push(hex_escape(&mut chars, 2)?),
+                'u' => {
+                    if chars.next_if_eq(&'{').is_some() {
+                        let mut code_point = 0u32;
+                        let mut digits = 0;
+                        while let Some(digit) = chars.next_if(char::is_ascii_hexdigit) {
+                            code_point = code_point.checked_mul(16)? + digit.to_digit(16)?;
+                            digits += 1;
+                        }
+                        if digits == 0 || chars.next() != Some('}') {
+                            return None;
+                        }
+                        value.push(char::from_u32(code_point)?);
+                    } else {
+                        value.push(hex_escape(&mut chars, 4)?);
+                    }
+                }
+                // Line continuation
+                '\r' => {
+                    chars.next_if_eq(&'\n');
+                }
+                '\n' | '\u{2028}' | '\u{2029}' => {}
+                // Any other escaped character represents itself (e.g. `\'`, `\\`)
+                c => value.push(c),
+            }
+        }
+        Some(self.interner.intern(&value))
+    }
+}
+
+/// Read `len` hex digits from `chars` and convert to a `char`.
+/// Surrogate code points (lone halves of a UTF-16 pair) cannot be represented, so are rejected.
+fn hex_escape(chars: &mut Peekable<Chars<'_>>, len: usize) -> Option<char> {
+    let mut code_point = 0;
+    for _ in 0..len {
+        code_point = code_point * 16 + chars.next()?.to_digit(16)?;
+    }
+    char::from_u32(code_point)
+}
+
+/// Get binary operator for a token, if it is one.
+fn binary_operator(kind: TokenKind) -> Option<BinaryOperator> {
+    let operator = match kind {
+        TokenKind::EqEq => BinaryOperator::Equality,
+        TokenKind::EqEqEq => BinaryOperator::StrictEquality,
+        TokenKind::BangEq => BinaryOperator::Inequality,
+        TokenKind::BangEqEq => BinaryOperator::StrictInequality,
+        TokenKind::Lt => BinaryOperator::LessThan,
+        TokenKind::LtEq => BinaryOperator::LessEqual,
+        TokenKind::Gt => BinaryOperator::GreaterThan,
+        TokenKind::GtEq => BinaryOperator::GreaterEqual,
+        TokenKind::In => BinaryOperator::In,
+        TokenKind::Instanceof => BinaryOperator::Instanceof,
+        TokenKind::LtLt => BinaryOperator::ShiftLeft,
+        TokenKind::GtGt => BinaryOperator::ShiftRight,
+        TokenKind::GtGtGt => BinaryOperator::ShiftRightZeroFill,
+        TokenKind::Plus => BinaryOperator::Add,
+        TokenKind::Minus => BinaryOperator::Subtract,
+        TokenKind::Star => BinaryOperator::Multiply,
+        TokenKind::Slash => BinaryOperator::Divide,
+        TokenKind::Percent => BinaryOperator::Remainder,
+        TokenKind::StarStar => BinaryOperator::Exponent,
+        TokenKind::Pipe => BinaryOperator::BitwiseOr,
+        TokenKind::Caret => BinaryOperator::BitwiseXor,
+        TokenKind::Amp => BinaryOperator::BitwiseAnd,
+        _ => return None,
+    };
+    Some(operator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::StatementParent;
+
+    /// Visitor which records start of each top-level statement, and whether it had no parent
+    /// when visited.
+    #[derive(Default)]
+    struct CollectStatements {
+        visited: std::vec::Vec<(u32, bool)>,
+    }
+
+    impl<'a> Visit<'a> for CollectStatements {
+        fn visit_statement(&mut self, stmt: &Statement<'a>) {
+            let no_parent = matches!(stmt.parent(), StatementParent::None);
+            self.visited.push((stmt.span().start, no_parent));
+        }
+    }
+
+    #[test]
+    fn parse_with_visitor_in_source_order() {
+        let alloc = Allocator::default();
+        let mut visitor = CollectStatements::default();
+        let program = parse_with_visitor("a; { b; } if (c) d;", &alloc, &mut visitor).unwrap();
+        // Statements are visited before the `Program` exists, so have no parent
+        assert_eq!(visitor.visited, [(0, true), (3, true), (10, true)]);
+        assert_eq!(program.body.len(), 3);
+        assert!(program
+            .body
+            .iter()
+            .all(|stmt| matches!(stmt.parent(), StatementParent::Program(_))));
+    }
+
+    #[test]
+    fn parse_with_visitor_stops_at_error() {
+        let alloc = Allocator::default();
+        let mut visitor = CollectStatements::default();
+        let err = parse_with_visitor("a; b c; d;", &alloc, &mut visitor).unwrap_err();
+        assert_eq!(err.offset, 5);
+        assert_eq!(visitor.visited, [(0, true)]);
+    }
+
+    #[test]
+    fn intern_stats() {
+        let alloc = Allocator::default();
+        let (program, stats) = parse_str_with_stats("let x = 'x'; f(x, 'y', x);", &alloc).unwrap();
+        // `x`, `'x'`, `f`, `x`, `'y'`, `x`. All but the first `x`, `f` and `'y'` are deduplicated.
+        assert_eq!(
+            stats,
+            InternStats {
+                lookups: 6,
+                hits: 3
+            }
+        );
+        assert_eq!(stats.hit_rate(), 0.5);
+        let Statement::VariableDeclaration(decl) = &program.body[0] else {
+            unreachable!()
+        };
+        let Some(Expression::StringLiteral(str_lit)) = &decl.declarations[0].init else {
+            unreachable!()
+        };
+        // Binding `x` and string `'x'` share one string
+        assert!(core::ptr::eq(decl.declarations[0].id.name, str_lit.value));
+    }
+
+    #[test]
+    fn render() {
+        let source = "let x = 'é';\r\nlet = 2;";
+        let alloc = Allocator::default();
+        let err = parse_str(source, &alloc).unwrap_err();
+        assert_eq!(
+            err.render(&SourceText::new(source)),
+            "Expected variable name at 2:5\nlet = 2;\n    ^"
+        );
+    }
+
+    #[test]
+    fn parse_stream() {
+        let alloc = Allocator::default();
+        let mut stream = ParseStream::new("{ a; } b c; d;", &alloc);
+
+        let Some(Ok(Statement::BlockStatement(block))) = stream.next() else {
+            unreachable!()
+        };
+        // Statement has no parent yet, but statements inside it do
+        assert!(matches!(block.parent, StatementParent::None));
+        assert!(matches!(
+            block.body[0].parent(),
+            StatementParent::BlockStatement(_)
+        ));
+
+        let Some(Err(err)) = stream.next() else {
+            unreachable!()
+        };
+        assert_eq!(err.offset, 9);
+        assert!(stream.next().is_none());
+        assert!(stream.next().is_none());
+    }
+
+    fn nested_parens(depth: usize) -> String {
+        format!("{}x{};", "(".repeat(depth), ")".repeat(depth))
+    }
+
+    #[test]
+    fn max_depth() {
+        let alloc = Allocator::default();
+        // Statement and outer expression take 2 levels, each `(` takes 1 more
+        let depth = MAX_DEPTH as usize - 2;
+        assert!(parse_str(&nested_parens(depth), &alloc).is_ok());
+
+        let err = parse_str(&nested_parens(depth + 1), &alloc).unwrap_err();
+        assert_eq!(err.message, "Maximum nesting depth exceeded");
+        // Error is at `x`, after the last `(`
+        assert_eq!(err.offset, depth + 1);
+    }
+
+    #[test]
+    fn deep_nesting_is_error_not_stack_overflow() {
+        let sources = [
+            nested_parens(100_000),
+            format!("{}x;", "-".repeat(100_000)),
+            format!("x{};", " ** x".repeat(100_000)),
+            format!("x{};", " = x".repeat(100_000)),
+            format!("x{};", " ? x : x".repeat(100_000)),
+            format!("{}x;", "[".repeat(100_000)),
+            format!("({}x);", "{ a: ".repeat(100_000)),
+            format!("{}x;", "f(".repeat(100_000)),
+            format!("{}x;", "a[".repeat(100_000)),
+            format!("{}x;", "`${".repeat(100_000)),
+            format!("{}x;", "if (x) ".repeat(100_000)),
+            "{".repeat(100_000),
+        ];
+        for source in sources {
+            let alloc = Allocator::default();
+            let err = parse_str(&source, &alloc).unwrap_err();
+            assert_eq!(err.message, "Maximum nesting depth exceeded");
+        }
+    }
 }
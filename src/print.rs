@@ -1,7 +1,17 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
 use crate::{
     ast::{
-        BinaryExpression, BinaryOperator, ExpressionStatement, IdentifierReference, Program,
-        StringLiteral, UnaryExpression, UnaryOperator,
+        ArrayExpression, AssignmentExpression, BinaryExpression, BinaryOperator, BlockStatement,
+        BooleanLiteral, CallExpression, CommentKind, ConditionalExpression, Expression,
+        ExpressionParent, ExpressionStatement, IdentifierReference, IfStatement, LogicalExpression,
+        LogicalOperator, MemberExpression, NullLiteral, NumericLiteral, ObjectExpression,
+        Precedence, Program, Property, ReturnStatement, Span, Statement, StringLiteral,
+        TemplateLiteral, UnaryExpression, VarKind, VariableDeclaration, VariableDeclarator,
     },
     Visit,
 };
@@ -11,58 +21,1018 @@ use crate::{
 /// This just a quick hack to get something to check this demo is working.
 pub struct Printer {
     output: String,
+    options: PrinterOptions,
+    /// Current nesting depth of block statements
+    indent_level: usize,
+    /// Minimum precedence the next expression must have to be printed without parentheses
+    precedence: Precedence,
+    /// Source map being built. `None` if not requested.
+    sourcemap: Option<SourceMap>,
+    /// Comments attached to statements, as `(statement start, kind, text)`,
+    /// in ascending order of statement start
+    leading_comments: Vec<(u32, CommentKind, String)>,
+}
+
+/// Options for `Printer`.
+///
+/// `Default` is compact output: everything on one line (including the contents of blocks),
+/// but with spaces around operators.
+#[derive(Clone, Copy, Default, Debug)]
+pub struct PrinterOptions {
+    /// Number of spaces to indent each level of block statement by.
+    /// Only used if `newline_between_statements` is set.
+    pub indent: usize,
+    /// Print each statement on a new line, including statements in blocks
+    pub newline_between_statements: bool,
+    /// Omit all whitespace which is not required by the grammar
+    pub minify: bool,
+    /// Quotes to use for string literals
+    pub quote: QuoteStyle,
+}
+
+/// Quotes for `Printer` to use for string literals.
+///
+/// String literals which were parsed from source text are printed verbatim, unless their quotes
+/// differ from those required by a `Single` or `Double` style.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum QuoteStyle {
+    /// Always `'`
+    Single,
+    /// Always `"`
+    Double,
+    /// Whichever requires fewer escapes. `'` if equal.
+    #[default]
+    Prefer,
+}
+
+/// Mappings from positions in `Printer` output to the nodes which produced them.
+///
+/// Not a real source map in VLQ format, just a list of mappings, intended for debugging.
+#[derive(Clone, Default, Debug)]
+pub struct SourceMap {
+    /// Mappings in the order nodes were printed, which is ascending order of `output_offset`
+    pub mappings: Vec<Mapping>,
+}
+
+/// Mapping from a position in output to the span of a node in original source.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Mapping {
+    /// Byte offset in output where the node's text starts
+    pub output_offset: u32,
+    /// Span of the node in original source
+    pub span: Span,
 }
 
 impl Printer {
     pub fn print(program: &Program<'_>) -> String {
-        let mut printer = Printer {
-            output: String::new(),
-        };
+        Self::print_with(program, PrinterOptions::default())
+    }
+
+    pub fn print_with(program: &Program<'_>, options: PrinterOptions) -> String {
+        let mut printer = Printer::new(options, None);
         printer.visit_program(program);
         printer.output
     }
 
+    /// Print AST, and produce a source map mapping each statement and expression in the output
+    /// back to its span in the original source.
+    pub fn print_with_sourcemap(
+        program: &Program<'_>,
+        options: PrinterOptions,
+    ) -> (String, SourceMap) {
+        let mut printer = Printer::new(options, Some(SourceMap::default()));
+        printer.visit_program(program);
+        (printer.output, printer.sourcemap.unwrap())
+    }
+
+    fn new(options: PrinterOptions, sourcemap: Option<SourceMap>) -> Self {
+        Self {
+            output: String::new(),
+            options,
+            indent_level: 0,
+            precedence: Precedence::Assignment,
+            sourcemap,
+            leading_comments: Vec::new(),
+        }
+    }
+
     fn output(&mut self, s: &str) {
         self.output.push_str(s);
     }
+
+    /// Record that the node with `span` starts at current position in output.
+    fn add_mapping(&mut self, span: Span) {
+        if let Some(sourcemap) = &mut self.sourcemap {
+            sourcemap.mappings.push(Mapping {
+                output_offset: self.output.len() as u32,
+                span,
+            });
+        }
+    }
+
+    /// Output an operator, surrounded by spaces unless minifying.
+    fn output_operator(&mut self, operator: &str) {
+        if self.options.minify {
+            self.output(operator);
+        } else {
+            self.output(&format!(" {operator} "));
+        }
+    }
+
+    /// Output `,` separator in a list, followed by a space unless minifying.
+    fn output_comma(&mut self) {
+        self.output(if self.options.minify { "," } else { ", " });
+    }
+
+    /// Insert a space at `start` if the text before and after it would otherwise merge into
+    /// a single token (e.g. `typeof` + `foo`, or `-` + `-x`).
+    fn separate_from(&mut self, start: usize) {
+        let before = self.output[..start].chars().next_back();
+        let after = self.output[start..].chars().next();
+        if let (Some(before), Some(after)) = (before, after) {
+            let is_word_char = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+            if (is_word_char(before) && is_word_char(after))
+                || (before == after && matches!(before, '+' | '-'))
+            {
+                self.output.insert(start, ' ');
+                // Shift mappings for text after the inserted space
+                if let Some(sourcemap) = &mut self.sourcemap {
+                    for mapping in sourcemap.mappings.iter_mut().rev() {
+                        if (mapping.output_offset as usize) < start {
+                            break;
+                        }
+                        mapping.output_offset += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Visit an expression in a position which requires at least `precedence`.
+    /// The expression is wrapped in parentheses if it binds more loosely than that.
+    fn visit_expression_at(&mut self, expr: &Expression<'_>, precedence: Precedence) {
+        self.precedence = precedence;
+        self.visit_expression(expr);
+    }
+
+    /// Output comments attached to the statement starting at `start` in original source.
+    /// Comments are omitted when minifying.
+    fn output_leading_comments(&mut self, start: u32) {
+        if self.options.minify {
+            return;
+        }
+        let first = self
+            .leading_comments
+            .partition_point(|(attached_to, _, _)| *attached_to < start);
+        for index in first..self.leading_comments.len() {
+            let (attached_to, kind, text) = &self.leading_comments[index];
+            if *attached_to != start {
+                break;
+            }
+            match kind {
+                CommentKind::Line => {
+                    let line = format!("//{text}\n");
+                    self.output(&line);
+                    self.output_indent();
+                }
+                CommentKind::Block => {
+                    let block = format!("/*{text}*/ ");
+                    self.output(&block);
+                }
+            }
+        }
+    }
+
+    fn output_indent(&mut self) {
+        let width = self.indent_level * self.options.indent;
+        self.output.push_str(&" ".repeat(width));
+    }
 }
 
 impl<'a> Visit<'a> for Printer {
+    fn visit_expression(&mut self, expr: &Expression<'a>) {
+        // Reset precedence, so children visited without `visit_expression_at` don't inherit it
+        let required = core::mem::replace(&mut self.precedence, Precedence::Assignment);
+        if expr.precedence() < required {
+            self.output("(");
+            self.add_mapping(expr.span());
+            self.walk_expression(expr);
+            self.output(")");
+        } else {
+            self.add_mapping(expr.span());
+            self.walk_expression(expr);
+        }
+    }
+
+    fn visit_expression_with_parent(
+        &mut self,
+        expr: &Expression<'a>,
+        parent: ExpressionParent<'a>,
+    ) {
+        // `visit_member_expression` only passes this parent for non-computed member expressions.
+        // `1.toString()` would be parsed as number `1.` followed by `toString`,
+        // so integers need parentheses there: `(1).toString()`
+        if let (Expression::NumericLiteral(num_lit), ExpressionParent::MemberExpressionObject(_)) =
+            (expr, parent)
+        {
+            if format_number(num_lit.value)
+                .bytes()
+                .all(|b| b.is_ascii_digit())
+            {
+                self.output("(");
+                self.visit_expression(expr);
+                self.output(")");
+                return;
+            }
+        }
+        self.visit_expression(expr);
+    }
+
+    fn visit_statement(&mut self, stmt: &Statement<'a>) {
+        self.output_leading_comments(stmt.span().start);
+        self.add_mapping(stmt.span());
+        self.walk_statement(stmt);
+    }
+
+    fn visit_program(&mut self, program: &Program<'a>) {
+        self.leading_comments = program
+            .comments
+            .iter()
+            .filter_map(|comment| {
+                let start = comment.attached_to?;
+                Some((start, comment.kind, comment.text.to_string()))
+            })
+            .collect();
+        for (index, stmt) in program.body.iter().enumerate() {
+            if index > 0 && self.options.newline_between_statements {
+                self.output("\n");
+            }
+            self.visit_statement(stmt);
+        }
+    }
+
     fn visit_expression_statement(&mut self, expr_stmt: &ExpressionStatement<'a>) {
+        let start = self.output.len();
+        let first_mapping = self.sourcemap.as_ref().map_or(0, |sm| sm.mappings.len());
         self.walk_expression_statement(expr_stmt);
+        // `{` at start of a statement would be parsed as a block, so wrap in parentheses
+        if self.output[start..].starts_with('{') {
+            self.output.insert(start, '(');
+            // Shift mappings for the expression, but not for the statement itself
+            if let Some(sourcemap) = &mut self.sourcemap {
+                for mapping in &mut sourcemap.mappings[first_mapping..] {
+                    mapping.output_offset += 1;
+                }
+            }
+            self.output(")");
+        }
+        self.output(";");
+    }
+
+    fn visit_block_statement(&mut self, block: &BlockStatement<'a>) {
+        if self.options.minify {
+            self.output("{");
+            self.visit_statements(&block.body);
+            self.output("}");
+            return;
+        }
+
+        if !self.options.newline_between_statements {
+            if block.body.is_empty() {
+                self.output("{}");
+                return;
+            }
+            self.output("{");
+            for stmt in &block.body {
+                self.output(" ");
+                self.visit_statement(stmt);
+            }
+            self.output(" }");
+            return;
+        }
+
+        self.output("{\n");
+        self.indent_level += 1;
+        for stmt in &block.body {
+            self.output_indent();
+            self.visit_statement(stmt);
+            self.output("\n");
+        }
+        self.indent_level -= 1;
+        self.output_indent();
+        self.output("}");
+    }
+
+    fn visit_if_statement(&mut self, if_stmt: &IfStatement<'a>) {
+        if self.options.minify {
+            self.output("if(");
+            self.visit_expression(&if_stmt.test);
+            self.output(")");
+        } else {
+            self.output("if (");
+            self.visit_expression(&if_stmt.test);
+            self.output(") ");
+        }
+        self.visit_statement(&if_stmt.consequent);
+        if let Some(alternate) = &if_stmt.alternate {
+            self.output(if self.options.minify {
+                "else"
+            } else {
+                " else "
+            });
+            let start = self.output.len();
+            self.visit_statement(alternate);
+            self.separate_from(start);
+        }
+    }
+
+    fn visit_return_statement(&mut self, return_stmt: &ReturnStatement<'a>) {
+        self.output("return");
+        if let Some(argument) = &return_stmt.argument {
+            if !self.options.minify {
+                self.output(" ");
+            }
+            let start = self.output.len();
+            self.visit_expression(argument);
+            self.separate_from(start);
+        }
         self.output(";");
     }
 
+    fn visit_variable_declaration(&mut self, var_decl: &VariableDeclaration<'a>) {
+        self.output(match var_decl.kind {
+            VarKind::Var => "var ",
+            VarKind::Let => "let ",
+            VarKind::Const => "const ",
+        });
+        for (index, declarator) in var_decl.declarations.iter().enumerate() {
+            if index > 0 {
+                self.output_comma();
+            }
+            self.visit_variable_declarator(declarator);
+        }
+        self.output(";");
+    }
+
+    fn visit_variable_declarator(&mut self, declarator: &VariableDeclarator<'a>) {
+        self.add_mapping(declarator.span);
+        self.output(declarator.id.name);
+        if let Some(init) = &declarator.init {
+            self.output_operator("=");
+            self.visit_expression(init);
+        }
+    }
+
     fn visit_identifier_reference(&mut self, id: &IdentifierReference<'a>) {
         self.output(id.name);
     }
 
     fn visit_string_literal(&mut self, str_lit: &StringLiteral<'a>) {
-        self.output(&format!("'{}'", str_lit.value));
+        let quote = match self.options.quote {
+            QuoteStyle::Single => '\'',
+            QuoteStyle::Double => '"',
+            QuoteStyle::Prefer => {
+                if let Some(raw) = str_lit.raw {
+                    self.output(raw);
+                    return;
+                }
+                preferred_quote(str_lit.value)
+            }
+        };
+        match str_lit.raw {
+            Some(raw) if raw.starts_with(quote) => self.output(raw),
+            _ => self.output(&quote_string(str_lit.value, quote)),
+        }
+    }
+
+    fn visit_numeric_literal(&mut self, num_lit: &NumericLiteral<'a>) {
+        // `Infinity` is an identifier, not a literal, so would not parse back to a numeric literal.
+        // `1e999` overflows to infinity, so is a literal with the same value.
+        if num_lit.value.is_infinite() {
+            self.output(if num_lit.value > 0.0 {
+                "1e999"
+            } else {
+                "-1e999"
+            });
+        } else {
+            self.output(&format_number(num_lit.value));
+        }
+    }
+
+    fn visit_boolean_literal(&mut self, bool_lit: &BooleanLiteral<'a>) {
+        self.output(if bool_lit.value { "true" } else { "false" });
+    }
+
+    fn visit_null_literal(&mut self, _null_lit: &NullLiteral<'a>) {
+        self.output("null");
     }
 
     fn visit_unary_expression(&mut self, unary_expr: &UnaryExpression<'a>) {
-        match unary_expr.operator {
-            UnaryOperator::UnaryNegation => self.output("-"),
-            UnaryOperator::UnaryPlus => self.output("+"),
-            UnaryOperator::LogicalNot => self.output("!"),
-            UnaryOperator::BitwiseNot => self.output("~"),
-            UnaryOperator::Typeof => self.output("typeof "),
-            UnaryOperator::Void => self.output("void "),
-            UnaryOperator::Delete => self.output("delete "),
+        self.output(unary_expr.operator.as_str());
+        // Keyword operators (`typeof` etc) are followed by a space unless minifying.
+        // When minifying, `separate_from` adds one only if it's required.
+        if !self.options.minify && unary_expr.operator.needs_trailing_space() {
+            self.output(" ");
         }
-        self.visit_expression(&unary_expr.argument);
+        let start = self.output.len();
+        self.visit_expression_at(&unary_expr.argument, unary_expr.operator.precedence());
+        self.separate_from(start);
     }
 
     fn visit_binary_expression(&mut self, bin_expr: &BinaryExpression<'a>) {
-        self.visit_expression(&bin_expr.left);
-        self.output(&format!(
-            " {} ",
-            match bin_expr.operator {
-                BinaryOperator::Equality => "==",
-                BinaryOperator::StrictEquality => "===",
-            }
-        ));
-        self.visit_expression(&bin_expr.right);
+        let precedence = bin_expr.operator.precedence();
+        let (left_precedence, right_precedence) = if bin_expr.operator == BinaryOperator::Exponent {
+            // `**` is right-associative, and its left side cannot be a unary expression
+            (Precedence::Unary.next(), precedence)
+        } else {
+            (precedence, precedence.next())
+        };
+        self.visit_expression_at(&bin_expr.left, left_precedence);
+        let operator_start = self.output.len();
+        self.output_operator(bin_expr.operator.as_str());
+        let right_start = self.output.len();
+        self.visit_expression_at(&bin_expr.right, right_precedence);
+        // When minifying, `a in b` and `a - -b` need spaces
+        self.separate_from(right_start);
+        self.separate_from(operator_start);
+    }
+
+    fn visit_assignment_expression(&mut self, assign_expr: &AssignmentExpression<'a>) {
+        self.visit_expression_at(&assign_expr.target, Precedence::Call);
+        self.output_operator(assign_expr.operator.as_str());
+        self.visit_expression(&assign_expr.value);
+    }
+
+    fn visit_conditional_expression(&mut self, cond_expr: &ConditionalExpression<'a>) {
+        self.visit_expression_at(&cond_expr.test, Precedence::Conditional.next());
+        self.output_operator("?");
+        // JS allows assignments in consequent and alternate without parentheses,
+        // but our parser doesn't, so add them to keep output parseable
+        self.visit_expression_at(&cond_expr.consequent, Precedence::Conditional);
+        self.output_operator(":");
+        self.visit_expression_at(&cond_expr.alternate, Precedence::Conditional);
+    }
+
+    fn visit_logical_expression(&mut self, logical_expr: &LogicalExpression<'a>) {
+        let precedence = logical_expr.operator.precedence();
+        let left_precedence =
+            logical_child_precedence(logical_expr, &logical_expr.left, precedence);
+        self.visit_expression_at(&logical_expr.left, left_precedence);
+        self.output_operator(logical_expr.operator.as_str());
+        let right_precedence =
+            logical_child_precedence(logical_expr, &logical_expr.right, precedence.next());
+        self.visit_expression_at(&logical_expr.right, right_precedence);
+    }
+
+    fn visit_call_expression(&mut self, call_expr: &CallExpression<'a>) {
+        self.visit_expression_at(&call_expr.callee, Precedence::Call);
+        self.output("(");
+        for (index, arg) in call_expr.arguments.iter().enumerate() {
+            if index > 0 {
+                self.output_comma();
+            }
+            self.visit_expression(arg);
+        }
+        self.output(")");
+    }
+
+    fn visit_member_expression(&mut self, member_expr: &MemberExpression<'a>) {
+        if member_expr.computed {
+            self.visit_expression_at(&member_expr.object, Precedence::Call);
+            self.output("[");
+            self.visit_expression(&member_expr.property);
+            self.output("]");
+        } else {
+            self.precedence = Precedence::Call;
+            self.visit_expression_with_parent(
+                &member_expr.object,
+                ExpressionParent::MemberExpressionObject(member_expr),
+            );
+            self.output(".");
+            self.visit_expression(&member_expr.property);
+        }
+    }
+
+    fn visit_template_literal(&mut self, template_lit: &TemplateLiteral<'a>) {
+        self.output("`");
+        self.output(template_lit.quasis.as_slice()[0]);
+        for (expr, quasi) in template_lit
+            .expressions
+            .iter()
+            .zip(&template_lit.quasis.as_slice()[1..])
+        {
+            self.output("${");
+            self.visit_expression(expr);
+            self.output("}");
+            self.output(quasi);
+        }
+        self.output("`");
+    }
+
+    fn visit_array_expression(&mut self, array_expr: &ArrayExpression<'a>) {
+        self.output("[");
+        for (index, element) in array_expr.elements.iter().enumerate() {
+            if index > 0 {
+                self.output_comma();
+            }
+            if let Some(element) = element {
+                self.visit_expression(element);
+            }
+        }
+        // A hole at the end needs a trailing comma, or it would be dropped
+        if matches!(array_expr.elements.last(), Some(None)) {
+            self.output(",");
+        }
+        self.output("]");
+    }
+
+    fn visit_object_expression(&mut self, object_expr: &ObjectExpression<'a>) {
+        if object_expr.properties.is_empty() {
+            self.output("{}");
+            return;
+        }
+        self.output(if self.options.minify { "{" } else { "{ " });
+        for (index, prop) in object_expr.properties.iter().enumerate() {
+            if index > 0 {
+                self.output_comma();
+            }
+            self.visit_property(prop);
+        }
+        self.output(if self.options.minify { "}" } else { " }" });
+    }
+
+    fn visit_property(&mut self, prop: &Property<'a>) {
+        // Only print as shorthand if key and value still have the same name.
+        // A transform may have renamed the value, but not the key.
+        if prop.shorthand {
+            if let (Expression::Identifier(key), Expression::Identifier(value)) =
+                (&prop.key, &prop.value)
+            {
+                if key.name == value.name {
+                    self.visit_expression(&prop.value);
+                    return;
+                }
+            }
+        }
+        if prop.computed {
+            self.output("[");
+            self.visit_expression(&prop.key);
+            self.output("]");
+        } else {
+            self.visit_expression(&prop.key);
+        }
+        self.output(if self.options.minify { ":" } else { ": " });
+        self.visit_expression(&prop.value);
+    }
+}
+
+/// Get precedence required for a child of a logical expression.
+/// `??` cannot be mixed with `&&` or `||` without parentheses, so those force parentheses.
+fn logical_child_precedence(
+    logical_expr: &LogicalExpression<'_>,
+    child: &Expression<'_>,
+    precedence: Precedence,
+) -> Precedence {
+    if let Expression::LogicalExpression(child) = child {
+        let is_nullish = |operator| operator == LogicalOperator::NullishCoalescing;
+        if is_nullish(logical_expr.operator) != is_nullish(child.operator) {
+            return Precedence::Primary;
+        }
+    }
+    precedence
+}
+
+/// Format a number the way JS would print it, following `Number::toString` in the spec.
+///
+/// Rust's `LowerExp` for `f64` produces the shortest digits which round-trip, as JS does.
+/// Only where the decimal point goes, and whether to use exponent form, differ.
+/// JS uses exponent form for values `>= 1e21` or `< 1e-6`, e.g. `1e+21`, `1.5e-7`.
+fn format_number(value: f64) -> String {
+    if value.is_nan() {
+        return "NaN".to_string();
+    }
+    if value.is_infinite() {
+        return if value > 0.0 { "Infinity" } else { "-Infinity" }.to_string();
+    }
+    if value == 0.0 {
+        // Includes `-0`, which JS prints as `0`
+        return "0".to_string();
+    }
+    if value < 0.0 {
+        return format!("-{}", format_number(-value));
+    }
+
+    // `value` is `0.digits * 10^n`, where `digits` has no trailing zeros
+    let exp = format!("{value:e}");
+    let (mantissa, exponent) = exp.split_once('e').unwrap();
+    let digits = mantissa.replace('.', "");
+    let k = digits.len() as i32;
+    let n = exponent.parse::<i32>().unwrap() + 1;
+
+    if k <= n && n <= 21 {
+        format!("{digits}{}", "0".repeat((n - k) as usize))
+    } else if 0 < n && n <= 21 {
+        let (int, fraction) = digits.split_at(n as usize);
+        format!("{int}.{fraction}")
+    } else if -6 < n && n <= 0 {
+        format!("0.{}{digits}", "0".repeat(-n as usize))
+    } else {
+        let sign = if n > 0 { '+' } else { '-' };
+        let (first, rest) = digits.split_at(1);
+        let dot = if rest.is_empty() { "" } else { "." };
+        format!("{first}{dot}{rest}e{sign}{}", (n - 1).abs())
+    }
+}
+
+/// Get quote which requires fewer escapes to print `value` as a string literal.
+/// Single quotes are used unless the string contains more single quotes than double quotes.
+fn preferred_quote(value: &str) -> char {
+    let singles = value.matches('\'').count();
+    let doubles = value.matches('"').count();
+    if singles > doubles {
+        '"'
+    } else {
+        '\''
+    }
+}
+
+/// Format a string as a JS string literal, with `quote` quotes and escapes.
+fn quote_string(value: &str, quote: char) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push(quote);
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '\u{8}' => out.push_str("\\b"),
+            '\u{b}' => out.push_str("\\v"),
+            '\u{c}' => out.push_str("\\f"),
+            // `\0` followed by a digit would be read as a legacy octal escape
+            '\0' if !chars.peek().is_some_and(char::is_ascii_digit) => out.push_str("\\0"),
+            '\u{2028}' => out.push_str("\\u2028"),
+            '\u{2029}' => out.push_str("\\u2029"),
+            c if c == quote => {
+                out.push('\\');
+                out.push(c);
+            }
+            c if c.is_ascii_control() => out.push_str(&format!("\\x{:02x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push(quote);
+    out
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use oxc_allocator::Allocator;
+
+    use super::*;
+    use crate::{builder::AstBuilder, parser};
+
+    fn round_trip(source: &str) -> String {
+        let alloc = Allocator::default();
+        let program = parser::parse_str(source, &alloc).unwrap();
+        Printer::print(program)
+    }
+
+    /// Print a program containing just a string literal with `value`, which has no `raw`.
+    fn print_string(value: &str, options: PrinterOptions) -> String {
+        let alloc = Allocator::default();
+        let ast = AstBuilder::new(&alloc);
+        let span = Span::default();
+        let mut body = ast.vec();
+        body.push(ast.expression_statement(span, ast.string_literal(span, value, None)));
+        Printer::print_with(ast.program(body), options)
+    }
+
+    #[test]
+    fn number_formatting() {
+        let cases = [
+            (0.0, "0"),
+            (-0.0, "0"),
+            (123.0, "123"),
+            (-1.5, "-1.5"),
+            (0.1, "0.1"),
+            (0.000001, "0.000001"),
+            (1e-7, "1e-7"),
+            (1.5e-7, "1.5e-7"),
+            (1e20, "100000000000000000000"),
+            (123456789012345680000.0, "123456789012345680000"),
+            (1e21, "1e+21"),
+            (1.25e21, "1.25e+21"),
+            (f64::MAX, "1.7976931348623157e+308"),
+            (5e-324, "5e-324"),
+            (f64::NAN, "NaN"),
+            (f64::NEG_INFINITY, "-Infinity"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(format_number(value), expected);
+        }
+    }
+
+    #[test]
+    fn exponent_literals() {
+        assert_eq!(
+            round_trip("1e21; 1.5E-7; 1e-6; 2.50e0; 1e21.x;"),
+            "1e+21;1.5e-7;0.000001;2.5;1e+21.x;"
+        );
+        // Too large to represent, so infinite
+        assert_eq!(round_trip("5e1115; !1e999;"), "1e999;!1e999;");
+        let alloc = Allocator::default();
+        let err = parser::parse_str("1e;", &alloc).unwrap_err();
+        assert_eq!(err.message, "Invalid or unexpected token");
+    }
+
+    #[test]
+    fn left_associative_parens() {
+        assert_eq!(round_trip("(a === b) === c;"), "a === b === c;");
+        assert_eq!(round_trip("a === (b === c);"), "a === (b === c);");
+    }
+
+    #[test]
+    fn logical_parens() {
+        assert_eq!(round_trip("(a || b) && c;"), "(a || b) && c;");
+    }
+
+    #[test]
+    fn member_expression() {
+        assert_eq!(round_trip("foo.bar;"), "foo.bar;");
+        assert_eq!(round_trip("foo['bar'];"), "foo['bar'];");
+        assert_eq!(round_trip("foo[bar].baz;"), "foo[bar].baz;");
+    }
+
+    #[test]
+    fn nested_conditional() {
+        assert_eq!(round_trip("a ? b ? c : d : e;"), "a ? b ? c : d : e;");
+        assert_eq!(round_trip("a ? b : c ? d : e;"), "a ? b : c ? d : e;");
+        assert_eq!(round_trip("(a ? b : c) ? d : e;"), "(a ? b : c) ? d : e;");
+    }
+
+    #[test]
+    fn if_statement() {
+        assert_eq!(round_trip("if (a) b;"), "if (a) b;");
+        assert_eq!(round_trip("if (a) b; else c;"), "if (a) b; else c;");
+    }
+
+    #[test]
+    fn block_statement() {
+        let source = "if (a) { b; c; } else { { } }";
+        assert_eq!(round_trip(source), "if (a) { b; c; } else { {} }");
+
+        let alloc = Allocator::default();
+        let program = parser::parse_str(source, &alloc).unwrap();
+        let options = PrinterOptions {
+            indent: 2,
+            newline_between_statements: true,
+            ..PrinterOptions::default()
+        };
+        assert_eq!(
+            Printer::print_with(program, options),
+            "if (a) {\n  b;\n  c;\n} else {\n  {\n  }\n}"
+        );
+        let minify = PrinterOptions {
+            minify: true,
+            ..PrinterOptions::default()
+        };
+        assert_eq!(Printer::print_with(program, minify), "if(a){b;c;}else{{}}");
+    }
+
+    #[test]
+    fn string_escapes() {
+        let single = PrinterOptions {
+            quote: QuoteStyle::Single,
+            ..PrinterOptions::default()
+        };
+        assert_eq!(print_string("it's", single), r"'it\'s';");
+        assert_eq!(
+            print_string("it's", PrinterOptions::default()),
+            r#""it's";"#
+        );
+        assert_eq!(
+            print_string("line 1\nline 2\r\n\ttab \\", PrinterOptions::default()),
+            r"'line 1\nline 2\r\n\ttab \\';"
+        );
+        assert_eq!(round_trip("'it\\'s';"), "'it\\'s';");
+    }
+
+    #[test]
+    fn quote_style() {
+        let with_quote = |quote| PrinterOptions {
+            quote,
+            ..PrinterOptions::default()
+        };
+        // More single quotes than double
+        let value = r#"it's "x" 'y'"#;
+        assert_eq!(
+            print_string(value, with_quote(QuoteStyle::Single)),
+            r#"'it\'s "x" \'y\'';"#
+        );
+        assert_eq!(
+            print_string(value, with_quote(QuoteStyle::Double)),
+            r#""it's \"x\" 'y'";"#
+        );
+        assert_eq!(
+            print_string(value, with_quote(QuoteStyle::Prefer)),
+            r#""it's \"x\" 'y'";"#
+        );
+        // More double quotes than single
+        let value = r#"it's "x""#;
+        assert_eq!(
+            print_string(value, with_quote(QuoteStyle::Prefer)),
+            r#"'it\'s "x"';"#
+        );
+        // Raw string is reused if it has the required quotes, otherwise value is re-quoted
+        assert_eq!(round_trip(r#"'a' + "b";"#), r#"'a' + "b";"#);
+        let alloc = Allocator::default();
+        let program = parser::parse_str(r#"'a' + "b";"#, &alloc).unwrap();
+        assert_eq!(
+            Printer::print_with(program, with_quote(QuoteStyle::Double)),
+            r#""a" + "b";"#
+        );
+    }
+
+    #[test]
+    fn array_expression() {
+        assert_eq!(round_trip("[1, 2, 3];"), "[1, 2, 3];");
+        assert_eq!(round_trip("[1, , 3];"), "[1, , 3];");
+        assert_eq!(round_trip("[, a];"), "[, a];");
+        // Trailing hole needs an extra comma
+        assert_eq!(round_trip("[a, , ];"), "[a, ,];");
+        assert_eq!(round_trip("[];"), "[];");
+    }
+
+    #[test]
+    fn comments() {
+        let source = "/* a */ x; /* b */ if (y) /* c */ z; w /* d */;";
+        let alloc = Allocator::default();
+        let program = parser::parse_str(source, &alloc).unwrap();
+        let attached = program
+            .comments
+            .iter()
+            .map(|comment| (comment.text, comment.attached_to))
+            .collect::<std::vec::Vec<_>>();
+        // `a` is before the first statement, `b` between statements, `c` before a nested
+        // statement. `d` is inside a statement, so is not attached, and not printed.
+        assert_eq!(
+            attached,
+            [
+                (" a ", Some(8)),
+                (" b ", Some(19)),
+                (" c ", Some(34)),
+                (" d ", None)
+            ]
+        );
+        assert_eq!(
+            Printer::print(program),
+            "/* a */ x;/* b */ if (y) /* c */ z;w;"
+        );
+
+        let options = PrinterOptions {
+            newline_between_statements: true,
+            ..PrinterOptions::default()
+        };
+        assert_eq!(
+            Printer::print_with(program, options),
+            "/* a */ x;\n/* b */ if (y) /* c */ z;\nw;"
+        );
+        let minify = PrinterOptions {
+            minify: true,
+            ..PrinterOptions::default()
+        };
+        assert_eq!(Printer::print_with(program, minify), "x;if(y)z;w;");
+
+        assert_eq!(
+            round_trip("// a\nx;\n// b\n// c\ny;"),
+            "// a\nx;// b\n// c\ny;"
+        );
+    }
+
+    /// Print `source` with a source map, and get each mapping as output offset and the source
+    /// text of the mapped node.
+    fn mappings(source: &str, options: PrinterOptions) -> (String, Vec<(u32, &str)>) {
+        let alloc = Allocator::default();
+        let program = parser::parse_str(source, &alloc).unwrap();
+        let (output, sourcemap) = Printer::print_with_sourcemap(program, options);
+        let mappings = sourcemap
+            .mappings
+            .iter()
+            .map(|mapping| {
+                let span = mapping.span;
+                (
+                    mapping.output_offset,
+                    &source[span.start as usize..span.end as usize],
+                )
+            })
+            .collect();
+        (output, mappings)
+    }
+
+    #[test]
+    fn sourcemap() {
+        let (output, mappings) = mappings("let x = a  +  b;", PrinterOptions::default());
+        assert_eq!(output, "let x = a + b;");
+        assert_eq!(
+            mappings,
+            [
+                (0, "let x = a  +  b;"),
+                (4, "x = a  +  b"),
+                (8, "a  +  b"),
+                (8, "a"),
+                (12, "b")
+            ]
+        );
+    }
+
+    #[test]
+    fn sourcemap_inserted_space() {
+        // `separate_from` inserts a space before `foo` and `-b` after they're printed
+        let minify = PrinterOptions {
+            minify: true,
+            ..PrinterOptions::default()
+        };
+        let (output, mappings) = mappings("typeof  foo; a - -b;", minify);
+        assert_eq!(output, "typeof foo;a- -b;");
+        assert_eq!(
+            mappings,
+            [
+                (0, "typeof  foo;"),
+                (0, "typeof  foo"),
+                (7, "foo"),
+                (11, "a - -b;"),
+                (11, "a - -b"),
+                (11, "a"),
+                (14, "-b"),
+                (15, "b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn sourcemap_inserted_paren() {
+        // `visit_expression_statement` inserts `(` before the expression, but after the statement
+        let (output, mappings) = mappings("x; ({ a: 1 }).b;", PrinterOptions::default());
+        assert_eq!(output, "x;({ a: 1 }.b);");
+        assert_eq!(
+            mappings,
+            [
+                (0, "x;"),
+                (0, "x"),
+                (2, "({ a: 1 }).b;"),
+                (3, "({ a: 1 }).b"),
+                (3, "{ a: 1 }"),
+                (5, "a"),
+                (8, "1"),
+                (12, "b"),
+            ]
+        );
+    }
+
+    #[test]
+    fn template_literal() {
+        assert_eq!(round_trip("`abc`;"), "`abc`;");
+        assert_eq!(round_trip("``;"), "``;");
+        assert_eq!(round_trip("`a${b}c${d + 1}e`;"), "`a${b}c${d + 1}e`;");
+        assert_eq!(round_trip("`${a}${`${b}`}`;"), "`${a}${`${b}`}`;");
+        // Escaped backtick and `${` are kept in the raw quasis
+        assert_eq!(round_trip(r"`\`${a}\${b}`;"), r"`\`${a}\${b}`;");
+
+        let alloc = Allocator::default();
+        let program = parser::parse_str(r"`\`${a}\${b}`;", &alloc).unwrap();
+        let Statement::ExpressionStatement(stmt) = &program.body[0] else {
+            unreachable!()
+        };
+        let Expression::TemplateLiteral(template_lit) = &stmt.expression else {
+            unreachable!()
+        };
+        assert_eq!(template_lit.quasis.as_slice(), [r"\`", r"\${b}"]);
+        assert_eq!(template_lit.expressions.len(), 1);
+    }
+
+    #[test]
+    fn negative_literal_parens() {
+        let alloc = Allocator::default();
+        let ast = AstBuilder::new(&alloc);
+        let span = Span::default();
+        let exponent = ast.binary_expression(
+            span,
+            ast.numeric_literal(span, -5.0),
+            BinaryOperator::Exponent,
+            ast.numeric_literal(span, 2.0),
+        );
+        let member = ast.member_expression(
+            span,
+            ast.numeric_literal(span, -5.0),
+            ast.identifier_reference(span, "x"),
+            false,
+        );
+        let mut body = ast.vec();
+        body.push(ast.expression_statement(span, exponent));
+        body.push(ast.expression_statement(span, member));
+        let program = ast.program(body);
+        assert_eq!(Printer::print(program), "(-5) ** 2;(-5).x;");
     }
 }
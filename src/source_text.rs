@@ -0,0 +1,123 @@
+//! Resolution of byte offsets in source text to line and column numbers.
+
+use alloc::{format, string::String, vec, vec::Vec};
+
+/// Source text, with an index of where each line starts, for converting byte offsets
+/// (e.g. in `Span`s and errors) to line and column numbers.
+pub struct SourceText<'a> {
+    text: &'a str,
+    /// Byte offset of start of each line. First entry is always 0.
+    line_starts: Vec<u32>,
+}
+
+impl<'a> SourceText<'a> {
+    /// Build line index in a single pass over `text`.
+    /// Lines are terminated by `\n`, `\r\n`, or `\r`.
+    pub fn new(text: &'a str) -> Self {
+        let bytes = text.as_bytes();
+        let mut line_starts = vec![0];
+        for (index, &b) in bytes.iter().enumerate() {
+            if b == b'\n' || (b == b'\r' && bytes.get(index + 1) != Some(&b'\n')) {
+                line_starts.push(index as u32 + 1);
+            }
+        }
+        Self { text, line_starts }
+    }
+
+    pub fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Get line and column of byte `offset`. Both are 1-based.
+    /// Column is counted in chars, not bytes.
+    /// Offsets past end of text resolve to the end of the last line.
+    pub fn line_col(&self, offset: u32) -> (u32, u32) {
+        let offset = offset.min(self.text.len() as u32);
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line] as usize;
+        // Count bytes which are not UTF-8 continuation bytes
+        let column = self.text.as_bytes()[line_start..offset as usize]
+            .iter()
+            .filter(|&&b| (b as i8) >= -0x40)
+            .count();
+        (line as u32 + 1, column as u32 + 1)
+    }
+
+    /// Get text of `line` (1-based), without its line terminator.
+    pub fn line(&self, line: u32) -> &'a str {
+        let start = self.line_starts[line as usize - 1] as usize;
+        let end = self
+            .line_starts
+            .get(line as usize)
+            .map_or(self.text.len(), |&end| end as usize);
+        self.text[start..end].trim_end_matches(['\n', '\r'])
+    }
+
+    /// Get the line containing byte `offset`, followed by a line with a `^` under `offset`.
+    pub fn caret(&self, offset: u32) -> String {
+        let (line, column) = self.line_col(offset);
+        format!("{}\n{}^", self.line(line), " ".repeat(column as usize - 1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_col() {
+        let source = SourceText::new("ab\ncd");
+        assert_eq!(source.line_col(0), (1, 1));
+        assert_eq!(source.line_col(2), (1, 3));
+        assert_eq!(source.line_col(3), (2, 1));
+        assert_eq!(source.line_col(4), (2, 2));
+    }
+
+    #[test]
+    fn crlf() {
+        let source = SourceText::new("ab\r\ncd");
+        // `\r\n` is a single line break, so `\n` is still on line 1
+        assert_eq!(source.line_col(2), (1, 3));
+        assert_eq!(source.line_col(3), (1, 4));
+        assert_eq!(source.line_col(4), (2, 1));
+        assert_eq!(source.line(1), "ab");
+        assert_eq!(source.caret(5), "cd\n ^");
+    }
+
+    #[test]
+    fn lone_cr() {
+        let source = SourceText::new("ab\rcd\ne");
+        assert_eq!(source.line_col(3), (2, 1));
+        assert_eq!(source.line_col(6), (3, 1));
+        assert_eq!(source.line(1), "ab");
+        assert_eq!(source.line(2), "cd");
+        assert_eq!(source.caret(4), "cd\n ^");
+    }
+
+    #[test]
+    fn multibyte() {
+        // `é` is 2 bytes, `€` is 3 bytes, but each is 1 column
+        let source = SourceText::new("x\né€y");
+        assert_eq!(source.line_col(2), (2, 1));
+        assert_eq!(source.line_col(4), (2, 2));
+        assert_eq!(source.line_col(7), (2, 3));
+        assert_eq!(source.caret(7), "é€y\n  ^");
+    }
+
+    #[test]
+    fn end_of_text() {
+        let source = SourceText::new("ab\ncd");
+        assert_eq!(source.line_col(5), (2, 3));
+        assert_eq!(source.line_col(100), (2, 3));
+        assert_eq!(source.caret(100), "cd\n  ^");
+
+        // Offset after a trailing line break is at start of an empty last line
+        let source = SourceText::new("ab\n");
+        assert_eq!(source.line_col(3), (2, 1));
+        assert_eq!(source.caret(3), "\n^");
+
+        let source = SourceText::new("");
+        assert_eq!(source.line_col(0), (1, 1));
+        assert_eq!(source.caret(0), "\n^");
+    }
+}
@@ -0,0 +1,296 @@
+//! Statistics about an AST.
+
+use oxc_allocator::Allocator;
+
+use crate::{
+    ast::{
+        ArrayExpression, AssignmentExpression, BinaryExpression, BlockStatement, BooleanLiteral,
+        CallExpression, ConditionalExpression, Expression, ExpressionStatement,
+        IdentifierReference, IfStatement, LogicalExpression, MemberExpression, NullLiteral,
+        NumericLiteral, ObjectExpression, Program, Property, ReturnStatement, Statement,
+        StringLiteral, TemplateLiteral, UnaryExpression, VariableDeclaration, VariableDeclarator,
+    },
+    Visit,
+};
+
+/// Type of an AST node.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[repr(u8)]
+pub enum AstType {
+    Program = 0,
+    ExpressionStatement = 1,
+    BlockStatement = 2,
+    IfStatement = 3,
+    ReturnStatement = 4,
+    VariableDeclaration = 5,
+    VariableDeclarator = 6,
+    IdentifierReference = 7,
+    StringLiteral = 8,
+    NumericLiteral = 9,
+    BooleanLiteral = 10,
+    NullLiteral = 11,
+    BinaryExpression = 12,
+    UnaryExpression = 13,
+    CallExpression = 14,
+    MemberExpression = 15,
+    LogicalExpression = 16,
+    ConditionalExpression = 17,
+    AssignmentExpression = 18,
+    TemplateLiteral = 19,
+    ArrayExpression = 20,
+    ObjectExpression = 21,
+    Property = 22,
+}
+
+impl AstType {
+    /// Number of node types
+    pub const COUNT: usize = Self::ALL.len();
+
+    /// All node types, in discriminant order
+    pub const ALL: [AstType; 23] = [
+        Self::Program,
+        Self::ExpressionStatement,
+        Self::BlockStatement,
+        Self::IfStatement,
+        Self::ReturnStatement,
+        Self::VariableDeclaration,
+        Self::VariableDeclarator,
+        Self::IdentifierReference,
+        Self::StringLiteral,
+        Self::NumericLiteral,
+        Self::BooleanLiteral,
+        Self::NullLiteral,
+        Self::BinaryExpression,
+        Self::UnaryExpression,
+        Self::CallExpression,
+        Self::MemberExpression,
+        Self::LogicalExpression,
+        Self::ConditionalExpression,
+        Self::AssignmentExpression,
+        Self::TemplateLiteral,
+        Self::ArrayExpression,
+        Self::ObjectExpression,
+        Self::Property,
+    ];
+
+    /// Get type of a statement.
+    pub fn of_statement(stmt: &Statement<'_>) -> Self {
+        match stmt {
+            Statement::ExpressionStatement(_) => Self::ExpressionStatement,
+            Statement::BlockStatement(_) => Self::BlockStatement,
+            Statement::IfStatement(_) => Self::IfStatement,
+            Statement::ReturnStatement(_) => Self::ReturnStatement,
+            Statement::VariableDeclaration(_) => Self::VariableDeclaration,
+        }
+    }
+
+    /// Get type of an expression.
+    pub fn of_expression(expr: &Expression<'_>) -> Self {
+        match expr {
+            Expression::Identifier(_) => Self::IdentifierReference,
+            Expression::StringLiteral(_) => Self::StringLiteral,
+            Expression::NumericLiteral(_) => Self::NumericLiteral,
+            Expression::BooleanLiteral(_) => Self::BooleanLiteral,
+            Expression::NullLiteral(_) => Self::NullLiteral,
+            Expression::BinaryExpression(_) => Self::BinaryExpression,
+            Expression::UnaryExpression(_) => Self::UnaryExpression,
+            Expression::CallExpression(_) => Self::CallExpression,
+            Expression::MemberExpression(_) => Self::MemberExpression,
+            Expression::LogicalExpression(_) => Self::LogicalExpression,
+            Expression::ConditionalExpression(_) => Self::ConditionalExpression,
+            Expression::AssignmentExpression(_) => Self::AssignmentExpression,
+            Expression::TemplateLiteral(_) => Self::TemplateLiteral,
+            Expression::ArrayExpression(_) => Self::ArrayExpression,
+            Expression::ObjectExpression(_) => Self::ObjectExpression,
+        }
+    }
+}
+
+/// Number of nodes of each type in an AST, indexed by `AstType` discriminant.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct NodeCounts(pub [usize; AstType::COUNT]);
+
+impl NodeCounts {
+    pub fn get(&self, ty: AstType) -> usize {
+        self.0[ty as usize]
+    }
+
+    /// Total number of nodes of all types.
+    pub fn total(&self) -> usize {
+        self.0.iter().sum()
+    }
+
+    /// Iterate over node types with a non-zero count.
+    pub fn iter(&self) -> impl Iterator<Item = (AstType, usize)> + '_ {
+        AstType::ALL
+            .iter()
+            .map(|&ty| (ty, self.get(ty)))
+            .filter(|&(_, count)| count > 0)
+    }
+}
+
+/// Count how many nodes of each type are in AST.
+pub fn node_type_counts(program: &Program<'_>) -> NodeCounts {
+    let mut counter = NodeCounter([0; AstType::COUNT]);
+    counter.visit_program(program);
+    NodeCounts(counter.0)
+}
+
+struct NodeCounter([usize; AstType::COUNT]);
+
+impl NodeCounter {
+    #[inline]
+    fn count(&mut self, ty: AstType) {
+        self.0[ty as usize] += 1;
+    }
+}
+
+impl<'a> Visit<'a> for NodeCounter {
+    fn visit_program(&mut self, program: &Program<'a>) {
+        self.count(AstType::Program);
+        self.walk_program(program);
+    }
+
+    fn visit_expression_statement(&mut self, expr_stmt: &ExpressionStatement<'a>) {
+        self.count(AstType::ExpressionStatement);
+        self.walk_expression_statement(expr_stmt);
+    }
+
+    fn visit_block_statement(&mut self, block: &BlockStatement<'a>) {
+        self.count(AstType::BlockStatement);
+        self.walk_block_statement(block);
+    }
+
+    fn visit_if_statement(&mut self, if_stmt: &IfStatement<'a>) {
+        self.count(AstType::IfStatement);
+        self.walk_if_statement(if_stmt);
+    }
+
+    fn visit_return_statement(&mut self, return_stmt: &ReturnStatement<'a>) {
+        self.count(AstType::ReturnStatement);
+        self.walk_return_statement(return_stmt);
+    }
+
+    fn visit_variable_declaration(&mut self, var_decl: &VariableDeclaration<'a>) {
+        self.count(AstType::VariableDeclaration);
+        self.walk_variable_declaration(var_decl);
+    }
+
+    fn visit_variable_declarator(&mut self, declarator: &VariableDeclarator<'a>) {
+        self.count(AstType::VariableDeclarator);
+        self.walk_variable_declarator(declarator);
+    }
+
+    fn visit_identifier_reference(&mut self, _id: &IdentifierReference<'a>) {
+        self.count(AstType::IdentifierReference);
+    }
+
+    fn visit_string_literal(&mut self, _str_lit: &StringLiteral<'a>) {
+        self.count(AstType::StringLiteral);
+    }
+
+    fn visit_numeric_literal(&mut self, _num_lit: &NumericLiteral<'a>) {
+        self.count(AstType::NumericLiteral);
+    }
+
+    fn visit_boolean_literal(&mut self, _bool_lit: &BooleanLiteral<'a>) {
+        self.count(AstType::BooleanLiteral);
+    }
+
+    fn visit_null_literal(&mut self, _null_lit: &NullLiteral<'a>) {
+        self.count(AstType::NullLiteral);
+    }
+
+    fn visit_binary_expression(&mut self, bin_expr: &BinaryExpression<'a>) {
+        self.count(AstType::BinaryExpression);
+        self.walk_binary_expression(bin_expr);
+    }
+
+    fn visit_unary_expression(&mut self, unary_expr: &UnaryExpression<'a>) {
+        self.count(AstType::UnaryExpression);
+        self.walk_unary_expression(unary_expr);
+    }
+
+    fn visit_call_expression(&mut self, call_expr: &CallExpression<'a>) {
+        self.count(AstType::CallExpression);
+        self.walk_call_expression(call_expr);
+    }
+
+    fn visit_member_expression(&mut self, member_expr: &MemberExpression<'a>) {
+        self.count(AstType::MemberExpression);
+        self.walk_member_expression(member_expr);
+    }
+
+    fn visit_logical_expression(&mut self, logical_expr: &LogicalExpression<'a>) {
+        self.count(AstType::LogicalExpression);
+        self.walk_logical_expression(logical_expr);
+    }
+
+    fn visit_conditional_expression(&mut self, cond_expr: &ConditionalExpression<'a>) {
+        self.count(AstType::ConditionalExpression);
+        self.walk_conditional_expression(cond_expr);
+    }
+
+    fn visit_assignment_expression(&mut self, assign_expr: &AssignmentExpression<'a>) {
+        self.count(AstType::AssignmentExpression);
+        self.walk_assignment_expression(assign_expr);
+    }
+
+    fn visit_template_literal(&mut self, template_lit: &TemplateLiteral<'a>) {
+        self.count(AstType::TemplateLiteral);
+        self.walk_template_literal(template_lit);
+    }
+
+    fn visit_array_expression(&mut self, array_expr: &ArrayExpression<'a>) {
+        self.count(AstType::ArrayExpression);
+        self.walk_array_expression(array_expr);
+    }
+
+    fn visit_object_expression(&mut self, object_expr: &ObjectExpression<'a>) {
+        self.count(AstType::ObjectExpression);
+        self.walk_object_expression(object_expr);
+    }
+
+    fn visit_property(&mut self, prop: &Property<'a>) {
+        self.count(AstType::Property);
+        self.walk_property(prop);
+    }
+}
+
+/// Number of bytes the arena has allocated from the system.
+///
+/// This is the total size of the arena's chunks, so includes space in the current chunk
+/// which has not been used yet.
+pub fn arena_bytes_allocated(alloc: &Allocator) -> usize {
+    alloc.allocated_bytes()
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::parser::parse_str;
+
+    #[test]
+    fn counts() {
+        let alloc = Allocator::default();
+        let program = parse_str("let x = 1; if (x) { f(x, 'a'); }", &alloc).unwrap();
+        let counts = node_type_counts(program);
+        assert_eq!(
+            counts.iter().collect::<Vec<_>>(),
+            [
+                (AstType::Program, 1),
+                (AstType::ExpressionStatement, 1),
+                (AstType::BlockStatement, 1),
+                (AstType::IfStatement, 1),
+                (AstType::VariableDeclaration, 1),
+                (AstType::VariableDeclarator, 1),
+                (AstType::IdentifierReference, 3),
+                (AstType::StringLiteral, 1),
+                (AstType::NumericLiteral, 1),
+                (AstType::CallExpression, 1),
+            ]
+        );
+        assert_eq!(counts.get(AstType::NullLiteral), 0);
+        assert_eq!(counts.total(), 12);
+    }
+}
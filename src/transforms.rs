@@ -0,0 +1,200 @@
+//! Example transforms, used by the demo in `main.rs`, and in benchmarks.
+
+use crate::{
+    ast::{
+        self,
+        traversable::{Expression, ExpressionParent, UnaryExpression},
+        BinaryOperator, BinarySlot, UnaryOperator,
+    },
+    builder::AstBuilder,
+    cell::{gcell, Token},
+    traverse::Traverse,
+    visit_mut::{VisitAction, VisitMut},
+};
+
+/// Transformer for `typeof x === 'y'` to `'y' === typeof x`
+pub struct TransformTypeof;
+
+impl<'a, 't> Traverse<'a, 't> for TransformTypeof {
+    fn exit_unary_expression(
+        &mut self,
+        unary_expr: &gcell!(UnaryExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+        if unary_expr.borrow(tk).operator == UnaryOperator::Typeof {
+            if let ExpressionParent::BinaryExpressionLeft(bin_expr) = unary_expr.borrow(tk).parent {
+                if matches!(
+                    bin_expr.borrow(tk).operator,
+                    BinaryOperator::Equality | BinaryOperator::StrictEquality
+                ) {
+                    if let Expression::StringLiteral(str_lit) = bin_expr.borrow(tk).right {
+                        // Swap left and right of binary expression.
+                        // `set_expression_child` updates parent links of both.
+                        let right = Expression::StringLiteral(str_lit);
+                        let left = bin_expr.set_expression_child(BinarySlot::Left, right, tk);
+                        bin_expr.set_expression_child(BinarySlot::Right, left, tk);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Transformer which folds unary expressions with a literal argument (e.g. `!true` -> `false`),
+/// and `==` / `===` comparisons between 2 string literals, into literals.
+pub struct TransformConstantFold<'a> {
+    pub ast: AstBuilder<'a>,
+}
+
+impl<'a> TransformConstantFold<'a> {
+    fn fold_unary(&self, unary_expr: &ast::UnaryExpression<'a>) -> Option<ast::Expression<'a>> {
+        use ast::Expression as E;
+        let span = unary_expr.span;
+        let folded = match (unary_expr.operator, &unary_expr.argument) {
+            (UnaryOperator::LogicalNot, E::BooleanLiteral(bool_lit)) => {
+                self.ast.boolean_literal(span, !bool_lit.value)
+            }
+            (UnaryOperator::LogicalNot, E::NumericLiteral(num_lit)) => self
+                .ast
+                .boolean_literal(span, num_lit.value == 0.0 || num_lit.value.is_nan()),
+            (UnaryOperator::LogicalNot, E::StringLiteral(str_lit)) => {
+                self.ast.boolean_literal(span, str_lit.value.is_empty())
+            }
+            (UnaryOperator::LogicalNot, E::NullLiteral(_)) => self.ast.boolean_literal(span, true),
+            (UnaryOperator::UnaryNegation, E::NumericLiteral(num_lit)) => {
+                self.ast.numeric_literal(span, -num_lit.value)
+            }
+            (UnaryOperator::UnaryPlus, E::NumericLiteral(num_lit)) => {
+                self.ast.numeric_literal(span, num_lit.value)
+            }
+            (UnaryOperator::Typeof, E::BooleanLiteral(_)) => {
+                self.ast.string_literal(span, "boolean", None)
+            }
+            (UnaryOperator::Typeof, E::NumericLiteral(_)) => {
+                self.ast.string_literal(span, "number", None)
+            }
+            (UnaryOperator::Typeof, E::StringLiteral(_)) => {
+                self.ast.string_literal(span, "string", None)
+            }
+            (UnaryOperator::Typeof, E::NullLiteral(_)) => {
+                self.ast.string_literal(span, "object", None)
+            }
+            _ => return None,
+        };
+        Some(folded)
+    }
+
+    fn fold_binary(&self, bin_expr: &ast::BinaryExpression<'a>) -> Option<ast::Expression<'a>> {
+        let negate = match bin_expr.operator {
+            BinaryOperator::Equality | BinaryOperator::StrictEquality => false,
+            BinaryOperator::Inequality | BinaryOperator::StrictInequality => true,
+            _ => return None,
+        };
+        match (&bin_expr.left, &bin_expr.right) {
+            (ast::Expression::StringLiteral(left), ast::Expression::StringLiteral(right)) => {
+                // `==` and `===` are equivalent when both sides are strings
+                Some(
+                    self.ast
+                        .boolean_literal(bin_expr.span, (left.value == right.value) != negate),
+                )
+            }
+            _ => None,
+        }
+    }
+}
+
+impl<'a> VisitMut<'a> for TransformConstantFold<'a> {
+    fn visit_expression(&mut self, expr: &mut ast::Expression<'a>) -> VisitAction<'a> {
+        // Fold children first, so e.g. `!!true` folds completely
+        self.walk_expression(expr);
+        let folded = match expr {
+            ast::Expression::UnaryExpression(unary_expr) => self.fold_unary(unary_expr),
+            ast::Expression::BinaryExpression(bin_expr) => self.fold_binary(bin_expr),
+            _ => None,
+        };
+        match folded {
+            Some(folded) => VisitAction::Replace(folded),
+            None => VisitAction::Keep,
+        }
+    }
+}
+
+/// Transformer which converts loose equality `==` to strict equality `===`.
+///
+/// Only the operator changes, so no nodes are moved and parent links stay valid.
+pub struct TransformStrictEquality;
+
+impl<'a> VisitMut<'a> for TransformStrictEquality {
+    fn visit_binary_expression(&mut self, bin_expr: &mut ast::BinaryExpression<'a>) {
+        if bin_expr.operator == BinaryOperator::Equality {
+            bin_expr.operator = BinaryOperator::StrictEquality;
+        }
+        self.walk_binary_expression(bin_expr);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use oxc_allocator::Allocator;
+
+    use super::*;
+    use crate::{dump::dump, parser, print::Printer};
+
+    fn fold(source: &str) -> String {
+        let alloc = Allocator::default();
+        let program = parser::parse_str(source, &alloc).unwrap();
+        TransformConstantFold {
+            ast: AstBuilder::new(&alloc),
+        }
+        .visit_program(program);
+        Printer::print(program)
+    }
+
+    #[test]
+    fn strict_equality() {
+        let alloc = Allocator::default();
+        let program = parser::parse_str("typeof foo == 'object'; a != b;", &alloc).unwrap();
+        TransformStrictEquality.visit_program(program);
+        assert_eq!(Printer::print(program), "typeof foo === 'object';a != b;");
+    }
+
+    #[test]
+    fn constant_fold() {
+        assert_eq!(
+            fold("!true; -5; !!'x'; 'a' === 'b'; typeof null;"),
+            "false;-5;true;false;'object';"
+        );
+    }
+
+    #[test]
+    fn strict_equality_snapshot() {
+        let alloc = Allocator::default();
+        let program = parser::parse_str("typeof foo == 'object'; a != b;", &alloc).unwrap();
+        TransformStrictEquality.visit_program(program);
+        insta::assert_snapshot!("strict_equality_print", Printer::print(program));
+        insta::assert_snapshot!("strict_equality_dump", dump(program));
+    }
+
+    #[test]
+    fn constant_fold_snapshot() {
+        let alloc = Allocator::default();
+        let program =
+            parser::parse_str("!true; -5; !!'x'; 'a' === 'b'; typeof null;", &alloc).unwrap();
+        TransformConstantFold {
+            ast: AstBuilder::new(&alloc),
+        }
+        .visit_program(program);
+        insta::assert_snapshot!("constant_fold_print", Printer::print(program));
+        insta::assert_snapshot!("constant_fold_dump", dump(program));
+    }
+
+    #[test]
+    fn constant_fold_negative_exponent_base() {
+        assert_eq!(fold("(-5) ** 2;"), "(-5) ** 2;");
+    }
+
+    #[test]
+    fn constant_fold_negative_member_object() {
+        assert_eq!(fold("(-5).x;"), "(-5).x;");
+    }
+}
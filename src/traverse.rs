@@ -1,13 +1,50 @@
+#[cfg(feature = "std")]
+use oxc_allocator::Allocator;
+
 use crate::{
     ast::{
         traversable::{
-            BinaryExpression, Expression, ExpressionStatement, IdentifierReference,
-            Program as TraversableProgram, Statement, StringLiteral, UnaryExpression,
+            ArrayExpression, AssignmentExpression, BinaryExpression, BlockStatement,
+            BooleanLiteral, CallExpression, ConditionalExpression, Expression, ExpressionStatement,
+            IdentifierReference, IfStatement, LogicalExpression, MemberExpression, NullLiteral,
+            NumericLiteral, ObjectExpression, Program as TraversableProgram, Property,
+            ReturnStatement, Statement, StatementParent, StringLiteral, TemplateLiteral,
+            UnaryExpression, VariableDeclaration, VariableDeclarator,
         },
         Program,
     },
     cell::{gcell, GCell, Token},
 };
+#[cfg(feature = "std")]
+use crate::{
+    parents::fix_parents,
+    parser::{parse_str, ParseError},
+};
+
+/// Options for `transform_with`.
+#[cfg(feature = "std")]
+#[derive(Clone, Copy, Default, Debug)]
+pub struct TransformOptions {
+    /// After the transform, rewrite parent links of all nodes top-down from the `Program`
+    /// (see `fix_parents`). Saves the transformer from keeping parent links up to date itself,
+    /// if it only needs them to be correct once the transform has finished.
+    pub fixup_parents: bool,
+}
+
+/// Run transform visitor on AST, with `options`. See `transform`.
+#[cfg(feature = "std")]
+pub fn transform_with<'a, T>(
+    transformer: &mut T,
+    program: &mut Program<'a>,
+    options: TransformOptions,
+) where
+    T: for<'t> Traverse<'a, 't>,
+{
+    transform(transformer, program);
+    if options.fixup_parents {
+        fix_parents(program);
+    }
+}
 
 /// Run transform visitor on AST.
 ///
@@ -15,55 +52,312 @@ use crate::{
 /// with interior mutability, allowing traversal in any direction (up or down).
 /// Once the transform is finished, caller can continue to use the standard version of the AST
 /// in the usual way, without interior mutability.
-pub fn transform<'a, 't, T>(transformer: &mut T, program: &mut Program<'a>)
+///
+/// Each call to `transform` uses a token with a new brand `'t`, chosen by the compiler, so the
+/// transformer must implement `Traverse<'a, 't>` for any `'t`. The token passed to `Traverse`
+/// methods cannot escape `transform`. A transformer which tries to store the token, or a borrow
+/// of a node obtained with it, fails to compile (more cases are in `tests/ui`):
+/// ```compile_fail
+/// # use ast_cell_test::{
+/// #     ast::traversable::IdentifierReference,
+/// #     cell::{GCell, Token},
+/// #     traverse::Traverse,
+/// # };
+/// struct Escape<'b, 't>(Option<&'b mut Token<'t>>);
+///
+/// impl<'a, 'b, 't> Traverse<'a, 't> for Escape<'b, 't> {
+///     fn exit_identifier_reference(
+///         &mut self,
+///         id: &GCell<'t, IdentifierReference<'a, 't>>,
+///         tk: &mut Token<'t>,
+///     ) {
+///         self.0 = Some(tk); // error: lifetime may not live long enough
+///     }
+/// }
+/// ```
+/// ```compile_fail
+/// # use ast_cell_test::{
+/// #     ast::traversable::IdentifierReference,
+/// #     cell::{GCell, Token},
+/// #     traverse::Traverse,
+/// # };
+/// struct Keep<'a, 'b, 't>(Option<&'b IdentifierReference<'a, 't>>);
+///
+/// impl<'a, 'b, 't> Traverse<'a, 't> for Keep<'a, 'b, 't> {
+///     fn exit_identifier_reference(
+///         &mut self,
+///         id: &GCell<'t, IdentifierReference<'a, 't>>,
+///         tk: &mut Token<'t>,
+///     ) {
+///         self.0 = Some(id.borrow(tk)); // error: lifetime may not live long enough
+///     }
+/// }
+/// ```
+pub fn transform<'a, T>(transformer: &mut T, program: &mut Program<'a>)
 where
-    't: 'a,
-    T: Traverse<'a, 't>,
+    T: for<'t> Traverse<'a, 't>,
 {
-    // Generate `GhostToken` which transformer uses to access the AST.
-    // SAFETY: We only create one token, and it never leaves this function.
-    let mut token = unsafe { Token::new_unchecked() };
+    with_traversable_program(program, |program, mut token| {
+        // Run transformer on the traversable AST
+        Traverse::visit_program(transformer, program, &mut token);
+    });
+
+    // The access token goes out of scope at this point, which guarantees that no references
+    // (either mutable or immutable) to the traversable AST or the token still exist.
+    // If the transformer attempts to hold on to any references to the AST, or to the token,
+    // this will produce a compile-time error.
+    // Therefore, the caller can now safely continue using the `&mut Statement` that they passed in.
+}
 
+/// Convert `program` to the traversable AST, and call `f` with it and a token to access it.
+///
+/// `f` must accept any brand `'t`, so inside `f` the brand is unique to this call, the same as
+/// with `GhostToken::new`. No other token can access the traversable AST, and neither the AST
+/// nor the token can escape `f`.
+///
+/// `GhostToken::new` can't be used here, as nodes hold `&'a GCell<'t, _>`, which requires
+/// `'t: 'a`. A closure which is generic over `'t` can only rely on that if it's implied by
+/// the closure's parameter types, as it is here.
+fn with_traversable_program<'a, R>(
+    program: &mut Program<'a>,
+    f: impl for<'t> FnOnce(&gcell!(TraversableProgram<'a, 't>), Token<'t>) -> R,
+) -> R {
     // Convert AST to traversable version.
     // SAFETY: `Program` and `TraversableProgram` are mirrors of each other, with identical layouts.
     // The same is true of all child types - this is ensured by `#[repr(C)]` on all types.
     // Therefore one can safely be transmuted to the other.
     // As we hold a `&mut` reference, it's guaranteed there are no other live references.
-    let program = unsafe { &mut *(program as *mut Program<'a> as *mut TraversableProgram<'a, 't>) };
+    let program =
+        unsafe { &mut *(program as *mut Program<'a> as *mut TraversableProgram<'a, 'static>) };
     let program = GCell::from_mut(program);
+    // SAFETY: `program` is only used via shared refs from here on. The `Program` must live
+    // for `'a`, the same as is assumed for the parent links set by the parser.
+    let program: &'a gcell!(TraversableProgram<'a, 'static>) = unsafe { &*(program as *const _) };
 
-    // Run transformer on the traversable AST
-    Traverse::visit_program(transformer, program, &mut token);
+    // Generate token which `f` uses to access the AST.
+    // SAFETY: `f` is generic over the brand, so inside `f` this token's brand is distinct from
+    // every other token's. This is the only token `f` receives, and it cannot escape `f`.
+    let mut token = unsafe { Token::new_unchecked() };
 
-    // The access token goes out of scope at this point, which guarantees that no references
-    // (either mutable or immutable) to the traversable AST or the token still exist.
-    // If the transformer attempts to hold on to any references to the AST, or to the token,
-    // this will produce a compile-time error.
-    // Therefore, the caller can now safely continue using the `&mut Statement` that they passed in.
+    // Parent links of top-level statements were derived from a different pointer to the
+    // `Program` than `program`. Writing to the `Program` through them (e.g. in
+    // `Statement::insert_before`) is UB, and in release builds the change is not seen
+    // when reading through `program`. So point them at `program`.
+    for index in 0..program.borrow(&token).body.len() {
+        let stmt = program.borrow(&token).body[index].borrow(&token).clone();
+        stmt.set_parent(StatementParent::Program(program), &mut token);
+    }
+
+    f(program, token)
+}
+
+/// Run transform visitors on multiple ASTs in parallel, one thread per AST.
+///
+/// `transformers[i]` is run on `programs[i]`. Each thread runs `transform`, which creates
+/// its own `Token` for the AST it operates on.
+///
+/// # Panics
+/// Panics if `transformers` and `programs` have different lengths,
+/// or if any transformer panics.
+///
+/// # SAFETY
+/// Each AST must be allocated in a different `Allocator`, and those allocators must not be
+/// used by any other code (including other threads) until this function returns.
+///
+/// Rationale:
+///
+/// Each thread calls `transform`, which creates a token with a fresh brand, so every AST is
+/// accessed with a token of a different brand. A `GCell` from one AST cannot be accessed with
+/// another thread's token, as their brands differ. Because `T` must implement `Traverse<'a, 't>`
+/// for any `'t`, a transformer cannot hold references to nodes of any particular brand either,
+/// so cannot smuggle nodes between threads (see `tests/ui/share_node_between_threads.rs`).
+///
+/// However, AST nodes are not `Send`, because `Vec`s in the AST hold a reference to the `Allocator`
+/// they were allocated in, which they use to grow, and `Allocator` is not `Sync`. If 2 ASTs shared
+/// an allocator, transformers on 2 threads pushing to `Vec`s at the same time would be a data race.
+/// It's only sound to send an AST to another thread if no other thread can access its allocator.
+#[cfg(feature = "std")]
+pub unsafe fn transform_many<'a, T>(transformers: Vec<&mut T>, programs: &mut [&mut Program<'a>])
+where
+    T: for<'t> Traverse<'a, 't> + Send,
+{
+    assert_eq!(
+        transformers.len(),
+        programs.len(),
+        "Must provide one transformer per program"
+    );
+
+    std::thread::scope(|scope| {
+        for (transformer, program) in transformers.into_iter().zip(programs.iter_mut()) {
+            // SAFETY: Caller guarantees each program has its own allocator, which is not used
+            // by any other thread. See above.
+            let program = unsafe { AssertSend::new(&mut **program) };
+            scope.spawn(move || transform(transformer, program.into_inner()));
+        }
+    });
+}
+
+/// Wrapper to send an AST to another thread. Only used by `transform_many`.
+#[cfg(feature = "std")]
+struct AssertSend<T>(T);
+
+#[cfg(feature = "std")]
+impl<T> AssertSend<T> {
+    /// # SAFETY
+    /// Caller must ensure it's sound to access `value` from another thread.
+    unsafe fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Unwrap value.
+    /// Using a method rather than `.0` makes closures capture the whole wrapper, not just the
+    /// non-`Send` field.
+    fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[cfg(feature = "std")]
+// SAFETY: `AssertSend::new` is unsafe, and caller must ensure sending the value is sound
+unsafe impl<T> Send for AssertSend<T> {}
+
+/// Set of ASTs, each in its own `Allocator`, which can be transformed in parallel.
+///
+/// A safe wrapper around `transform_many`. Each AST is parsed into an allocator which the pool
+/// borrows exclusively for its whole lifetime, so the requirement that no two ASTs share
+/// an allocator, and that no other code uses the allocators, is checked by the compiler.
+#[cfg(feature = "std")]
+#[derive(Default)]
+pub struct ParallelTransformPool<'a> {
+    programs: Vec<&'a mut Program<'a>>,
+}
+
+#[cfg(feature = "std")]
+impl<'a> ParallelTransformPool<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parse `source` into `alloc`, and add the AST to the pool.
+    pub fn parse(&mut self, source: &'a str, alloc: &'a mut Allocator) -> Result<(), ParseError> {
+        let program = parse_str(source, alloc)?;
+        self.programs.push(program);
+        Ok(())
+    }
+
+    /// Number of ASTs in the pool.
+    pub fn len(&self) -> usize {
+        self.programs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.programs.is_empty()
+    }
+
+    /// Run `transformers[i]` on the `i`th AST added to the pool, all in parallel,
+    /// one thread per AST.
+    ///
+    /// # Panics
+    /// Panics if number of transformers is not the same as number of ASTs,
+    /// or if any transformer panics.
+    pub fn transform<T>(&mut self, transformers: Vec<&mut T>)
+    where
+        T: for<'t> Traverse<'a, 't> + Send,
+    {
+        // SAFETY: Each program was parsed into a different allocator, which the pool holds
+        // an exclusive borrow of, so no other code can access it
+        unsafe { transform_many(transformers, &mut self.programs) };
+    }
+
+    /// Get the ASTs, in the order they were added.
+    pub fn programs(&self) -> impl Iterator<Item = &Program<'a>> {
+        self.programs.iter().map(|program| &**program)
+    }
+
+    /// Consume the pool, and return the ASTs in the order they were added.
+    pub fn into_programs(self) -> Vec<&'a mut Program<'a>> {
+        self.programs
+    }
+}
+
+/// Signal returned from `enter_*` and `visit_*` methods to control traversal.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum WalkControl {
+    /// Walk the node's children as usual.
+    Continue,
+    /// Do not walk into the node's children. `exit_*` is still called for the node.
+    Skip,
 }
 
+/// Transform visitor for the traversable AST.
+///
+/// Each node type has a `visit_*` method which by default calls `enter_*`, then `walk_*`
+/// to visit the node's children, then `exit_*`.
+/// `enter_*` and `exit_*` do nothing by default. Override `exit_*` to rewrite a node
+/// after its children have already been transformed.
+///
+/// Returning `WalkControl::Skip` from an `enter_*` method prunes the node's subtree.
+/// `visit_*` methods return the `WalkControl` which was applied to the node.
+///
+/// Statements can be added to or removed from the statement list currently being walked
+/// with `Statement::insert_before`, `insert_after` and `remove`. An expression can be swapped
+/// for another in its parent with `Expression::replace_with`.
 pub trait Traverse<'a, 't> {
-    fn visit_program(&mut self, program: &gcell!(TraversableProgram<'a, 't>), tk: &mut Token<'t>) {
-        self.walk_program(program, tk)
+    /// Return `true` to visit children of binary expressions right-to-left.
+    fn reverse(&self) -> bool {
+        false
+    }
+
+    fn visit_program(
+        &mut self,
+        program: &gcell!(TraversableProgram<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_program(program, tk);
+        if control == WalkControl::Continue {
+            self.walk_program(program, tk);
+        }
+        self.exit_program(program, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_program(
+        &mut self,
+        program: &gcell!(TraversableProgram<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
     }
 
+    #[allow(unused_variables)]
+    fn exit_program(&mut self, program: &gcell!(TraversableProgram<'a, 't>), tk: &mut Token<'t>) {}
+
     fn walk_program(&mut self, program: &gcell!(TraversableProgram<'a, 't>), tk: &mut Token<'t>) {
-        let len = program.borrow(tk).body.len();
-        for index in 0..len {
-            let stmt = program.borrow(tk).body.as_slice()[index].borrow(tk).clone();
+        // Statements may be inserted or removed while visiting, so re-read the list each time
+        let mut index = 0;
+        while let Some(stmt) = program.borrow(tk).body.get(index) {
+            let stmt = stmt.borrow(tk).clone();
             self.visit_statement(&stmt, tk);
+            index = next_statement_index(&program.borrow(tk).body, &stmt, index, tk);
         }
     }
 
-    fn visit_statement(&mut self, stmt: &Statement<'a, 't>, tk: &mut Token<'t>) {
+    fn visit_statement(&mut self, stmt: &Statement<'a, 't>, tk: &mut Token<'t>) -> WalkControl {
         self.walk_statement(stmt, tk)
     }
 
-    fn walk_statement(&mut self, stmt: &Statement<'a, 't>, tk: &mut Token<'t>) {
+    fn walk_statement(&mut self, stmt: &Statement<'a, 't>, tk: &mut Token<'t>) -> WalkControl {
         match stmt {
             Statement::ExpressionStatement(expr_stmt) => {
                 self.visit_expression_statement(expr_stmt, tk)
-            } // _ => {} // No other variants at present
+            }
+            Statement::BlockStatement(block) => self.visit_block_statement(block, tk),
+            Statement::IfStatement(if_stmt) => self.visit_if_statement(if_stmt, tk),
+            Statement::ReturnStatement(return_stmt) => self.visit_return_statement(return_stmt, tk),
+            Statement::VariableDeclaration(var_decl) => {
+                self.visit_variable_declaration(var_decl, tk)
+            }
         }
     }
 
@@ -71,8 +365,30 @@ pub trait Traverse<'a, 't> {
         &mut self,
         expr_stmt: &gcell!(ExpressionStatement<'a, 't>),
         tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_expression_statement(expr_stmt, tk);
+        if control == WalkControl::Continue {
+            self.walk_expression_statement(expr_stmt, tk);
+        }
+        self.exit_expression_statement(expr_stmt, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_expression_statement(
+        &mut self,
+        expr_stmt: &gcell!(ExpressionStatement<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_expression_statement(
+        &mut self,
+        expr_stmt: &gcell!(ExpressionStatement<'a, 't>),
+        tk: &mut Token<'t>,
     ) {
-        self.walk_expression_statement(expr_stmt, tk);
     }
 
     fn walk_expression_statement(
@@ -83,49 +399,196 @@ pub trait Traverse<'a, 't> {
         self.visit_expression(&expr_stmt.borrow(tk).expression.clone(), tk);
     }
 
-    fn visit_expression(&mut self, expr: &Expression<'a, 't>, tk: &mut Token<'t>) {
-        self.walk_expression(expr, tk);
+    fn visit_expression(&mut self, expr: &Expression<'a, 't>, tk: &mut Token<'t>) -> WalkControl {
+        self.walk_expression(expr, tk)
     }
 
-    fn walk_expression(&mut self, expr: &Expression<'a, 't>, tk: &mut Token<'t>) {
+    fn walk_expression(&mut self, expr: &Expression<'a, 't>, tk: &mut Token<'t>) -> WalkControl {
         match expr {
-            Expression::Identifier(id) => {
-                self.visit_identifier_reference(id, tk);
+            Expression::Identifier(id) => self.visit_identifier_reference(id, tk),
+            Expression::StringLiteral(str_lit) => self.visit_string_literal(str_lit, tk),
+            Expression::NumericLiteral(num_lit) => self.visit_numeric_literal(num_lit, tk),
+            Expression::BooleanLiteral(bool_lit) => self.visit_boolean_literal(bool_lit, tk),
+            Expression::NullLiteral(null_lit) => self.visit_null_literal(null_lit, tk),
+            Expression::BinaryExpression(bin_expr) => self.visit_binary_expression(bin_expr, tk),
+            Expression::UnaryExpression(unary_expr) => self.visit_unary_expression(unary_expr, tk),
+            Expression::CallExpression(call_expr) => self.visit_call_expression(call_expr, tk),
+            Expression::MemberExpression(member_expr) => {
+                self.visit_member_expression(member_expr, tk)
             }
-            Expression::StringLiteral(str_lit) => {
-                self.visit_string_literal(str_lit, tk);
+            Expression::LogicalExpression(logical_expr) => {
+                self.visit_logical_expression(logical_expr, tk)
             }
-            Expression::BinaryExpression(bin_expr) => {
-                self.visit_binary_expression(bin_expr, tk);
+            Expression::ConditionalExpression(cond_expr) => {
+                self.visit_conditional_expression(cond_expr, tk)
             }
-            Expression::UnaryExpression(unary_expr) => {
-                self.visit_unary_expression(unary_expr, tk);
+            Expression::AssignmentExpression(assign_expr) => {
+                self.visit_assignment_expression(assign_expr, tk)
+            }
+            Expression::TemplateLiteral(template_lit) => {
+                self.visit_template_literal(template_lit, tk)
+            }
+            Expression::ArrayExpression(array_expr) => self.visit_array_expression(array_expr, tk),
+            Expression::ObjectExpression(object_expr) => {
+                self.visit_object_expression(object_expr, tk)
             }
         }
     }
 
-    #[allow(unused_variables)]
     fn visit_identifier_reference(
         &mut self,
         id: &gcell!(IdentifierReference<'a, 't>),
         tk: &mut Token<'t>,
-    ) {
+    ) -> WalkControl {
+        let control = self.enter_identifier_reference(id, tk);
+        self.exit_identifier_reference(id, tk);
+        control
     }
 
     #[allow(unused_variables)]
+    fn enter_identifier_reference(
+        &mut self,
+        id: &gcell!(IdentifierReference<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_identifier_reference(
+        &mut self,
+        id: &gcell!(IdentifierReference<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+    }
+
     fn visit_string_literal(
         &mut self,
         str_lit: &gcell!(StringLiteral<'a, 't>),
         tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_string_literal(str_lit, tk);
+        self.exit_string_literal(str_lit, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_string_literal(
+        &mut self,
+        str_lit: &gcell!(StringLiteral<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_string_literal(&mut self, str_lit: &gcell!(StringLiteral<'a, 't>), tk: &mut Token<'t>) {
+    }
+
+    fn visit_numeric_literal(
+        &mut self,
+        num_lit: &gcell!(NumericLiteral<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_numeric_literal(num_lit, tk);
+        self.exit_numeric_literal(num_lit, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_numeric_literal(
+        &mut self,
+        num_lit: &gcell!(NumericLiteral<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_numeric_literal(
+        &mut self,
+        num_lit: &gcell!(NumericLiteral<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+    }
+
+    fn visit_boolean_literal(
+        &mut self,
+        bool_lit: &gcell!(BooleanLiteral<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_boolean_literal(bool_lit, tk);
+        self.exit_boolean_literal(bool_lit, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_boolean_literal(
+        &mut self,
+        bool_lit: &gcell!(BooleanLiteral<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_boolean_literal(
+        &mut self,
+        bool_lit: &gcell!(BooleanLiteral<'a, 't>),
+        tk: &mut Token<'t>,
     ) {
     }
 
+    fn visit_null_literal(
+        &mut self,
+        null_lit: &gcell!(NullLiteral<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_null_literal(null_lit, tk);
+        self.exit_null_literal(null_lit, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_null_literal(
+        &mut self,
+        null_lit: &gcell!(NullLiteral<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_null_literal(&mut self, null_lit: &gcell!(NullLiteral<'a, 't>), tk: &mut Token<'t>) {}
+
     fn visit_binary_expression(
         &mut self,
         bin_expr: &gcell!(BinaryExpression<'a, 't>),
         tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_binary_expression(bin_expr, tk);
+        if control == WalkControl::Continue {
+            self.walk_binary_expression(bin_expr, tk);
+        }
+        self.exit_binary_expression(bin_expr, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_binary_expression(
+        &mut self,
+        bin_expr: &gcell!(BinaryExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_binary_expression(
+        &mut self,
+        bin_expr: &gcell!(BinaryExpression<'a, 't>),
+        tk: &mut Token<'t>,
     ) {
-        self.walk_binary_expression(bin_expr, tk);
     }
 
     fn walk_binary_expression(
@@ -133,16 +596,43 @@ pub trait Traverse<'a, 't> {
         bin_expr: &gcell!(BinaryExpression<'a, 't>),
         tk: &mut Token<'t>,
     ) {
-        self.visit_expression(&bin_expr.borrow(tk).left.clone(), tk);
-        self.visit_expression(&bin_expr.borrow(tk).right.clone(), tk);
+        if self.reverse() {
+            self.visit_expression(&bin_expr.borrow(tk).right.clone(), tk);
+            self.visit_expression(&bin_expr.borrow(tk).left.clone(), tk);
+        } else {
+            self.visit_expression(&bin_expr.borrow(tk).left.clone(), tk);
+            self.visit_expression(&bin_expr.borrow(tk).right.clone(), tk);
+        }
     }
 
     fn visit_unary_expression(
         &mut self,
         unary_expr: &gcell!(UnaryExpression<'a, 't>),
         tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_unary_expression(unary_expr, tk);
+        if control == WalkControl::Continue {
+            self.walk_unary_expression(unary_expr, tk);
+        }
+        self.exit_unary_expression(unary_expr, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_unary_expression(
+        &mut self,
+        unary_expr: &gcell!(UnaryExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_unary_expression(
+        &mut self,
+        unary_expr: &gcell!(UnaryExpression<'a, 't>),
+        tk: &mut Token<'t>,
     ) {
-        self.walk_unary_expression(unary_expr, tk);
     }
 
     fn walk_unary_expression(
@@ -152,4 +642,742 @@ pub trait Traverse<'a, 't> {
     ) {
         self.visit_expression(&unary_expr.borrow(tk).argument.clone(), tk);
     }
+
+    fn visit_call_expression(
+        &mut self,
+        call_expr: &gcell!(CallExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_call_expression(call_expr, tk);
+        if control == WalkControl::Continue {
+            self.walk_call_expression(call_expr, tk);
+        }
+        self.exit_call_expression(call_expr, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_call_expression(
+        &mut self,
+        call_expr: &gcell!(CallExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_call_expression(
+        &mut self,
+        call_expr: &gcell!(CallExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+    }
+
+    fn walk_call_expression(
+        &mut self,
+        call_expr: &gcell!(CallExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+        self.visit_expression(&call_expr.borrow(tk).callee.clone(), tk);
+        let len = call_expr.borrow(tk).arguments.len();
+        for index in 0..len {
+            let arg = call_expr.borrow(tk).arguments.as_slice()[index]
+                .borrow(tk)
+                .clone();
+            self.visit_expression(&arg, tk);
+        }
+    }
+
+    fn visit_member_expression(
+        &mut self,
+        member_expr: &gcell!(MemberExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_member_expression(member_expr, tk);
+        if control == WalkControl::Continue {
+            self.walk_member_expression(member_expr, tk);
+        }
+        self.exit_member_expression(member_expr, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_member_expression(
+        &mut self,
+        member_expr: &gcell!(MemberExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_member_expression(
+        &mut self,
+        member_expr: &gcell!(MemberExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+    }
+
+    fn walk_member_expression(
+        &mut self,
+        member_expr: &gcell!(MemberExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+        self.visit_expression(&member_expr.borrow(tk).object.clone(), tk);
+        self.visit_expression(&member_expr.borrow(tk).property.clone(), tk);
+    }
+
+    fn visit_logical_expression(
+        &mut self,
+        logical_expr: &gcell!(LogicalExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_logical_expression(logical_expr, tk);
+        if control == WalkControl::Continue {
+            self.walk_logical_expression(logical_expr, tk);
+        }
+        self.exit_logical_expression(logical_expr, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_logical_expression(
+        &mut self,
+        logical_expr: &gcell!(LogicalExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_logical_expression(
+        &mut self,
+        logical_expr: &gcell!(LogicalExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+    }
+
+    fn walk_logical_expression(
+        &mut self,
+        logical_expr: &gcell!(LogicalExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+        self.visit_expression(&logical_expr.borrow(tk).left.clone(), tk);
+        self.visit_expression(&logical_expr.borrow(tk).right.clone(), tk);
+    }
+
+    fn visit_conditional_expression(
+        &mut self,
+        cond_expr: &gcell!(ConditionalExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_conditional_expression(cond_expr, tk);
+        if control == WalkControl::Continue {
+            self.walk_conditional_expression(cond_expr, tk);
+        }
+        self.exit_conditional_expression(cond_expr, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_conditional_expression(
+        &mut self,
+        cond_expr: &gcell!(ConditionalExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_conditional_expression(
+        &mut self,
+        cond_expr: &gcell!(ConditionalExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+    }
+
+    fn walk_conditional_expression(
+        &mut self,
+        cond_expr: &gcell!(ConditionalExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+        self.visit_expression(&cond_expr.borrow(tk).test.clone(), tk);
+        self.visit_expression(&cond_expr.borrow(tk).consequent.clone(), tk);
+        self.visit_expression(&cond_expr.borrow(tk).alternate.clone(), tk);
+    }
+
+    fn visit_assignment_expression(
+        &mut self,
+        assign_expr: &gcell!(AssignmentExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_assignment_expression(assign_expr, tk);
+        if control == WalkControl::Continue {
+            self.walk_assignment_expression(assign_expr, tk);
+        }
+        self.exit_assignment_expression(assign_expr, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_assignment_expression(
+        &mut self,
+        assign_expr: &gcell!(AssignmentExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_assignment_expression(
+        &mut self,
+        assign_expr: &gcell!(AssignmentExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+    }
+
+    fn walk_assignment_expression(
+        &mut self,
+        assign_expr: &gcell!(AssignmentExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+        self.visit_expression(&assign_expr.borrow(tk).target.clone(), tk);
+        self.visit_expression(&assign_expr.borrow(tk).value.clone(), tk);
+    }
+
+    fn visit_template_literal(
+        &mut self,
+        template_lit: &gcell!(TemplateLiteral<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_template_literal(template_lit, tk);
+        if control == WalkControl::Continue {
+            self.walk_template_literal(template_lit, tk);
+        }
+        self.exit_template_literal(template_lit, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_template_literal(
+        &mut self,
+        template_lit: &gcell!(TemplateLiteral<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_template_literal(
+        &mut self,
+        template_lit: &gcell!(TemplateLiteral<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+    }
+
+    fn walk_template_literal(
+        &mut self,
+        template_lit: &gcell!(TemplateLiteral<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+        let len = template_lit.borrow(tk).expressions.len();
+        for index in 0..len {
+            let expr = template_lit.borrow(tk).expressions.as_slice()[index]
+                .borrow(tk)
+                .clone();
+            self.visit_expression(&expr, tk);
+        }
+    }
+
+    fn visit_array_expression(
+        &mut self,
+        array_expr: &gcell!(ArrayExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_array_expression(array_expr, tk);
+        if control == WalkControl::Continue {
+            self.walk_array_expression(array_expr, tk);
+        }
+        self.exit_array_expression(array_expr, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_array_expression(
+        &mut self,
+        array_expr: &gcell!(ArrayExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_array_expression(
+        &mut self,
+        array_expr: &gcell!(ArrayExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+    }
+
+    fn walk_array_expression(
+        &mut self,
+        array_expr: &gcell!(ArrayExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+        let len = array_expr.borrow(tk).elements.len();
+        for index in 0..len {
+            // Skip holes
+            let element = array_expr.borrow(tk).elements.as_slice()[index]
+                .borrow(tk)
+                .clone();
+            if let Some(element) = element {
+                self.visit_expression(&element, tk);
+            }
+        }
+    }
+
+    fn visit_object_expression(
+        &mut self,
+        object_expr: &gcell!(ObjectExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_object_expression(object_expr, tk);
+        if control == WalkControl::Continue {
+            self.walk_object_expression(object_expr, tk);
+        }
+        self.exit_object_expression(object_expr, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_object_expression(
+        &mut self,
+        object_expr: &gcell!(ObjectExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_object_expression(
+        &mut self,
+        object_expr: &gcell!(ObjectExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+    }
+
+    fn walk_object_expression(
+        &mut self,
+        object_expr: &gcell!(ObjectExpression<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+        let len = object_expr.borrow(tk).properties.len();
+        for index in 0..len {
+            let prop = *object_expr.borrow(tk).properties.as_slice()[index].borrow(tk);
+            self.visit_property(prop, tk);
+        }
+    }
+
+    fn visit_property(
+        &mut self,
+        prop: &gcell!(Property<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_property(prop, tk);
+        if control == WalkControl::Continue {
+            self.walk_property(prop, tk);
+        }
+        self.exit_property(prop, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_property(
+        &mut self,
+        prop: &gcell!(Property<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_property(&mut self, prop: &gcell!(Property<'a, 't>), tk: &mut Token<'t>) {}
+
+    fn walk_property(&mut self, prop: &gcell!(Property<'a, 't>), tk: &mut Token<'t>) {
+        self.visit_expression(&prop.borrow(tk).key.clone(), tk);
+        self.visit_expression(&prop.borrow(tk).value.clone(), tk);
+    }
+
+    fn visit_block_statement(
+        &mut self,
+        block: &gcell!(BlockStatement<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_block_statement(block, tk);
+        if control == WalkControl::Continue {
+            self.walk_block_statement(block, tk);
+        }
+        self.exit_block_statement(block, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_block_statement(
+        &mut self,
+        block: &gcell!(BlockStatement<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_block_statement(&mut self, block: &gcell!(BlockStatement<'a, 't>), tk: &mut Token<'t>) {
+    }
+
+    fn walk_block_statement(&mut self, block: &gcell!(BlockStatement<'a, 't>), tk: &mut Token<'t>) {
+        // Statements may be inserted or removed while visiting, so re-read the list each time
+        let mut index = 0;
+        while let Some(stmt) = block.borrow(tk).body.get(index) {
+            let stmt = stmt.borrow(tk).clone();
+            self.visit_statement(&stmt, tk);
+            index = next_statement_index(&block.borrow(tk).body, &stmt, index, tk);
+        }
+    }
+
+    fn visit_if_statement(
+        &mut self,
+        if_stmt: &gcell!(IfStatement<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_if_statement(if_stmt, tk);
+        if control == WalkControl::Continue {
+            self.walk_if_statement(if_stmt, tk);
+        }
+        self.exit_if_statement(if_stmt, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_if_statement(
+        &mut self,
+        if_stmt: &gcell!(IfStatement<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_if_statement(&mut self, if_stmt: &gcell!(IfStatement<'a, 't>), tk: &mut Token<'t>) {}
+
+    fn walk_if_statement(&mut self, if_stmt: &gcell!(IfStatement<'a, 't>), tk: &mut Token<'t>) {
+        self.visit_expression(&if_stmt.borrow(tk).test.clone(), tk);
+        self.visit_statement(&if_stmt.borrow(tk).consequent.clone(), tk);
+        if let Some(alternate) = if_stmt.borrow(tk).alternate.clone() {
+            self.visit_statement(&alternate, tk);
+        }
+    }
+
+    fn visit_return_statement(
+        &mut self,
+        return_stmt: &gcell!(ReturnStatement<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_return_statement(return_stmt, tk);
+        if control == WalkControl::Continue {
+            self.walk_return_statement(return_stmt, tk);
+        }
+        self.exit_return_statement(return_stmt, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_return_statement(
+        &mut self,
+        return_stmt: &gcell!(ReturnStatement<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_return_statement(
+        &mut self,
+        return_stmt: &gcell!(ReturnStatement<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+    }
+
+    fn walk_return_statement(
+        &mut self,
+        return_stmt: &gcell!(ReturnStatement<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+        if let Some(argument) = return_stmt.borrow(tk).argument.clone() {
+            self.visit_expression(&argument, tk);
+        }
+    }
+
+    fn visit_variable_declaration(
+        &mut self,
+        var_decl: &gcell!(VariableDeclaration<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_variable_declaration(var_decl, tk);
+        if control == WalkControl::Continue {
+            self.walk_variable_declaration(var_decl, tk);
+        }
+        self.exit_variable_declaration(var_decl, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_variable_declaration(
+        &mut self,
+        var_decl: &gcell!(VariableDeclaration<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_variable_declaration(
+        &mut self,
+        var_decl: &gcell!(VariableDeclaration<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+    }
+
+    fn walk_variable_declaration(
+        &mut self,
+        var_decl: &gcell!(VariableDeclaration<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+        let len = var_decl.borrow(tk).declarations.len();
+        for index in 0..len {
+            let declarator = *var_decl.borrow(tk).declarations.as_slice()[index].borrow(tk);
+            self.visit_variable_declarator(declarator, tk);
+        }
+    }
+
+    fn visit_variable_declarator(
+        &mut self,
+        declarator: &gcell!(VariableDeclarator<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        let control = self.enter_variable_declarator(declarator, tk);
+        if control == WalkControl::Continue {
+            self.walk_variable_declarator(declarator, tk);
+        }
+        self.exit_variable_declarator(declarator, tk);
+        control
+    }
+
+    #[allow(unused_variables)]
+    fn enter_variable_declarator(
+        &mut self,
+        declarator: &gcell!(VariableDeclarator<'a, 't>),
+        tk: &mut Token<'t>,
+    ) -> WalkControl {
+        WalkControl::Continue
+    }
+
+    #[allow(unused_variables)]
+    fn exit_variable_declarator(
+        &mut self,
+        declarator: &gcell!(VariableDeclarator<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+    }
+
+    fn walk_variable_declarator(
+        &mut self,
+        declarator: &gcell!(VariableDeclarator<'a, 't>),
+        tk: &mut Token<'t>,
+    ) {
+        if let Some(init) = declarator.borrow(tk).init.clone() {
+            self.visit_expression(&init, tk);
+        }
+    }
+}
+
+/// Get index of the statement to visit after `stmt`, which was at `index` in `stmts`
+/// before it was visited.
+///
+/// Statements inserted before `stmt` while it was visited are skipped, and statements
+/// inserted after it will be visited. If `stmt` was removed, continue from `index`.
+fn next_statement_index<'a, 't>(
+    stmts: &[GCell<'t, Statement<'a, 't>>],
+    stmt: &Statement<'a, 't>,
+    index: usize,
+    tk: &Token<'t>,
+) -> usize {
+    // Fast path: nothing inserted or removed before `stmt`
+    if stmts
+        .get(index)
+        .is_some_and(|other| other.borrow(tk).is_same(stmt))
+    {
+        return index + 1;
+    }
+    match stmts
+        .iter()
+        .position(|other| other.borrow(tk).is_same(stmt))
+    {
+        Some(position) => position + 1,
+        None => index,
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{parents::validate_tree, print::Printer};
+
+    /// Records names of identifiers, in the order they're visited.
+    #[derive(Default)]
+    struct CollectNames {
+        names: Vec<String>,
+        skip_binary: bool,
+        reverse: bool,
+    }
+
+    impl<'a, 't> Traverse<'a, 't> for CollectNames {
+        fn reverse(&self) -> bool {
+            self.reverse
+        }
+
+        fn enter_binary_expression(
+            &mut self,
+            _bin_expr: &gcell!(BinaryExpression<'a, 't>),
+            _tk: &mut Token<'t>,
+        ) -> WalkControl {
+            if self.skip_binary {
+                WalkControl::Skip
+            } else {
+                WalkControl::Continue
+            }
+        }
+
+        fn exit_identifier_reference(
+            &mut self,
+            id: &gcell!(IdentifierReference<'a, 't>),
+            tk: &mut Token<'t>,
+        ) {
+            self.names.push(id.borrow(tk).name.to_string());
+        }
+    }
+
+    fn collect_names(source: &str, collector: &mut CollectNames) {
+        let alloc = Allocator::default();
+        let program = parse_str(source, &alloc).unwrap();
+        transform(collector, program);
+    }
+
+    #[test]
+    fn skip() {
+        let mut collector = CollectNames::default();
+        collect_names("a === b; -c;", &mut collector);
+        assert_eq!(collector.names, ["a", "b", "c"]);
+
+        let mut collector = CollectNames {
+            skip_binary: true,
+            ..CollectNames::default()
+        };
+        collect_names("a === b; -c;", &mut collector);
+        assert_eq!(collector.names, ["c"]);
+    }
+
+    /// Records names of identifiers, visiting only the left side of binary expressions.
+    #[derive(Default)]
+    struct SkipRight {
+        names: Vec<String>,
+    }
+
+    impl<'a, 't> Traverse<'a, 't> for SkipRight {
+        fn visit_binary_expression(
+            &mut self,
+            bin_expr: &gcell!(BinaryExpression<'a, 't>),
+            tk: &mut Token<'t>,
+        ) -> WalkControl {
+            let left = bin_expr.borrow(tk).left.clone();
+            self.visit_expression(&left, tk);
+            WalkControl::Skip
+        }
+
+        fn exit_identifier_reference(
+            &mut self,
+            id: &gcell!(IdentifierReference<'a, 't>),
+            tk: &mut Token<'t>,
+        ) {
+            self.names.push(id.borrow(tk).name.to_string());
+        }
+    }
+
+    #[test]
+    fn skip_right() {
+        let alloc = Allocator::default();
+        let program = parse_str("a === b; -c;", &alloc).unwrap();
+        let mut collector = SkipRight::default();
+        transform(&mut collector, program);
+        assert_eq!(collector.names, ["a", "c"]);
+    }
+
+    /// Swaps sides of binary expressions, without updating parent links.
+    struct SwapSides;
+
+    impl<'a, 't> Traverse<'a, 't> for SwapSides {
+        fn exit_binary_expression(
+            &mut self,
+            bin_expr: &gcell!(BinaryExpression<'a, 't>),
+            tk: &mut Token<'t>,
+        ) {
+            let bin_expr = bin_expr.borrow_mut(tk);
+            core::mem::swap(&mut bin_expr.left, &mut bin_expr.right);
+        }
+    }
+
+    #[test]
+    fn fixup_parents() {
+        let alloc = Allocator::default();
+        let program = parse_str("a === -b;", &alloc).unwrap();
+        transform_with(&mut SwapSides, program, TransformOptions::default());
+        assert!(validate_tree(program).is_err());
+
+        let program = parse_str("a === -b;", &alloc).unwrap();
+        let options = TransformOptions {
+            fixup_parents: true,
+        };
+        transform_with(&mut SwapSides, program, options);
+        assert!(validate_tree(program).is_ok());
+    }
+
+    #[test]
+    fn reverse() {
+        let mut collector = CollectNames {
+            reverse: true,
+            ..CollectNames::default()
+        };
+        collect_names("a === (b === c);", &mut collector);
+        assert_eq!(collector.names, ["c", "b", "a"]);
+    }
+
+    #[test]
+    fn parallel_transform_pool() {
+        let mut allocs = [Allocator::default(), Allocator::default()];
+        let [alloc1, alloc2] = &mut allocs;
+        let mut pool = ParallelTransformPool::new();
+        pool.parse("a === b;", alloc1).unwrap();
+        pool.parse("c === (d === e);", alloc2).unwrap();
+
+        let mut collectors = [CollectNames::default(), CollectNames::default()];
+        pool.transform(collectors.iter_mut().collect());
+        assert_eq!(collectors[0].names, ["a", "b"]);
+        assert_eq!(collectors[1].names, ["c", "d", "e"]);
+
+        pool.transform(vec![&mut SwapSides, &mut SwapSides]);
+        let printed = pool
+            .programs()
+            .map(|program| Printer::print(program))
+            .collect::<Vec<_>>();
+        assert_eq!(printed, ["b === a;", "e === d === c;"]);
+    }
 }
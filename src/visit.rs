@@ -1,89 +1,838 @@
 use oxc_allocator::Vec;
 
 use crate::ast::{
-    BinaryExpression, Expression, ExpressionStatement, IdentifierReference, Program, Statement,
-    StringLiteral, UnaryExpression,
+    ArrayExpression, AssignmentExpression, BinaryExpression, BindingIdentifier, BlockStatement,
+    BooleanLiteral, CallExpression, ConditionalExpression, Expression, ExpressionParent,
+    ExpressionStatement, IdentifierReference, IfStatement, LogicalExpression, MemberExpression,
+    NullLiteral, NumericLiteral, ObjectExpression, Program, Property, ReturnStatement, Statement,
+    StringLiteral, TemplateLiteral, UnaryExpression, VariableDeclaration, VariableDeclarator,
 };
 
+/// Visitor over the standard AST.
+///
+/// Each node type with children has a `visit_*` method which by default calls `walk_*`
+/// to visit the node's children, then `leave_*`.
+/// Override `visit_*` for pre-order processing (before children), or `leave_*` for post-order
+/// (after children). Leaf nodes (identifiers and literals) have no children, so they only have
+/// `visit_*` methods.
 #[allow(clippy::single_match)]
 pub trait Visit<'a> {
+    /// Return `true` to visit children of binary expressions right-to-left.
+    fn reverse(&self) -> bool {
+        false
+    }
+
+    /// Maximum depth to walk to. Return `Some` to stop descending into the tree once
+    /// the limit is reached. Nodes at the limit are still visited, but their children are not.
+    ///
+    /// Every statement and expression counts as one level, so in `-x;` the statement is at
+    /// depth 1, `-x` at depth 2, and `x` at depth 3. Other nodes (`Program`, object properties
+    /// and variable declarators) don't add a level - they're at the same depth as the node
+    /// containing them. So in `let x = -y;`, `-y` is at depth 2.
+    ///
+    /// Implementations returning `Some` must also implement `depth_mut`,
+    /// otherwise the limit is ignored (and debug builds panic).
+    fn max_depth(&self) -> Option<usize> {
+        None
+    }
+
+    /// Storage for current depth. Only used if `max_depth` returns `Some`.
+    fn depth_mut(&mut self) -> Option<&mut usize> {
+        None
+    }
+
+    /// Returns `true` if children of the current node should not be walked
+    /// because depth limit has been reached.
+    fn depth_limit_reached(&mut self) -> bool {
+        let Some(max_depth) = self.max_depth() else {
+            return false;
+        };
+        let depth = self.depth_mut();
+        debug_assert!(
+            depth.is_some(),
+            "`Visit` implementations with a `max_depth` must implement `depth_mut`"
+        );
+        depth.is_some_and(|depth| *depth >= max_depth)
+    }
+
     fn visit_program(&mut self, program: &Program<'a>) {
-        self.walk_program(program)
+        self.walk_program(program);
+        self.leave_program(program);
     }
 
     fn walk_program(&mut self, program: &Program<'a>) {
-        self.visit_statements(&program.body);
+        walk_program(self, program);
     }
 
+    #[allow(unused_variables)]
+    fn leave_program(&mut self, program: &Program<'a>) {}
+
     fn visit_statements(&mut self, stmts: &Vec<'a, Statement<'a>>) {
         self.walk_statements(stmts);
     }
 
     fn walk_statements(&mut self, stmts: &Vec<'a, Statement<'a>>) {
-        for stmt in stmts {
-            self.visit_statement(stmt);
-        }
+        walk_statements(self, stmts);
     }
 
     fn visit_statement(&mut self, stmt: &Statement<'a>) {
-        self.walk_statement(stmt)
+        self.walk_statement(stmt);
+        self.leave_statement(stmt);
     }
 
     fn walk_statement(&mut self, stmt: &Statement<'a>) {
-        match stmt {
-            Statement::ExpressionStatement(expr_stmt) => self.visit_expression_statement(expr_stmt), // _ => {} // No other variants at present
-        }
+        walk_statement(self, stmt);
     }
 
+    #[allow(unused_variables)]
+    fn leave_statement(&mut self, stmt: &Statement<'a>) {}
+
     fn visit_expression_statement(&mut self, expr_stmt: &ExpressionStatement<'a>) {
         self.walk_expression_statement(expr_stmt);
+        self.leave_expression_statement(expr_stmt);
     }
 
     fn walk_expression_statement(&mut self, expr_stmt: &ExpressionStatement<'a>) {
-        self.visit_expression(&expr_stmt.expression);
+        walk_expression_statement(self, expr_stmt);
     }
 
+    #[allow(unused_variables)]
+    fn leave_expression_statement(&mut self, expr_stmt: &ExpressionStatement<'a>) {}
+
     fn visit_expression(&mut self, expr: &Expression<'a>) {
         self.walk_expression(expr);
+        self.leave_expression(expr);
     }
 
+    /// Visit an expression, along with the parent it's being visited from.
+    ///
+    /// The `walk_*` functions call this for every child expression, with `parent` describing
+    /// the parent node and which of its fields holds `expr`. Override this for processing which
+    /// depends on an expression's context, rather than reading the expression's `parent` field,
+    /// which may be stale if the AST has been modified.
+    /// By default, `parent` is ignored and `visit_expression` is called.
+    #[allow(unused_variables)]
+    fn visit_expression_with_parent(
+        &mut self,
+        expr: &Expression<'a>,
+        parent: ExpressionParent<'a>,
+    ) {
+        self.visit_expression(expr);
+    }
+
+    /// Called for every expression before dispatching to the type-specific `visit_*` method.
+    /// Override this to run the same logic for all expression types.
+    #[allow(unused_variables)]
+    fn visit_any_expression(&mut self, expr: &Expression<'a>) {}
+
     fn walk_expression(&mut self, expr: &Expression<'a>) {
-        match expr {
-            Expression::Identifier(id) => {
-                self.visit_identifier_reference(id);
-            }
-            Expression::StringLiteral(str_lit) => {
-                self.visit_string_literal(str_lit);
-            }
-            Expression::BinaryExpression(bin_expr) => {
-                self.visit_binary_expression(bin_expr);
-            }
-            Expression::UnaryExpression(unary_expr) => {
-                self.visit_unary_expression(unary_expr);
-            }
-        }
+        walk_expression(self, expr);
     }
 
+    #[allow(unused_variables)]
+    fn leave_expression(&mut self, expr: &Expression<'a>) {}
+
     #[allow(unused_variables)]
     fn visit_identifier_reference(&mut self, id: &IdentifierReference<'a>) {}
 
+    #[allow(unused_variables)]
+    fn visit_binding_identifier(&mut self, id: &BindingIdentifier<'a>) {}
+
     #[allow(unused_variables)]
     fn visit_string_literal(&mut self, str_lit: &StringLiteral<'a>) {}
 
+    #[allow(unused_variables)]
+    fn visit_numeric_literal(&mut self, num_lit: &NumericLiteral<'a>) {}
+
+    #[allow(unused_variables)]
+    fn visit_boolean_literal(&mut self, bool_lit: &BooleanLiteral<'a>) {}
+
+    #[allow(unused_variables)]
+    fn visit_null_literal(&mut self, null_lit: &NullLiteral<'a>) {}
+
     fn visit_binary_expression(&mut self, bin_expr: &BinaryExpression<'a>) {
         self.walk_binary_expression(bin_expr);
+        self.leave_binary_expression(bin_expr);
     }
 
     fn walk_binary_expression(&mut self, bin_expr: &BinaryExpression<'a>) {
-        self.visit_expression(&bin_expr.left);
-        self.visit_expression(&bin_expr.right);
+        walk_binary_expression(self, bin_expr);
     }
 
+    #[allow(unused_variables)]
+    fn leave_binary_expression(&mut self, bin_expr: &BinaryExpression<'a>) {}
+
     fn visit_unary_expression(&mut self, unary_expr: &UnaryExpression<'a>) {
         self.walk_unary_expression(unary_expr);
+        self.leave_unary_expression(unary_expr);
     }
 
     fn walk_unary_expression(&mut self, unary_expr: &UnaryExpression<'a>) {
-        self.visit_expression(&unary_expr.argument);
+        walk_unary_expression(self, unary_expr);
+    }
+
+    #[allow(unused_variables)]
+    fn leave_unary_expression(&mut self, unary_expr: &UnaryExpression<'a>) {}
+
+    fn visit_call_expression(&mut self, call_expr: &CallExpression<'a>) {
+        self.walk_call_expression(call_expr);
+        self.leave_call_expression(call_expr);
+    }
+
+    fn walk_call_expression(&mut self, call_expr: &CallExpression<'a>) {
+        walk_call_expression(self, call_expr);
+    }
+
+    #[allow(unused_variables)]
+    fn leave_call_expression(&mut self, call_expr: &CallExpression<'a>) {}
+
+    fn visit_member_expression(&mut self, member_expr: &MemberExpression<'a>) {
+        self.walk_member_expression(member_expr);
+        self.leave_member_expression(member_expr);
+    }
+
+    fn walk_member_expression(&mut self, member_expr: &MemberExpression<'a>) {
+        walk_member_expression(self, member_expr);
+    }
+
+    #[allow(unused_variables)]
+    fn leave_member_expression(&mut self, member_expr: &MemberExpression<'a>) {}
+
+    fn visit_logical_expression(&mut self, logical_expr: &LogicalExpression<'a>) {
+        self.walk_logical_expression(logical_expr);
+        self.leave_logical_expression(logical_expr);
+    }
+
+    fn walk_logical_expression(&mut self, logical_expr: &LogicalExpression<'a>) {
+        walk_logical_expression(self, logical_expr);
+    }
+
+    #[allow(unused_variables)]
+    fn leave_logical_expression(&mut self, logical_expr: &LogicalExpression<'a>) {}
+
+    fn visit_conditional_expression(&mut self, cond_expr: &ConditionalExpression<'a>) {
+        self.walk_conditional_expression(cond_expr);
+        self.leave_conditional_expression(cond_expr);
+    }
+
+    fn walk_conditional_expression(&mut self, cond_expr: &ConditionalExpression<'a>) {
+        walk_conditional_expression(self, cond_expr);
+    }
+
+    #[allow(unused_variables)]
+    fn leave_conditional_expression(&mut self, cond_expr: &ConditionalExpression<'a>) {}
+
+    fn visit_assignment_expression(&mut self, assign_expr: &AssignmentExpression<'a>) {
+        self.walk_assignment_expression(assign_expr);
+        self.leave_assignment_expression(assign_expr);
+    }
+
+    fn walk_assignment_expression(&mut self, assign_expr: &AssignmentExpression<'a>) {
+        walk_assignment_expression(self, assign_expr);
+    }
+
+    #[allow(unused_variables)]
+    fn leave_assignment_expression(&mut self, assign_expr: &AssignmentExpression<'a>) {}
+
+    fn visit_template_literal(&mut self, template_lit: &TemplateLiteral<'a>) {
+        self.walk_template_literal(template_lit);
+        self.leave_template_literal(template_lit);
+    }
+
+    fn walk_template_literal(&mut self, template_lit: &TemplateLiteral<'a>) {
+        walk_template_literal(self, template_lit);
+    }
+
+    #[allow(unused_variables)]
+    fn leave_template_literal(&mut self, template_lit: &TemplateLiteral<'a>) {}
+
+    fn visit_array_expression(&mut self, array_expr: &ArrayExpression<'a>) {
+        self.walk_array_expression(array_expr);
+        self.leave_array_expression(array_expr);
+    }
+
+    fn walk_array_expression(&mut self, array_expr: &ArrayExpression<'a>) {
+        walk_array_expression(self, array_expr);
+    }
+
+    #[allow(unused_variables)]
+    fn leave_array_expression(&mut self, array_expr: &ArrayExpression<'a>) {}
+
+    fn visit_object_expression(&mut self, object_expr: &ObjectExpression<'a>) {
+        self.walk_object_expression(object_expr);
+        self.leave_object_expression(object_expr);
+    }
+
+    fn walk_object_expression(&mut self, object_expr: &ObjectExpression<'a>) {
+        walk_object_expression(self, object_expr);
+    }
+
+    #[allow(unused_variables)]
+    fn leave_object_expression(&mut self, object_expr: &ObjectExpression<'a>) {}
+
+    fn visit_property(&mut self, prop: &Property<'a>) {
+        self.walk_property(prop);
+        self.leave_property(prop);
+    }
+
+    fn walk_property(&mut self, prop: &Property<'a>) {
+        walk_property(self, prop);
+    }
+
+    #[allow(unused_variables)]
+    fn leave_property(&mut self, prop: &Property<'a>) {}
+
+    fn visit_block_statement(&mut self, block: &BlockStatement<'a>) {
+        self.walk_block_statement(block);
+        self.leave_block_statement(block);
+    }
+
+    fn walk_block_statement(&mut self, block: &BlockStatement<'a>) {
+        walk_block_statement(self, block);
+    }
+
+    #[allow(unused_variables)]
+    fn leave_block_statement(&mut self, block: &BlockStatement<'a>) {}
+
+    fn visit_if_statement(&mut self, if_stmt: &IfStatement<'a>) {
+        self.walk_if_statement(if_stmt);
+        self.leave_if_statement(if_stmt);
+    }
+
+    fn walk_if_statement(&mut self, if_stmt: &IfStatement<'a>) {
+        walk_if_statement(self, if_stmt);
+    }
+
+    #[allow(unused_variables)]
+    fn leave_if_statement(&mut self, if_stmt: &IfStatement<'a>) {}
+
+    fn visit_return_statement(&mut self, return_stmt: &ReturnStatement<'a>) {
+        self.walk_return_statement(return_stmt);
+        self.leave_return_statement(return_stmt);
+    }
+
+    fn walk_return_statement(&mut self, return_stmt: &ReturnStatement<'a>) {
+        walk_return_statement(self, return_stmt);
+    }
+
+    #[allow(unused_variables)]
+    fn leave_return_statement(&mut self, return_stmt: &ReturnStatement<'a>) {}
+
+    fn visit_variable_declaration(&mut self, var_decl: &VariableDeclaration<'a>) {
+        self.walk_variable_declaration(var_decl);
+        self.leave_variable_declaration(var_decl);
+    }
+
+    fn walk_variable_declaration(&mut self, var_decl: &VariableDeclaration<'a>) {
+        walk_variable_declaration(self, var_decl);
+    }
+
+    #[allow(unused_variables)]
+    fn leave_variable_declaration(&mut self, var_decl: &VariableDeclaration<'a>) {}
+
+    fn visit_variable_declarator(&mut self, declarator: &VariableDeclarator<'a>) {
+        self.walk_variable_declarator(declarator);
+        self.leave_variable_declarator(declarator);
+    }
+
+    fn walk_variable_declarator(&mut self, declarator: &VariableDeclarator<'a>) {
+        walk_variable_declarator(self, declarator);
+    }
+
+    #[allow(unused_variables)]
+    fn leave_variable_declarator(&mut self, declarator: &VariableDeclarator<'a>) {}
+}
+
+// Walk functions are free functions, so children of a node can be walked with a different
+// visitor from the one visiting the node. `Visit`'s `walk_*` methods delegate to these.
+
+pub fn walk_program<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, program: &Program<'a>) {
+    visitor.visit_statements(&program.body);
+}
+
+pub fn walk_statements<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, stmts: &Vec<'a, Statement<'a>>) {
+    for stmt in stmts {
+        visitor.visit_statement(stmt);
+    }
+}
+
+pub fn walk_statement<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, stmt: &Statement<'a>) {
+    if let Some(depth) = visitor.depth_mut() {
+        *depth += 1;
+    }
+    match stmt {
+        Statement::ExpressionStatement(expr_stmt) => visitor.visit_expression_statement(expr_stmt),
+        Statement::BlockStatement(block) => visitor.visit_block_statement(block),
+        Statement::IfStatement(if_stmt) => visitor.visit_if_statement(if_stmt),
+        Statement::ReturnStatement(return_stmt) => visitor.visit_return_statement(return_stmt),
+        Statement::VariableDeclaration(var_decl) => visitor.visit_variable_declaration(var_decl),
+    }
+    if let Some(depth) = visitor.depth_mut() {
+        *depth -= 1;
+    }
+}
+
+pub fn walk_expression_statement<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    expr_stmt: &ExpressionStatement<'a>,
+) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    visitor.visit_expression_with_parent(
+        &expr_stmt.expression,
+        ExpressionParent::ExpressionStatement(expr_stmt),
+    );
+}
+
+pub fn walk_expression<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, expr: &Expression<'a>) {
+    if let Some(depth) = visitor.depth_mut() {
+        *depth += 1;
+    }
+    visitor.visit_any_expression(expr);
+    match expr {
+        Expression::Identifier(id) => {
+            visitor.visit_identifier_reference(id);
+        }
+        Expression::StringLiteral(str_lit) => {
+            visitor.visit_string_literal(str_lit);
+        }
+        Expression::NumericLiteral(num_lit) => {
+            visitor.visit_numeric_literal(num_lit);
+        }
+        Expression::BooleanLiteral(bool_lit) => {
+            visitor.visit_boolean_literal(bool_lit);
+        }
+        Expression::NullLiteral(null_lit) => {
+            visitor.visit_null_literal(null_lit);
+        }
+        Expression::BinaryExpression(bin_expr) => {
+            visitor.visit_binary_expression(bin_expr);
+        }
+        Expression::UnaryExpression(unary_expr) => {
+            visitor.visit_unary_expression(unary_expr);
+        }
+        Expression::CallExpression(call_expr) => {
+            visitor.visit_call_expression(call_expr);
+        }
+        Expression::MemberExpression(member_expr) => {
+            visitor.visit_member_expression(member_expr);
+        }
+        Expression::LogicalExpression(logical_expr) => {
+            visitor.visit_logical_expression(logical_expr);
+        }
+        Expression::ConditionalExpression(cond_expr) => {
+            visitor.visit_conditional_expression(cond_expr);
+        }
+        Expression::AssignmentExpression(assign_expr) => {
+            visitor.visit_assignment_expression(assign_expr);
+        }
+        Expression::TemplateLiteral(template_lit) => {
+            visitor.visit_template_literal(template_lit);
+        }
+        Expression::ArrayExpression(array_expr) => {
+            visitor.visit_array_expression(array_expr);
+        }
+        Expression::ObjectExpression(object_expr) => {
+            visitor.visit_object_expression(object_expr);
+        }
+    }
+    if let Some(depth) = visitor.depth_mut() {
+        *depth -= 1;
+    }
+}
+
+pub fn walk_binary_expression<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    bin_expr: &BinaryExpression<'a>,
+) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    if visitor.reverse() {
+        visitor.visit_expression_with_parent(
+            &bin_expr.right,
+            ExpressionParent::BinaryExpressionRight(bin_expr),
+        );
+        visitor.visit_expression_with_parent(
+            &bin_expr.left,
+            ExpressionParent::BinaryExpressionLeft(bin_expr),
+        );
+    } else {
+        visitor.visit_expression_with_parent(
+            &bin_expr.left,
+            ExpressionParent::BinaryExpressionLeft(bin_expr),
+        );
+        visitor.visit_expression_with_parent(
+            &bin_expr.right,
+            ExpressionParent::BinaryExpressionRight(bin_expr),
+        );
+    }
+}
+
+pub fn walk_unary_expression<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    unary_expr: &UnaryExpression<'a>,
+) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    visitor.visit_expression_with_parent(
+        &unary_expr.argument,
+        ExpressionParent::UnaryExpression(unary_expr),
+    );
+}
+
+pub fn walk_call_expression<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    call_expr: &CallExpression<'a>,
+) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    visitor.visit_expression_with_parent(
+        &call_expr.callee,
+        ExpressionParent::CallExpressionCallee(call_expr),
+    );
+    for arg in &call_expr.arguments {
+        visitor
+            .visit_expression_with_parent(arg, ExpressionParent::CallExpressionArgument(call_expr));
+    }
+}
+
+pub fn walk_member_expression<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    member_expr: &MemberExpression<'a>,
+) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    visitor.visit_expression_with_parent(
+        &member_expr.object,
+        ExpressionParent::MemberExpressionObject(member_expr),
+    );
+    visitor.visit_expression_with_parent(
+        &member_expr.property,
+        ExpressionParent::MemberExpressionProperty(member_expr),
+    );
+}
+
+pub fn walk_logical_expression<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    logical_expr: &LogicalExpression<'a>,
+) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    visitor.visit_expression_with_parent(
+        &logical_expr.left,
+        ExpressionParent::LogicalExpressionLeft(logical_expr),
+    );
+    visitor.visit_expression_with_parent(
+        &logical_expr.right,
+        ExpressionParent::LogicalExpressionRight(logical_expr),
+    );
+}
+
+pub fn walk_conditional_expression<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    cond_expr: &ConditionalExpression<'a>,
+) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    visitor.visit_expression_with_parent(
+        &cond_expr.test,
+        ExpressionParent::ConditionalExpressionTest(cond_expr),
+    );
+    visitor.visit_expression_with_parent(
+        &cond_expr.consequent,
+        ExpressionParent::ConditionalExpressionConsequent(cond_expr),
+    );
+    visitor.visit_expression_with_parent(
+        &cond_expr.alternate,
+        ExpressionParent::ConditionalExpressionAlternate(cond_expr),
+    );
+}
+
+pub fn walk_assignment_expression<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    assign_expr: &AssignmentExpression<'a>,
+) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    visitor.visit_expression_with_parent(
+        &assign_expr.target,
+        ExpressionParent::AssignmentExpressionTarget(assign_expr),
+    );
+    visitor.visit_expression_with_parent(
+        &assign_expr.value,
+        ExpressionParent::AssignmentExpressionValue(assign_expr),
+    );
+}
+
+pub fn walk_template_literal<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    template_lit: &TemplateLiteral<'a>,
+) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    for expr in &template_lit.expressions {
+        visitor.visit_expression_with_parent(
+            expr,
+            ExpressionParent::TemplateLiteralExpression(template_lit),
+        );
+    }
+}
+
+pub fn walk_array_expression<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    array_expr: &ArrayExpression<'a>,
+) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    for element in array_expr.elements.iter().flatten() {
+        visitor.visit_expression_with_parent(
+            element,
+            ExpressionParent::ArrayExpressionElement(array_expr),
+        );
+    }
+}
+
+pub fn walk_object_expression<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    object_expr: &ObjectExpression<'a>,
+) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    for prop in &object_expr.properties {
+        visitor.visit_property(prop);
+    }
+}
+
+pub fn walk_property<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, prop: &Property<'a>) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    visitor.visit_expression_with_parent(&prop.key, ExpressionParent::PropertyKey(prop));
+    visitor.visit_expression_with_parent(&prop.value, ExpressionParent::PropertyValue(prop));
+}
+
+pub fn walk_block_statement<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    block: &BlockStatement<'a>,
+) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    visitor.visit_statements(&block.body);
+}
+
+pub fn walk_if_statement<'a, V: Visit<'a> + ?Sized>(visitor: &mut V, if_stmt: &IfStatement<'a>) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    visitor.visit_expression_with_parent(&if_stmt.test, ExpressionParent::IfStatementTest(if_stmt));
+    visitor.visit_statement(&if_stmt.consequent);
+    if let Some(alternate) = &if_stmt.alternate {
+        visitor.visit_statement(alternate);
+    }
+}
+
+pub fn walk_return_statement<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    return_stmt: &ReturnStatement<'a>,
+) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    if let Some(argument) = &return_stmt.argument {
+        visitor.visit_expression_with_parent(
+            argument,
+            ExpressionParent::ReturnStatementArgument(return_stmt),
+        );
+    }
+}
+
+pub fn walk_variable_declaration<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    var_decl: &VariableDeclaration<'a>,
+) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    for declarator in &var_decl.declarations {
+        visitor.visit_variable_declarator(declarator);
+    }
+}
+
+pub fn walk_variable_declarator<'a, V: Visit<'a> + ?Sized>(
+    visitor: &mut V,
+    declarator: &VariableDeclarator<'a>,
+) {
+    if visitor.depth_limit_reached() {
+        return;
+    }
+    visitor.visit_binding_identifier(&declarator.id);
+    if let Some(init) = &declarator.init {
+        visitor.visit_expression_with_parent(
+            init,
+            ExpressionParent::VariableDeclaratorInit(declarator),
+        );
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use oxc_allocator::Allocator;
+
+    use super::*;
+    use crate::parser::parse_str;
+
+    /// Records names of identifiers, in the order they're visited.
+    #[derive(Default)]
+    struct CollectNames {
+        names: std::vec::Vec<String>,
+        reverse: bool,
+    }
+
+    impl<'a> Visit<'a> for CollectNames {
+        fn reverse(&self) -> bool {
+            self.reverse
+        }
+
+        fn visit_identifier_reference(&mut self, id: &IdentifierReference<'a>) {
+            self.names.push(id.name.to_string());
+        }
+
+        fn visit_binding_identifier(&mut self, id: &BindingIdentifier<'a>) {
+            self.names.push(id.name.to_string());
+        }
+    }
+
+    fn collect_names(source: &str, collector: &mut CollectNames) {
+        let alloc = Allocator::default();
+        let program = parse_str(source, &alloc).unwrap();
+        collector.visit_program(program);
+    }
+
+    #[test]
+    fn array_holes() {
+        let mut collector = CollectNames::default();
+        collect_names("[a, b, c]; [, d, , e, ];", &mut collector);
+        assert_eq!(collector.names, ["a", "b", "c", "d", "e"]);
+    }
+
+    #[test]
+    fn binding_identifiers() {
+        let mut collector = CollectNames::default();
+        collect_names("let x = a; let y = x, z;", &mut collector);
+        assert_eq!(collector.names, ["x", "a", "y", "x", "z"]);
+    }
+
+    #[test]
+    fn reverse() {
+        let mut collector = CollectNames::default();
+        collect_names("a === (b === c);", &mut collector);
+        assert_eq!(collector.names, ["a", "b", "c"]);
+
+        let mut collector = CollectNames {
+            reverse: true,
+            ..CollectNames::default()
+        };
+        collect_names("a === (b === c);", &mut collector);
+        assert_eq!(collector.names, ["c", "b", "a"]);
+    }
+
+    /// Records pre-order `visit_*` and post-order `leave_*` calls.
+    #[derive(Default)]
+    struct RecordOrder {
+        events: std::vec::Vec<&'static str>,
+    }
+
+    impl<'a> Visit<'a> for RecordOrder {
+        fn visit_binary_expression(&mut self, bin_expr: &BinaryExpression<'a>) {
+            self.events.push("visit binary");
+            self.walk_binary_expression(bin_expr);
+            self.leave_binary_expression(bin_expr);
+        }
+
+        fn leave_binary_expression(&mut self, _bin_expr: &BinaryExpression<'a>) {
+            self.events.push("leave binary");
+        }
+
+        fn visit_unary_expression(&mut self, unary_expr: &UnaryExpression<'a>) {
+            self.events.push("visit unary");
+            self.walk_unary_expression(unary_expr);
+            self.leave_unary_expression(unary_expr);
+        }
+
+        fn leave_unary_expression(&mut self, _unary_expr: &UnaryExpression<'a>) {
+            self.events.push("leave unary");
+        }
+
+        fn visit_identifier_reference(&mut self, _id: &IdentifierReference<'a>) {
+            self.events.push("identifier");
+        }
+
+        fn visit_string_literal(&mut self, _str_lit: &StringLiteral<'a>) {
+            self.events.push("string");
+        }
+    }
+
+    #[test]
+    fn visit_leave_order() {
+        let alloc = Allocator::default();
+        let program = parse_str("typeof foo === 'object'", &alloc).unwrap();
+        let mut recorder = RecordOrder::default();
+        recorder.visit_program(program);
+        assert_eq!(
+            recorder.events,
+            [
+                "visit binary",
+                "visit unary",
+                "identifier",
+                "leave unary",
+                "string",
+                "leave binary",
+            ]
+        );
+    }
+
+    /// Records operators of unary expressions, down to depth 2.
+    #[derive(Default)]
+    struct CollectUnary {
+        operators: std::vec::Vec<&'static str>,
+        depth: usize,
+    }
+
+    impl<'a> Visit<'a> for CollectUnary {
+        fn max_depth(&self) -> Option<usize> {
+            Some(2)
+        }
+
+        fn depth_mut(&mut self) -> Option<&mut usize> {
+            Some(&mut self.depth)
+        }
+
+        fn visit_unary_expression(&mut self, unary_expr: &UnaryExpression<'a>) {
+            self.operators.push(unary_expr.operator.as_str());
+            self.walk_unary_expression(unary_expr);
+        }
+    }
+
+    #[test]
+    fn max_depth() {
+        let alloc = Allocator::default();
+        let program = parse_str("-!~x;", &alloc).unwrap();
+        let mut collector = CollectUnary::default();
+        collector.visit_program(program);
+        // Statement is depth 1, `-` depth 2. `-` is visited, but its children are not.
+        assert_eq!(collector.operators, ["-"]);
+        assert_eq!(collector.depth, 0);
     }
 }
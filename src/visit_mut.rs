@@ -0,0 +1,301 @@
+use crate::ast::{
+    ArrayExpression, AssignmentExpression, BinaryExpression, BindingIdentifier, BlockStatement,
+    BooleanLiteral, CallExpression, ConditionalExpression, Expression, ExpressionStatement,
+    IdentifierReference, IfStatement, LogicalExpression, MemberExpression, NullLiteral,
+    NumericLiteral, ObjectExpression, Program, Property, ReturnStatement, Statement, StringLiteral,
+    TemplateLiteral, UnaryExpression, VariableDeclaration, VariableDeclarator,
+};
+
+/// Action returned from `VisitMut::visit_expression`.
+pub enum VisitAction<'a> {
+    /// Leave expression as it is.
+    Keep,
+    /// Replace expression with a different one.
+    Replace(Expression<'a>),
+}
+
+/// Visitor over the standard AST with mutable access to nodes.
+///
+/// Unlike `Visit`, `visit_expression` returns a `VisitAction`. If it returns
+/// `VisitAction::Replace`, the caller writes the new expression into the slot in the parent node
+/// which held the old one.
+///
+/// The replacement is made with [`Expression::replace_with`], which describes how parent
+/// back-links are handled. The old node is discarded (it remains in the arena).
+pub trait VisitMut<'a> {
+    fn visit_program(&mut self, program: &mut Program<'a>) {
+        self.walk_program(program);
+    }
+
+    fn walk_program(&mut self, program: &mut Program<'a>) {
+        for stmt in program.body.iter_mut() {
+            self.visit_statement(stmt);
+        }
+    }
+
+    fn visit_statement(&mut self, stmt: &mut Statement<'a>) {
+        self.walk_statement(stmt);
+    }
+
+    fn walk_statement(&mut self, stmt: &mut Statement<'a>) {
+        match stmt {
+            Statement::ExpressionStatement(expr_stmt) => self.visit_expression_statement(expr_stmt),
+            Statement::BlockStatement(block) => self.visit_block_statement(block),
+            Statement::IfStatement(if_stmt) => self.visit_if_statement(if_stmt),
+            Statement::ReturnStatement(return_stmt) => self.visit_return_statement(return_stmt),
+            Statement::VariableDeclaration(var_decl) => self.visit_variable_declaration(var_decl),
+        }
+    }
+
+    fn visit_expression_statement(&mut self, expr_stmt: &mut ExpressionStatement<'a>) {
+        self.walk_expression_statement(expr_stmt);
+    }
+
+    fn walk_expression_statement(&mut self, expr_stmt: &mut ExpressionStatement<'a>) {
+        visit_expression_in(self, &mut expr_stmt.expression);
+    }
+
+    fn visit_block_statement(&mut self, block: &mut BlockStatement<'a>) {
+        self.walk_block_statement(block);
+    }
+
+    fn walk_block_statement(&mut self, block: &mut BlockStatement<'a>) {
+        for stmt in block.body.iter_mut() {
+            self.visit_statement(stmt);
+        }
+    }
+
+    fn visit_if_statement(&mut self, if_stmt: &mut IfStatement<'a>) {
+        self.walk_if_statement(if_stmt);
+    }
+
+    fn walk_if_statement(&mut self, if_stmt: &mut IfStatement<'a>) {
+        visit_expression_in(self, &mut if_stmt.test);
+        self.visit_statement(&mut if_stmt.consequent);
+        if let Some(alternate) = &mut if_stmt.alternate {
+            self.visit_statement(alternate);
+        }
+    }
+
+    fn visit_return_statement(&mut self, return_stmt: &mut ReturnStatement<'a>) {
+        self.walk_return_statement(return_stmt);
+    }
+
+    fn walk_return_statement(&mut self, return_stmt: &mut ReturnStatement<'a>) {
+        if let Some(argument) = &mut return_stmt.argument {
+            visit_expression_in(self, argument);
+        }
+    }
+
+    fn visit_variable_declaration(&mut self, var_decl: &mut VariableDeclaration<'a>) {
+        self.walk_variable_declaration(var_decl);
+    }
+
+    fn walk_variable_declaration(&mut self, var_decl: &mut VariableDeclaration<'a>) {
+        for declarator in var_decl.declarations.iter_mut() {
+            self.visit_variable_declarator(declarator);
+        }
+    }
+
+    fn visit_variable_declarator(&mut self, declarator: &mut VariableDeclarator<'a>) {
+        self.walk_variable_declarator(declarator);
+    }
+
+    fn walk_variable_declarator(&mut self, declarator: &mut VariableDeclarator<'a>) {
+        self.visit_binding_identifier(&mut declarator.id);
+        if let Some(init) = &mut declarator.init {
+            visit_expression_in(self, init);
+        }
+    }
+
+    /// Visit an expression. Return `VisitAction::Replace` to replace it with another expression.
+    fn visit_expression(&mut self, expr: &mut Expression<'a>) -> VisitAction<'a> {
+        self.walk_expression(expr);
+        VisitAction::Keep
+    }
+
+    fn walk_expression(&mut self, expr: &mut Expression<'a>) {
+        match expr {
+            Expression::Identifier(id) => self.visit_identifier_reference(id),
+            Expression::StringLiteral(str_lit) => self.visit_string_literal(str_lit),
+            Expression::NumericLiteral(num_lit) => self.visit_numeric_literal(num_lit),
+            Expression::BooleanLiteral(bool_lit) => self.visit_boolean_literal(bool_lit),
+            Expression::NullLiteral(null_lit) => self.visit_null_literal(null_lit),
+            Expression::BinaryExpression(bin_expr) => self.visit_binary_expression(bin_expr),
+            Expression::UnaryExpression(unary_expr) => self.visit_unary_expression(unary_expr),
+            Expression::CallExpression(call_expr) => self.visit_call_expression(call_expr),
+            Expression::MemberExpression(member_expr) => self.visit_member_expression(member_expr),
+            Expression::LogicalExpression(logical_expr) => {
+                self.visit_logical_expression(logical_expr)
+            }
+            Expression::ConditionalExpression(cond_expr) => {
+                self.visit_conditional_expression(cond_expr)
+            }
+            Expression::AssignmentExpression(assign_expr) => {
+                self.visit_assignment_expression(assign_expr)
+            }
+            Expression::TemplateLiteral(template_lit) => self.visit_template_literal(template_lit),
+            Expression::ArrayExpression(array_expr) => self.visit_array_expression(array_expr),
+            Expression::ObjectExpression(object_expr) => self.visit_object_expression(object_expr),
+        }
+    }
+
+    #[allow(unused_variables)]
+    fn visit_identifier_reference(&mut self, id: &mut IdentifierReference<'a>) {}
+
+    #[allow(unused_variables)]
+    fn visit_binding_identifier(&mut self, id: &mut BindingIdentifier<'a>) {}
+
+    #[allow(unused_variables)]
+    fn visit_string_literal(&mut self, str_lit: &mut StringLiteral<'a>) {}
+
+    #[allow(unused_variables)]
+    fn visit_numeric_literal(&mut self, num_lit: &mut NumericLiteral<'a>) {}
+
+    #[allow(unused_variables)]
+    fn visit_boolean_literal(&mut self, bool_lit: &mut BooleanLiteral<'a>) {}
+
+    #[allow(unused_variables)]
+    fn visit_null_literal(&mut self, null_lit: &mut NullLiteral<'a>) {}
+
+    fn visit_binary_expression(&mut self, bin_expr: &mut BinaryExpression<'a>) {
+        self.walk_binary_expression(bin_expr);
+    }
+
+    fn walk_binary_expression(&mut self, bin_expr: &mut BinaryExpression<'a>) {
+        visit_expression_in(self, &mut bin_expr.left);
+        visit_expression_in(self, &mut bin_expr.right);
+    }
+
+    fn visit_unary_expression(&mut self, unary_expr: &mut UnaryExpression<'a>) {
+        self.walk_unary_expression(unary_expr);
+    }
+
+    fn walk_unary_expression(&mut self, unary_expr: &mut UnaryExpression<'a>) {
+        visit_expression_in(self, &mut unary_expr.argument);
+    }
+
+    fn visit_call_expression(&mut self, call_expr: &mut CallExpression<'a>) {
+        self.walk_call_expression(call_expr);
+    }
+
+    fn walk_call_expression(&mut self, call_expr: &mut CallExpression<'a>) {
+        visit_expression_in(self, &mut call_expr.callee);
+        for arg in call_expr.arguments.iter_mut() {
+            visit_expression_in(self, arg);
+        }
+    }
+
+    fn visit_member_expression(&mut self, member_expr: &mut MemberExpression<'a>) {
+        self.walk_member_expression(member_expr);
+    }
+
+    fn walk_member_expression(&mut self, member_expr: &mut MemberExpression<'a>) {
+        visit_expression_in(self, &mut member_expr.object);
+        visit_expression_in(self, &mut member_expr.property);
+    }
+
+    fn visit_logical_expression(&mut self, logical_expr: &mut LogicalExpression<'a>) {
+        self.walk_logical_expression(logical_expr);
+    }
+
+    fn walk_logical_expression(&mut self, logical_expr: &mut LogicalExpression<'a>) {
+        visit_expression_in(self, &mut logical_expr.left);
+        visit_expression_in(self, &mut logical_expr.right);
+    }
+
+    fn visit_conditional_expression(&mut self, cond_expr: &mut ConditionalExpression<'a>) {
+        self.walk_conditional_expression(cond_expr);
+    }
+
+    fn walk_conditional_expression(&mut self, cond_expr: &mut ConditionalExpression<'a>) {
+        visit_expression_in(self, &mut cond_expr.test);
+        visit_expression_in(self, &mut cond_expr.consequent);
+        visit_expression_in(self, &mut cond_expr.alternate);
+    }
+
+    fn visit_assignment_expression(&mut self, assign_expr: &mut AssignmentExpression<'a>) {
+        self.walk_assignment_expression(assign_expr);
+    }
+
+    fn walk_assignment_expression(&mut self, assign_expr: &mut AssignmentExpression<'a>) {
+        visit_expression_in(self, &mut assign_expr.target);
+        visit_expression_in(self, &mut assign_expr.value);
+    }
+
+    fn visit_template_literal(&mut self, template_lit: &mut TemplateLiteral<'a>) {
+        self.walk_template_literal(template_lit);
+    }
+
+    fn walk_template_literal(&mut self, template_lit: &mut TemplateLiteral<'a>) {
+        for expr in template_lit.expressions.iter_mut() {
+            visit_expression_in(self, expr);
+        }
+    }
+
+    fn visit_array_expression(&mut self, array_expr: &mut ArrayExpression<'a>) {
+        self.walk_array_expression(array_expr);
+    }
+
+    fn walk_array_expression(&mut self, array_expr: &mut ArrayExpression<'a>) {
+        for element in array_expr.elements.iter_mut().flatten() {
+            visit_expression_in(self, element);
+        }
+    }
+
+    fn visit_object_expression(&mut self, object_expr: &mut ObjectExpression<'a>) {
+        self.walk_object_expression(object_expr);
+    }
+
+    fn walk_object_expression(&mut self, object_expr: &mut ObjectExpression<'a>) {
+        for prop in object_expr.properties.iter_mut() {
+            self.visit_property(prop);
+        }
+    }
+
+    fn visit_property(&mut self, prop: &mut Property<'a>) {
+        self.walk_property(prop);
+    }
+
+    fn walk_property(&mut self, prop: &mut Property<'a>) {
+        visit_expression_in(self, &mut prop.key);
+        visit_expression_in(self, &mut prop.value);
+    }
+}
+
+/// Visit the expression in `slot`, and write replacement into `slot` if visitor requests it.
+pub(crate) fn visit_expression_in<'a, V: VisitMut<'a> + ?Sized>(
+    visitor: &mut V,
+    slot: &mut Expression<'a>,
+) {
+    if let VisitAction::Replace(expr) = visitor.visit_expression(slot) {
+        slot.replace_with(expr);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use oxc_allocator::Allocator;
+
+    use super::*;
+    use crate::{parser::parse_str, print::Printer};
+
+    /// Adds a `_` prefix to names of bindings.
+    struct PrefixBindings<'a> {
+        alloc: &'a Allocator,
+    }
+
+    impl<'a> VisitMut<'a> for PrefixBindings<'a> {
+        fn visit_binding_identifier(&mut self, id: &mut BindingIdentifier<'a>) {
+            id.name = self.alloc.alloc_str(&format!("_{}", id.name));
+        }
+    }
+
+    #[test]
+    fn binding_identifiers() {
+        let alloc = Allocator::default();
+        let program = parse_str("let x = a; let y = x, z;", &alloc).unwrap();
+        PrefixBindings { alloc: &alloc }.visit_program(program);
+        assert_eq!(Printer::print(program), "let _x = a;let _y = x, _z;");
+    }
+}
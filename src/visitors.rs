@@ -0,0 +1,406 @@
+//! Reusable visitors for common tasks.
+
+use std::collections::HashSet;
+
+use crate::{
+    ast::{
+        ArrayExpression, AssignmentExpression, BinaryExpression, BindingIdentifier, BlockStatement,
+        BooleanLiteral, CallExpression, ConditionalExpression, Expression, ExpressionStatement,
+        IdentifierReference, IfStatement, LogicalExpression, MemberExpression, NullLiteral,
+        NumericLiteral, ObjectExpression, Program, Property, ReturnStatement, Span, Statement,
+        StringLiteral, TemplateLiteral, UnaryExpression, VariableDeclaration, VariableDeclarator,
+    },
+    visit_mut::{visit_expression_in, VisitMut},
+    Visit,
+};
+
+/// Visitor which collects spans of all identifier references whose name matches a predicate.
+///
+/// Property names in non-computed member expressions (`bar` in `foo.bar`) and object literal keys
+/// (`a` in `{ a: 1 }`) are not references to variables, so are skipped.
+pub struct IdentCollector<F: Fn(&str) -> bool> {
+    predicate: F,
+    spans: Vec<Span>,
+}
+
+impl<F: Fn(&str) -> bool> IdentCollector<F> {
+    pub fn new(predicate: F) -> Self {
+        Self {
+            predicate,
+            spans: vec![],
+        }
+    }
+
+    /// Collect spans of matching identifiers in `program`, in source order.
+    pub fn collect(predicate: F, program: &Program<'_>) -> Vec<Span> {
+        let mut collector = Self::new(predicate);
+        collector.visit_program(program);
+        collector.spans
+    }
+
+    /// Get spans of matching identifiers collected so far.
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+}
+
+impl<'a, F: Fn(&str) -> bool> Visit<'a> for IdentCollector<F> {
+    fn visit_identifier_reference(&mut self, id: &IdentifierReference<'a>) {
+        if (self.predicate)(id.name) {
+            self.spans.push(id.span);
+        }
+    }
+
+    fn visit_member_expression(&mut self, member_expr: &MemberExpression<'a>) {
+        self.visit_expression(&member_expr.object);
+        if member_expr.computed {
+            self.visit_expression(&member_expr.property);
+        }
+    }
+
+    fn visit_property(&mut self, prop: &Property<'a>) {
+        if prop.computed {
+            self.visit_expression(&prop.key);
+        }
+        self.visit_expression(&prop.value);
+    }
+}
+
+/// Visitor which renames all identifier references whose name matches a predicate.
+///
+/// Only references are renamed, not bindings (e.g. `x` in `let x`), and not property names
+/// in non-computed member expressions (`bar` in `foo.bar`) or object literal keys
+/// (`a` in `{ a: 1 }`).
+pub struct IdentRenamer<'a, F: Fn(&str) -> bool> {
+    predicate: F,
+    replacement: &'a str,
+}
+
+impl<'a, F: Fn(&str) -> bool> IdentRenamer<'a, F> {
+    pub fn new(predicate: F, replacement: &'a str) -> Self {
+        Self {
+            predicate,
+            replacement,
+        }
+    }
+
+    /// Rename matching identifiers in `program` to `replacement`.
+    pub fn rename(predicate: F, replacement: &'a str, program: &mut Program<'a>) {
+        Self::new(predicate, replacement).visit_program(program);
+    }
+}
+
+impl<'a, F: Fn(&str) -> bool> VisitMut<'a> for IdentRenamer<'a, F> {
+    fn visit_identifier_reference(&mut self, id: &mut IdentifierReference<'a>) {
+        if (self.predicate)(id.name) {
+            id.name = self.replacement;
+        }
+    }
+
+    fn visit_member_expression(&mut self, member_expr: &mut MemberExpression<'a>) {
+        visit_expression_in(self, &mut member_expr.object);
+        if member_expr.computed {
+            visit_expression_in(self, &mut member_expr.property);
+        }
+    }
+
+    fn visit_property(&mut self, prop: &mut Property<'a>) {
+        if prop.computed {
+            visit_expression_in(self, &mut prop.key);
+        }
+        visit_expression_in(self, &mut prop.value);
+    }
+}
+
+/// Visitor which finds the first expression matching a predicate, and stops traversal
+/// once it is found.
+///
+/// Expressions are tested in pre-order (parents before their children).
+pub struct ExpressionFinder<F: Fn(&Expression<'_>) -> bool> {
+    predicate: F,
+    found: Option<Span>,
+}
+
+impl<F: Fn(&Expression<'_>) -> bool> ExpressionFinder<F> {
+    pub fn new(predicate: F) -> Self {
+        Self {
+            predicate,
+            found: None,
+        }
+    }
+
+    /// Find first expression in `program` matching predicate, and return its span.
+    pub fn find(predicate: F, program: &Program<'_>) -> Option<Span> {
+        let mut finder = Self::new(predicate);
+        finder.visit_program(program);
+        finder.found
+    }
+
+    /// Get span of matching expression, if one has been found.
+    pub fn found(&self) -> Option<Span> {
+        self.found
+    }
+}
+
+impl<'a, F: Fn(&Expression<'_>) -> bool> Visit<'a> for ExpressionFinder<F> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>) {
+        if self.found.is_none() {
+            self.walk_statement(stmt);
+        }
+    }
+
+    fn visit_expression(&mut self, expr: &Expression<'a>) {
+        if self.found.is_some() {
+            return;
+        }
+        if (self.predicate)(expr) {
+            self.found = Some(expr.span());
+        } else {
+            self.walk_expression(expr);
+        }
+    }
+}
+
+/// Visitor which collects names of free identifiers referenced in an AST, deduplicated,
+/// in the order they are first seen.
+///
+/// Names bound by a variable declaration (e.g. `x` in `let x`) are not free, so are excluded.
+/// There is no scope analysis yet, so a binding anywhere in the AST excludes the name everywhere,
+/// e.g. `x` is not considered free in `{ let x; } x;`. Property names in non-computed member
+/// expressions (`bar` in `foo.bar`) and object literal keys (`a` in `{ a: 1 }`)
+/// are not references, so are skipped.
+pub struct FreeIdentifiers<'a> {
+    names: Vec<&'a str>,
+    seen: HashSet<&'a str>,
+    bound: HashSet<&'a str>,
+}
+
+impl<'a> FreeIdentifiers<'a> {
+    pub fn new() -> Self {
+        Self {
+            names: vec![],
+            seen: HashSet::new(),
+            bound: HashSet::new(),
+        }
+    }
+
+    /// Collect names of free identifiers in `program`.
+    pub fn collect(program: &Program<'a>) -> Vec<&'a str> {
+        let mut collector = Self::new();
+        collector.visit_program(program);
+        collector.names()
+    }
+
+    /// Get names of free identifiers collected so far.
+    pub fn names(&self) -> Vec<&'a str> {
+        self.names
+            .iter()
+            .copied()
+            .filter(|name| !self.bound.contains(name))
+            .collect()
+    }
+}
+
+impl<'a> Default for FreeIdentifiers<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> Visit<'a> for FreeIdentifiers<'a> {
+    fn visit_identifier_reference(&mut self, id: &IdentifierReference<'a>) {
+        if self.seen.insert(id.name) {
+            self.names.push(id.name);
+        }
+    }
+
+    fn visit_binding_identifier(&mut self, id: &BindingIdentifier<'a>) {
+        self.bound.insert(id.name);
+    }
+
+    fn visit_member_expression(&mut self, member_expr: &MemberExpression<'a>) {
+        self.visit_expression(&member_expr.object);
+        if member_expr.computed {
+            self.visit_expression(&member_expr.property);
+        }
+    }
+
+    fn visit_property(&mut self, prop: &Property<'a>) {
+        if prop.computed {
+            self.visit_expression(&prop.key);
+        }
+        self.visit_expression(&prop.value);
+    }
+}
+
+/// Visitor which runs several visitors in a single pass over the AST.
+///
+/// `Fanout` wraps a tuple of up to 4 visitors, e.g. `Fanout((a, b))`. After visiting,
+/// the visitors can be retrieved from `fanout.0`.
+///
+/// The fan-out drives the walk itself, so inner visitors' overrides of `visit_*` methods for
+/// nodes which have children are not called (calling them would walk the subtree once per
+/// visitor). Only these methods are forwarded to inner visitors, in tuple order:
+/// * `visit_*` for leaf nodes (identifiers and literals).
+/// * `visit_any_expression`, for pre-order processing of expressions.
+/// * All `leave_*` methods, for post-order processing.
+///
+/// Inner visitors' `reverse` and `max_depth` are ignored.
+pub struct Fanout<T>(pub T);
+
+macro_rules! fanout_forward {
+    ($indices:tt $($method:ident($param:ident: $ty:ident);)+) => {
+        $(fanout_forward!(@method $indices $method($param: $ty));)+
+    };
+    (@method [$($index:tt),+] $method:ident($param:ident: $ty:ident)) => {
+        fn $method(&mut self, $param: &$ty<'a>) {
+            $(self.0.$index.$method($param);)+
+        }
+    };
+}
+
+macro_rules! impl_fanout {
+    ($($visitor:ident $index:tt),+) => {
+        impl<'a, $($visitor: Visit<'a>),+> Visit<'a> for Fanout<($($visitor,)+)> {
+            fanout_forward!(
+                [$($index),+]
+                leave_program(program: Program);
+                leave_statement(stmt: Statement);
+                leave_expression_statement(expr_stmt: ExpressionStatement);
+                leave_expression(expr: Expression);
+                visit_any_expression(expr: Expression);
+                visit_identifier_reference(id: IdentifierReference);
+                visit_binding_identifier(id: BindingIdentifier);
+                visit_string_literal(str_lit: StringLiteral);
+                visit_numeric_literal(num_lit: NumericLiteral);
+                visit_boolean_literal(bool_lit: BooleanLiteral);
+                visit_null_literal(null_lit: NullLiteral);
+                leave_binary_expression(bin_expr: BinaryExpression);
+                leave_unary_expression(unary_expr: UnaryExpression);
+                leave_call_expression(call_expr: CallExpression);
+                leave_member_expression(member_expr: MemberExpression);
+                leave_logical_expression(logical_expr: LogicalExpression);
+                leave_conditional_expression(cond_expr: ConditionalExpression);
+                leave_assignment_expression(assign_expr: AssignmentExpression);
+                leave_template_literal(template_lit: TemplateLiteral);
+                leave_array_expression(array_expr: ArrayExpression);
+                leave_object_expression(object_expr: ObjectExpression);
+                leave_property(prop: Property);
+                leave_block_statement(block: BlockStatement);
+                leave_if_statement(if_stmt: IfStatement);
+                leave_return_statement(return_stmt: ReturnStatement);
+                leave_variable_declaration(var_decl: VariableDeclaration);
+                leave_variable_declarator(declarator: VariableDeclarator);
+            );
+        }
+    };
+}
+
+impl_fanout!(A 0);
+impl_fanout!(A 0, B 1);
+impl_fanout!(A 0, B 1, C 2);
+impl_fanout!(A 0, B 1, C 2, D 3);
+
+#[cfg(test)]
+mod tests {
+    use oxc_allocator::Allocator;
+
+    use super::*;
+    use crate::{ast::UnaryOperator, parser::parse_str, print::Printer};
+
+    #[test]
+    fn ident_collector() {
+        let alloc = Allocator::default();
+        let program = parse_str("foo.foo; foo[foo]; ({ foo: foo }); bar;", &alloc).unwrap();
+        let spans = IdentCollector::collect(|name| name == "foo", program);
+        let starts = spans.iter().map(|span| span.start).collect::<Vec<_>>();
+        assert_eq!(starts, [0, 9, 13, 27]);
+    }
+
+    #[test]
+    fn expression_finder() {
+        let alloc = Allocator::default();
+        let program = parse_str("a; f(-b, typeof c, typeof d);", &alloc).unwrap();
+        let is_typeof = |expr: &Expression<'_>| matches!(expr, Expression::UnaryExpression(unary_expr) if unary_expr.operator == UnaryOperator::Typeof);
+        // First match in pre-order
+        let span = ExpressionFinder::find(is_typeof, program).unwrap();
+        assert_eq!((span.start, span.end), (9, 17));
+
+        let program = parse_str("a; f(-b);", &alloc).unwrap();
+        assert_eq!(ExpressionFinder::find(is_typeof, program), None);
+    }
+
+    #[test]
+    fn free_identifiers() {
+        let alloc = Allocator::default();
+        let program = parse_str("typeof foo === 'object'", &alloc).unwrap();
+        assert_eq!(FreeIdentifiers::collect(program), ["foo"]);
+
+        let program = parse_str("let x = a; f(x, b.c, a); { let y = b; }", &alloc).unwrap();
+        assert_eq!(FreeIdentifiers::collect(program), ["a", "f", "b"]);
+    }
+
+    #[test]
+    fn ident_renamer() {
+        let alloc = Allocator::default();
+        let program = parse_str("foo.foo; foo[foo]; ({ foo: foo }); bar;", &alloc).unwrap();
+        IdentRenamer::rename(|name| name == "foo", "qux", program);
+        assert_eq!(
+            Printer::print(program),
+            "qux.foo;qux[qux];({ foo: qux });bar;"
+        );
+    }
+
+    /// Counts expressions (pre-order) and statements (post-order).
+    #[derive(Default)]
+    struct Counter {
+        expressions: usize,
+        statements: usize,
+    }
+
+    impl<'a> Visit<'a> for Counter {
+        fn visit_any_expression(&mut self, _expr: &Expression<'a>) {
+            self.expressions += 1;
+        }
+
+        fn leave_statement(&mut self, _stmt: &Statement<'a>) {
+            self.statements += 1;
+        }
+    }
+
+    #[test]
+    fn fanout() {
+        let alloc = Allocator::default();
+        let program = parse_str("typeof foo === 'object'; if (a) { b(c); }", &alloc).unwrap();
+        let mut fanout = Fanout((Counter::default(), Counter::default()));
+        fanout.visit_program(program);
+        let (first, second) = fanout.0;
+        // `typeof foo === 'object'`, `typeof foo`, `foo`, `'object'`, `a`, `b(c)`, `b`, `c`
+        assert_eq!(first.expressions, 8);
+        // Expression statement, if statement, block statement, `b(c);`
+        assert_eq!(first.statements, 4);
+        assert_eq!(second.expressions, first.expressions);
+        assert_eq!(second.statements, first.statements);
+    }
+
+    /// Records names of bindings.
+    #[derive(Default)]
+    struct CollectBindings {
+        names: Vec<String>,
+    }
+
+    impl<'a> Visit<'a> for CollectBindings {
+        fn visit_binding_identifier(&mut self, id: &BindingIdentifier<'a>) {
+            self.names.push(id.name.to_string());
+        }
+    }
+
+    #[test]
+    fn fanout_binding_identifiers() {
+        let alloc = Allocator::default();
+        let program = parse_str("let x = a, y; if (a) { let z = x; }", &alloc).unwrap();
+        let mut fanout = Fanout((Counter::default(), CollectBindings::default()));
+        fanout.visit_program(program);
+        assert_eq!(fanout.0 .1.names, ["x", "y", "z"]);
+    }
+}
@@ -0,0 +1,43 @@
+//! Entry point for using the crate from JS, via `wasm-bindgen`.
+
+use oxc_allocator::Allocator;
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::{parser::parse_str, print::Printer, transforms::TransformTypeof, traverse::transform};
+
+/// Parse `src`, run transforms on it, and print the result.
+///
+/// If `src` fails to parse, returns a JSON string `{"error": "<message>"}` instead.
+#[wasm_bindgen]
+pub fn transform_source(src: &str) -> String {
+    let alloc = Allocator::default();
+    let program = match parse_str(src, &alloc) {
+        Ok(program) => program,
+        Err(err) => return serde_json::json!({ "error": err.to_string() }).to_string(),
+    };
+    transform(&mut TransformTypeof, program);
+    Printer::print(program)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn transform() {
+        assert_eq!(
+            transform_source("typeof foo === 'object';"),
+            "'object' === typeof foo;"
+        );
+    }
+
+    #[test]
+    fn parse_error() {
+        let output = transform_source("let = 1;");
+        let value: serde_json::Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({ "error": "Expected variable name at offset 4" })
+        );
+    }
+}
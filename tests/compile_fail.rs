@@ -0,0 +1,13 @@
+//! Check that the brand on `Token` prevents misuse of the AST, by asserting that code which
+//! would break the rules fails to compile, with the expected errors.
+//!
+//! Expected compiler output is in `tests/ui/*.stderr`. To update it after a change to the
+//! compiler's messages, run `TRYBUILD=overwrite cargo test --test compile_fail`.
+
+#![cfg(feature = "std")]
+
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,120 @@
+//! Property tests over randomly generated expressions.
+
+#![cfg(feature = "std")]
+
+use ast_cell_test::{
+    ast::Program,
+    compare::stmt_eq,
+    parser::parse_str,
+    print::{Printer, PrinterOptions},
+    transforms::TransformTypeof,
+    traverse::transform,
+};
+use oxc_allocator::Allocator;
+use proptest::prelude::*;
+
+const UNARY_OPERATORS: &[&str] = &["-", "+", "!", "~", "typeof ", "void "];
+
+const BINARY_OPERATORS: &[&str] = &[
+    "==",
+    "!=",
+    "===",
+    "!==",
+    "<",
+    "<=",
+    ">",
+    ">=",
+    "<<",
+    ">>",
+    ">>>",
+    "+",
+    "-",
+    "*",
+    "/",
+    "%",
+    "**",
+    "|",
+    "^",
+    "&",
+    "in",
+    "instanceof",
+    "&&",
+    "||",
+    "??",
+];
+
+/// Source text of an expression. Every subexpression is wrapped in parentheses, so the source
+/// is always valid, whatever the precedence of the operators involved.
+fn expression() -> impl Strategy<Value = String> {
+    let leaf = prop_oneof![
+        prop::sample::select(vec!["a", "b", "foo"]).prop_map(str::to_string),
+        (0.0..1e30f64).prop_map(|value| value.to_string()),
+        (0u32..1000).prop_map(|value| value.to_string()),
+        "[a-z'\"]{0,3}".prop_map(|value| format!("{value:?}")),
+        prop::sample::select(vec!["true", "false", "null", "typeof x", "'object'"])
+            .prop_map(str::to_string),
+    ];
+    leaf.prop_recursive(6, 64, 3, |inner| {
+        prop_oneof![
+            (prop::sample::select(UNARY_OPERATORS), inner.clone())
+                .prop_map(|(op, arg)| format!("{op}({arg})")),
+            (
+                inner.clone(),
+                prop::sample::select(BINARY_OPERATORS),
+                inner.clone()
+            )
+                .prop_map(|(left, op, right)| format!("({left}) {op} ({right})")),
+            (inner.clone(), inner.clone(), inner.clone())
+                .prop_map(|(test, cons, alt)| format!("({test}) ? ({cons}) : ({alt})")),
+            inner.clone().prop_map(|value| format!("a = ({value})")),
+            inner.clone().prop_map(|object| format!("({object}).x")),
+            (inner.clone(), inner.clone()).prop_map(|(object, prop)| format!("({object})[{prop}]")),
+            prop::collection::vec(inner.clone(), 0..3)
+                .prop_map(|args| format!("f({})", args.join(", "))),
+            prop::collection::vec(inner.clone(), 0..3)
+                .prop_map(|elements| format!("[{}]", elements.join(", "))),
+            inner.prop_map(|value| format!("({{ a: {value} }})")),
+        ]
+    })
+}
+
+fn parse<'a>(source: &'a str, alloc: &'a Allocator) -> &'a mut Program<'a> {
+    parse_str(source, alloc).unwrap_or_else(|err| panic!("Failed to parse `{source}`: {err}"))
+}
+
+/// Check each statement in `a` has the same structure as the statement in `b`.
+fn programs_eq(a: &Program<'_>, b: &Program<'_>) -> bool {
+    a.body.len() == b.body.len() && a.body.iter().zip(b.body.iter()).all(|(a, b)| stmt_eq(a, b))
+}
+
+proptest! {
+    /// Printed output parses back to an AST with the same structure as the original,
+    /// which prints the same.
+    #[test]
+    fn print_parse_print_is_stable(expr in expression()) {
+        let source = format!("{expr};");
+        let alloc = Allocator::default();
+        let program = parse(&source, &alloc);
+        let minify = PrinterOptions { minify: true, ..PrinterOptions::default() };
+        for options in [PrinterOptions::default(), minify] {
+            let printed = Printer::print_with(program, options);
+            let reparsed = parse(&printed, &alloc);
+            prop_assert!(programs_eq(program, reparsed), "`{}` printed as `{}`", source, printed);
+            prop_assert_eq!(Printer::print_with(reparsed, options), printed);
+        }
+    }
+
+    /// Running `TransformTypeof` more than once gives the same result as running it once.
+    #[test]
+    fn transform_typeof_is_idempotent(expr in expression(), runs in 1usize..=5) {
+        let source = format!("{expr};");
+        let alloc = Allocator::default();
+        let program = parse_str(&source, &alloc).unwrap();
+        transform(&mut TransformTypeof, program);
+        let once = Printer::print(program);
+        for _ in 1..runs {
+            transform(&mut TransformTypeof, program);
+        }
+        prop_assert_eq!(Printer::print(program), once);
+    }
+}
@@ -0,0 +1,21 @@
+// A transformer cannot store the token it is given, to use it after `transform` returns.
+
+use ast_cell_test::{
+    ast::traversable::IdentifierReference,
+    cell::{GCell, Token},
+    traverse::Traverse,
+};
+
+struct Escape<'b, 't>(Option<&'b mut Token<'t>>);
+
+impl<'a, 'b, 't> Traverse<'a, 't> for Escape<'b, 't> {
+    fn exit_identifier_reference(
+        &mut self,
+        _id: &GCell<'t, IdentifierReference<'a, 't>>,
+        tk: &mut Token<'t>,
+    ) {
+        self.0 = Some(tk);
+    }
+}
+
+fn main() {}
@@ -0,0 +1,22 @@
+// A transformer cannot keep a borrow of a node obtained with the token, as the borrow is only
+// valid while the token is borrowed.
+
+use ast_cell_test::{
+    ast::traversable::IdentifierReference,
+    cell::{GCell, Token},
+    traverse::Traverse,
+};
+
+struct Keep<'a, 'b, 't>(Option<&'b IdentifierReference<'a, 't>>);
+
+impl<'a, 'b, 't> Traverse<'a, 't> for Keep<'a, 'b, 't> {
+    fn exit_identifier_reference(
+        &mut self,
+        id: &GCell<'t, IdentifierReference<'a, 't>>,
+        tk: &mut Token<'t>,
+    ) {
+        self.0 = Some(id.borrow(tk));
+    }
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+// Each thread run by `ParallelTransformPool` gets a token with a brand of its own, so the
+// transformers must implement `Traverse` for any brand. To hold nodes from one thread's AST, and
+// pass them to another thread, a transformer would have to name that AST's brand, so is rejected.
+
+use std::marker::PhantomData;
+
+use ast_cell_test::{
+    cell::Token,
+    traverse::{ParallelTransformPool, Traverse},
+};
+use oxc_allocator::Allocator;
+
+struct Share<'t>(PhantomData<Token<'t>>);
+
+impl<'a, 't> Traverse<'a, 't> for Share<'t> {}
+
+fn main() {
+    let mut alloc1 = Allocator::default();
+    let mut alloc2 = Allocator::default();
+    let mut pool = ParallelTransformPool::new();
+    pool.parse("a;", &mut alloc1).unwrap();
+    pool.parse("b;", &mut alloc2).unwrap();
+
+    pool.transform(vec![&mut Share(PhantomData), &mut Share(PhantomData)]);
+}
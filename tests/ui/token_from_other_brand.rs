@@ -0,0 +1,17 @@
+// `transform` picks a fresh brand for the token it creates, so a transformer which only
+// implements `Traverse` for one particular brand cannot be used.
+
+use std::marker::PhantomData;
+
+use ast_cell_test::{cell::Token, parser::parse_str, traverse::{transform, Traverse}};
+use oxc_allocator::Allocator;
+
+struct Branded<'t>(PhantomData<Token<'t>>);
+
+impl<'a, 't> Traverse<'a, 't> for Branded<'t> {}
+
+fn main() {
+    let alloc = Allocator::default();
+    let program = parse_str("x;", &alloc).unwrap();
+    transform(&mut Branded(PhantomData), program);
+}